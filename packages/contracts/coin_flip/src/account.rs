@@ -2,7 +2,7 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{collections::UnorderedMap, env, AccountId};
 
 use crate::errors::*;
-use crate::StorageKey;
+use crate::{GameId, HouseWithdrawal, Streak, StorageKey};
 
 // min deposit for storage is 0.25 NEAR
 pub const MIN_STORAGE_BALANCE: u128 = 250_000_000_000_000_000_000_000;
@@ -16,6 +16,15 @@ pub struct Account {
 
     // game balances
     pub balances: UnorderedMap<AccountId, u128>,
+    // winnings held to be pressed or collected, one streak per game
+    pub streaks: UnorderedMap<GameId, Streak>,
+    // house shares held in the bankroll of each game
+    pub house_shares: UnorderedMap<GameId, u128>,
+    // liquidity withdrawals queued in each game, oldest first
+    pub house_withdrawals: UnorderedMap<GameId, Vec<HouseWithdrawal>>,
+    // commit-reveal bets and pvp challenges of the account still waiting to be settled
+    pub pending_bets: u32,
+    pub open_challenges: u32,
 }
 
 impl Account {
@@ -27,6 +36,17 @@ impl Account {
             balances: UnorderedMap::new(StorageKey::AccountBalances {
                 account_id: account_id.clone(),
             }),
+            streaks: UnorderedMap::new(StorageKey::AccountStreaks {
+                account_id: account_id.clone(),
+            }),
+            house_shares: UnorderedMap::new(StorageKey::AccountHouseShares {
+                account_id: account_id.clone(),
+            }),
+            house_withdrawals: UnorderedMap::new(StorageKey::AccountHouseWithdrawals {
+                account_id: account_id.clone(),
+            }),
+            pending_bets: 0,
+            open_challenges: 0,
         }
     }
 }
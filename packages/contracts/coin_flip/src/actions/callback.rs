@@ -1,4 +1,8 @@
 use crate::*;
+use crate::events::*;
+
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::PromiseResult;
 
 // pub trait Callbacks {
 //     fn owner_transfer_callback(token_contract: AccountId, amount: U128);
@@ -9,12 +13,49 @@ use crate::*;
 
 #[near_bindgen]
 impl Contract {
+    #[private]
+    pub fn token_metadata_callback(&mut self, token_contract: AccountId) {
+        let metadata = match env::promise_result(0) {
+            PromiseResult::Successful(data) => {
+                serde_json::from_slice::<FungibleTokenMetadata>(&data).expect(ERR_503)
+            }
+            _ => panic!("{}", ERR_503),
+        };
+
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        let token = TokenInfo {
+            symbol: metadata.symbol,
+            decimals: metadata.decimals,
+            icon: metadata.icon,
+        };
+        self.tokens.insert(&token_contract, &token);
+        ContractEvent::TokenRegistered(TokenData {
+            token_contract: &token_contract,
+            token: Some(&token),
+        })
+        .emit();
+
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+    }
+
     #[private]
     pub fn owner_transfer_callback(&mut self, token_contract: AccountId, amount: U128) {
         match is_promise_success() {
             true => {}
             false => {
                 self.owner_balance.insert(&token_contract, &amount.0);
+                ContractEvent::WithdrawalFailed(WithdrawalData {
+                    kind: WithdrawalKind::Owner,
+                    receiver_id: &self.owner_id,
+                    token_contract: &token_contract,
+                    amount,
+                    game_code: None,
+                })
+                .emit();
             }
         }
     }
@@ -25,6 +66,14 @@ impl Contract {
             true => {}
             false => {
                 self.nft_balance.insert(&token_contract, &amount.0);
+                ContractEvent::WithdrawalFailed(WithdrawalData {
+                    kind: WithdrawalKind::Nft,
+                    receiver_id: &self.nft_account,
+                    token_contract: &token_contract,
+                    amount,
+                    game_code: None,
+                })
+                .emit();
             }
         }
     }
@@ -41,6 +90,14 @@ impl Contract {
                 let mut game = self.internal_get_game(&project_id).unwrap();
                 game.partner_balance += amount.0;
                 self.internal_update_game(&project_id, &game);
+                ContractEvent::WithdrawalFailed(WithdrawalData {
+                    kind: WithdrawalKind::Partner,
+                    receiver_id: &game.partner_owner,
+                    token_contract: &game.partner_token,
+                    amount,
+                    game_code: Some(&project_id),
+                })
+                .emit();
             }
         }
     }
@@ -55,8 +112,16 @@ impl Contract {
             true => {}
             false => {
                 let mut game = self.internal_get_game(&project_id).unwrap();
-                game.house_funds += amount.0;
+                game.return_partner_funds(amount.0);
                 self.internal_update_game(&project_id, &game);
+                ContractEvent::WithdrawalFailed(WithdrawalData {
+                    kind: WithdrawalKind::HouseFunds,
+                    receiver_id: &game.partner_owner,
+                    token_contract: &game.partner_token,
+                    amount,
+                    game_code: Some(&project_id),
+                })
+                .emit();
             }
         }
     }
@@ -71,17 +136,12 @@ impl Contract {
         match is_promise_success() {
             true => {
                 let mut account = self.internal_get_account(&user_account_id).unwrap();
-                match account.balances.get(&token_contract) {
-                    Some(v) => {
-                        if v == 0 {
-                            let initial_storage = env::storage_usage();
-                            account.balances.remove(&token_contract);
-                            self.internal_update_account(&user_account_id, &account);
-                            account.track_storage_usage(initial_storage);
-                            self.internal_update_account(&user_account_id, &account);
-                        }
-                    }
-                    None => {}
+                if let Some(0) = account.balances.get(&token_contract) {
+                    let initial_storage = env::storage_usage();
+                    account.balances.remove(&token_contract);
+                    self.internal_update_account(&user_account_id, &account);
+                    account.track_storage_usage(initial_storage);
+                    self.internal_update_account(&user_account_id, &account);
                 }
             }
             false => {
@@ -94,7 +154,131 @@ impl Contract {
                 self.internal_update_account(&user_account_id, &account);
                 account.track_storage_usage(initial_storage);
                 self.internal_update_account(&user_account_id, &account);
+                ContractEvent::WithdrawalFailed(WithdrawalData {
+                    kind: WithdrawalKind::Credits,
+                    receiver_id: &user_account_id,
+                    token_contract: &token_contract,
+                    amount,
+                    game_code: None,
+                })
+                .emit();
             }
         }
     }
+
+    // NEP-141 resolve of house_ft_transfer_call. Sends the shares the receiver did not use back
+    // to the sender, as far as the receiver still holds them, and returns the shares used.
+    // Refunds to a sender that unregistered in the meantime are burnt
+    #[private]
+    pub fn house_ft_resolve_transfer(
+        &mut self,
+        game_id: GameId,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        let unused = match env::promise_result(0) {
+            PromiseResult::Successful(data) => serde_json::from_slice::<U128>(&data)
+                .map(|unused| unused.0.min(amount.0))
+                .unwrap_or(amount.0),
+            _ => amount.0,
+        };
+        if unused == 0 {
+            return amount;
+        }
+
+        let mut game = self.internal_get_game(&game_id).expect(ERR_002);
+        let refund = unused.min(self.internal_house_shares(&game_id, &game, &receiver_id));
+        if refund == 0 {
+            return amount;
+        }
+        self.internal_withdraw_house_shares(&game_id, &mut game, &receiver_id, refund);
+        if game.partner_owner == sender_id || self.internal_get_account(&sender_id).is_some() {
+            self.internal_deposit_house_shares(&game_id, &mut game, &sender_id, refund);
+            ContractEvent::HouseSharesTransfer(HouseSharesTransferData {
+                game_code: &game_id,
+                sender_id: &receiver_id,
+                receiver_id: &sender_id,
+                shares: U128(refund),
+                memo: Some("refund"),
+            })
+            .emit();
+            self.internal_update_game(&game_id, &game);
+            return U128(amount.0 - refund);
+        }
+
+        game.total_shares -= refund;
+        ContractEvent::HouseSharesBurn(HouseSharesBurnData {
+            game_code: &game_id,
+            account_id: &receiver_id,
+            shares: U128(refund),
+        })
+        .emit();
+        self.internal_update_game(&game_id, &game);
+        amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use near_sdk::test_utils::testing_env_with_promise_results;
+
+    /// token_metadata_callback
+    /// method must:
+    /// 1. Assert metadata was fetched
+    /// 2. Cache token metadata in the whitelist
+    #[test]
+    fn test_token_metadata_callback() {
+        fn closure_generator(promise_succeeded: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let token = format!("{}-token.testnet", seed);
+                let mut context = get_context(vec![], false, 0, 1_000 * ONE_NEAR, CONTRACT_ACCOUNT.to_string());
+                testing_env!(context.clone());
+                let mut contract = sample_contract(seed);
+                contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1_000 * ONE_NEAR);
+                context.storage_usage = env::storage_usage();
+
+                let promise_result = if promise_succeeded {
+                    PromiseResult::Successful(
+                        json!({
+                            "spec": "ft-1.0.0",
+                            "name": "Token",
+                            "symbol": "TKN",
+                            "icon": "data:image/svg+xml,<svg></svg>",
+                            "reference": null,
+                            "reference_hash": null,
+                            "decimals": 6,
+                        })
+                        .to_string()
+                        .into_bytes(),
+                    )
+                } else {
+                    PromiseResult::Failed
+                };
+                testing_env_with_promise_results(context, promise_result);
+
+                contract.token_metadata_callback(token.clone());
+
+                let token_info = contract.tokens.get(&token).unwrap();
+                assert_eq!(token_info.symbol, "TKN");
+                assert_eq!(token_info.decimals, 6);
+                assert_eq!(token_info.format_amount(1_500_000), "1.5 TKN");
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert metadata was fetched
+            (false, Some(ERR_503.to_string())),
+            // 2. Cache token metadata in the whitelist
+            (true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+        });
+    }
 }
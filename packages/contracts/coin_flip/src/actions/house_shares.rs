@@ -0,0 +1,421 @@
+use crate::*;
+use crate::events::*;
+use crate::ext_interface::{ext_self, HOUSE_FT_RESOLVE_GAS, HOUSE_FT_TRANSFER_CALL_GAS};
+
+use near_contract_standards::fungible_token::core_impl::ext_fungible_token_receiver;
+use near_contract_standards::fungible_token::metadata::{FungibleTokenMetadata, FT_METADATA_SPEC};
+
+/// NEP-141 interface of the house shares of every game, namespaced by game_id: the
+/// standard methods prefixed with house_ and taking the game as first argument.
+/// Receivers of house_ft_transfer_call get the regular ft_on_transfer call, with this
+/// contract as predecessor. Shares of the game's partner are kept in the game and count
+/// as their balance
+#[near_bindgen]
+impl Contract {
+    #[payable]
+    pub fn house_ft_transfer(
+        &mut self,
+        game_id: GameId,
+        receiver_id: ValidAccountId,
+        amount: U128,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        self.assert_panic_button();
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer_house_shares(&game_id, &sender_id, receiver_id.as_ref(), amount.0, memo.as_deref());
+    }
+
+    // moves the shares and calls ft_on_transfer on the receiver, shares it reports as unused
+    // are sent back to the sender by house_ft_resolve_transfer
+    #[payable]
+    pub fn house_ft_transfer_call(
+        &mut self,
+        game_id: GameId,
+        receiver_id: ValidAccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert_one_yocto();
+        self.assert_panic_button();
+        let sender_id = env::predecessor_account_id();
+        let receiver_id: AccountId = receiver_id.into();
+        self.internal_transfer_house_shares(&game_id, &sender_id, &receiver_id, amount.0, memo.as_deref());
+        ext_fungible_token_receiver::ft_on_transfer(
+            sender_id.clone(),
+            amount,
+            msg,
+            &receiver_id,
+            0,
+            env::prepaid_gas() - HOUSE_FT_TRANSFER_CALL_GAS,
+        )
+        .then(ext_self::house_ft_resolve_transfer(
+            game_id,
+            sender_id,
+            receiver_id,
+            amount,
+            &env::current_account_id(),
+            0,
+            HOUSE_FT_RESOLVE_GAS,
+        ))
+        .into()
+    }
+
+    pub fn house_ft_total_supply(&self, game_id: GameId) -> U128 {
+        U128(self.internal_get_game(&game_id).expect(ERR_002).total_shares)
+    }
+
+    pub fn house_ft_balance_of(&self, game_id: GameId, account_id: ValidAccountId) -> U128 {
+        let game = self.internal_get_game(&game_id).expect(ERR_002);
+        U128(self.internal_house_shares(&game_id, &game, account_id.as_ref()))
+    }
+
+    // shares are minted 1:1 with the first deposit, so they use the decimals of the game's token
+    pub fn house_ft_metadata(&self, game_id: GameId) -> FungibleTokenMetadata {
+        let game = self.internal_get_game(&game_id).expect(ERR_002);
+        let token = self.tokens.get(&game.partner_token).expect(ERR_501);
+        FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: format!("{} house shares of {}", token.symbol, game_id),
+            symbol: format!("HOUSE-{}", token.symbol),
+            icon: token.icon,
+            reference: None,
+            reference_hash: None,
+            decimals: token.decimals,
+        }
+    }
+}
+
+impl Contract {
+    pub fn internal_transfer_house_shares(
+        &mut self,
+        game_id: &GameId,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<&str>,
+    ) {
+        assert!(amount > 0 && sender_id != receiver_id, "{}", ERR_905);
+        let mut game = self.internal_get_game(game_id).expect(ERR_002);
+        self.internal_withdraw_house_shares(game_id, &mut game, sender_id, amount);
+        self.internal_deposit_house_shares(game_id, &mut game, receiver_id, amount);
+        self.internal_update_game(game_id, &game);
+        ContractEvent::HouseSharesTransfer(HouseSharesTransferData {
+            game_code: game_id,
+            sender_id,
+            receiver_id,
+            shares: U128(amount),
+            memo,
+        })
+        .emit();
+    }
+
+    pub fn internal_house_shares(&self, game_id: &GameId, game: &PartneredGame, account_id: &AccountId) -> u128 {
+        let held = self
+            .internal_get_account(account_id)
+            .and_then(|account| account.house_shares.get(game_id))
+            .unwrap_or(0);
+        let partner_shares = if &game.partner_owner == account_id { game.partner_shares } else { 0 };
+        held + partner_shares
+    }
+
+    // takes shares kept in the game first when the account is the partner
+    pub fn internal_withdraw_house_shares(
+        &mut self,
+        game_id: &GameId,
+        game: &mut PartneredGame,
+        account_id: &AccountId,
+        shares: u128,
+    ) {
+        assert!(shares <= self.internal_house_shares(game_id, game, account_id), "{}", ERR_901);
+        let mut remaining = shares;
+        if &game.partner_owner == account_id {
+            let from_game = remaining.min(game.partner_shares);
+            game.partner_shares -= from_game;
+            remaining -= from_game;
+        }
+        if remaining == 0 {
+            return;
+        }
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(account_id).unwrap();
+        let held = account.house_shares.get(game_id).unwrap();
+        if held == remaining {
+            account.house_shares.remove(game_id);
+        } else {
+            account.house_shares.insert(game_id, &(held - remaining));
+        }
+        self.internal_update_account_storage_check(account_id, account, initial_storage);
+    }
+
+    // receivers other than the partner must be registered to pay for the storage of their shares
+    pub fn internal_deposit_house_shares(
+        &mut self,
+        game_id: &GameId,
+        game: &mut PartneredGame,
+        account_id: &AccountId,
+        shares: u128,
+    ) {
+        if &game.partner_owner == account_id {
+            game.partner_shares += shares;
+            return;
+        }
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(account_id).expect(ERR_001);
+        let held = account.house_shares.get(game_id).unwrap_or(0);
+        account.house_shares.insert(game_id, &(held + shares));
+        self.internal_update_account_storage_check(account_id, account, initial_storage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use near_sdk::test_utils::testing_env_with_promise_results;
+    use near_sdk::PromiseResult;
+
+    const HOUSE_FUNDS: u128 = 1_000;
+    const DEPOSIT: u128 = 500;
+
+    fn partner(seed: u128) -> AccountId {
+        format!("partner-{}.testnet", seed)
+    }
+
+    fn holder(seed: u128, index: usize) -> AccountId {
+        format!("holder-{}-{}.testnet", index, seed)
+    }
+
+    fn set_context(account_id: &AccountId, attached_deposit: u128) {
+        let mut context = get_context(vec![], false, attached_deposit, 1000 * ONE_NEAR, account_id.clone());
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+    }
+
+    // creates a game whose partner funded HOUSE_FUNDS and where the first holder provided DEPOSIT,
+    // registers a second holder without shares
+    fn sample_house_shares_contract(seed: u128) -> (Contract, GameId) {
+        let token = format!("{}-token.testnet", seed);
+        let game_id = "house".to_string();
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &token);
+        insert_test_game(
+            &mut contract,
+            &game_id,
+            &PartneredGame {
+                partner_owner: partner(seed),
+                max_bet: 1_000,
+                ..test_game(&token)
+            },
+        );
+        contract.fund_game_house(partner(seed), token.clone(), HOUSE_FUNDS, game_id.clone());
+        for index in 0..2 {
+            contract.internal_deposit_storage_account(&holder(seed, index), 1000 * ONE_NEAR);
+        }
+        set_context(&token, 0);
+        contract.provide_house_liquidity(holder(seed, 0), token, DEPOSIT, game_id.clone());
+        (contract, game_id)
+    }
+
+    /// house_ft_transfer
+    /// method must:
+    /// 1. Assert one yocto is attached
+    /// 2. Assert a positive amount sent to another account
+    /// 3. Assert the sender holds the shares
+    /// 4. Assert the receiver is registered or is the partner
+    /// 5. Move shares between holders, keeping the partner's in the game
+    #[test]
+    fn test_house_ft_transfer() {
+        fn closure_generator(
+            attached_deposit: u128,
+            sender: &'static str,
+            receiver: &'static str,
+            amount: u128,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id) = sample_house_shares_contract(seed);
+                let account = |name: &str| match name {
+                    "partner" => partner(seed),
+                    "holder" => holder(seed, 0),
+                    "empty_holder" => holder(seed, 1),
+                    _ => "unregistered.testnet".to_string(),
+                };
+                let (sender_id, receiver_id) = (account(sender), account(receiver));
+                let balance = |contract: &Contract, account_id: &AccountId| {
+                    contract.house_ft_balance_of(game_id.clone(), ValidAccountId::try_from(account_id.clone()).unwrap()).0
+                };
+                let sender_before = balance(&contract, &sender_id);
+                let receiver_before = balance(&contract, &receiver_id);
+
+                set_context(&sender_id, attached_deposit);
+                contract.house_ft_transfer(
+                    game_id.clone(),
+                    ValidAccountId::try_from(receiver_id.clone()).unwrap(),
+                    U128(amount),
+                    Some("memo".to_string()),
+                );
+
+                // 5. Move shares between holders, keeping the partner's in the game
+                assert_eq!(balance(&contract, &sender_id), sender_before - amount);
+                assert_eq!(balance(&contract, &receiver_id), receiver_before + amount);
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(game.total_shares, HOUSE_FUNDS + DEPOSIT);
+                let partner_shares = match (sender, receiver) {
+                    ("partner", _) => HOUSE_FUNDS - amount,
+                    (_, "partner") => HOUSE_FUNDS + amount,
+                    _ => HOUSE_FUNDS,
+                };
+                assert_eq!(game.partner_shares, partner_shares);
+                assert_eq!(contract.house_ft_balance_of(game_id.clone(), ValidAccountId::try_from(partner(seed)).unwrap()), U128(partner_shares));
+                let holder_account = contract.internal_get_account(&holder(seed, 0)).unwrap();
+                assert_eq!(holder_account.house_shares.get(&game_id).is_some(), balance(&contract, &holder(seed, 0)) > 0);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert one yocto is attached
+            (0, "holder", "empty_holder", 100, Some("Requires attached deposit of exactly 1 yoctoNEAR".to_string())),
+            // 2. Assert a positive amount sent to another account
+            (1, "holder", "empty_holder", 0, Some(ERR_905.to_string())),
+            (1, "holder", "holder", 100, Some(ERR_905.to_string())),
+            // 3. Assert the sender holds the shares
+            (1, "holder", "empty_holder", DEPOSIT + 1, Some(ERR_901.to_string())),
+            (1, "partner", "holder", HOUSE_FUNDS + 1, Some(ERR_901.to_string())),
+            // 4. Assert the receiver is registered or is the partner
+            (1, "holder", "unregistered", 100, Some(ERR_001.to_string())),
+            // 5. Move shares between holders, keeping the partner's in the game
+            (1, "holder", "empty_holder", 100, None),
+            (1, "holder", "empty_holder", DEPOSIT, None),
+            (1, "holder", "partner", 200, None),
+            (1, "partner", "empty_holder", HOUSE_FUNDS, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// house_ft_transfer_call, house_ft_resolve_transfer
+    /// methods must:
+    /// 1. Move the shares to the receiver before calling ft_on_transfer
+    /// 2. Send the shares the receiver did not use back to the sender
+    ///    a. all of them if ft_on_transfer failed
+    ///    b. never more than were sent or than the receiver still holds
+    /// 3. Burn refunds of senders that unregistered in the meantime
+    /// 4. Return the shares the receiver kept
+    #[test]
+    fn test_house_ft_transfer_call() {
+        fn closure_generator(
+            unused: Option<u128>,
+            receiver_spent: u128,
+            sender_unregistered: bool,
+            expected_used: u128,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id) = sample_house_shares_contract(seed);
+                let (sender_id, receiver_id) = (holder(seed, 0), holder(seed, 1));
+                let balance = |contract: &Contract, account_id: &AccountId| {
+                    contract.house_ft_balance_of(game_id.clone(), ValidAccountId::try_from(account_id.clone()).unwrap()).0
+                };
+                let amount = 300;
+
+                // 1. Move the shares to the receiver before calling ft_on_transfer
+                set_context(&sender_id, 1);
+                contract.house_ft_transfer_call(
+                    game_id.clone(),
+                    ValidAccountId::try_from(receiver_id.clone()).unwrap(),
+                    U128(amount),
+                    None,
+                    "msg".to_string(),
+                );
+                assert_eq!(balance(&contract, &sender_id), DEPOSIT - amount);
+                assert_eq!(balance(&contract, &receiver_id), amount);
+
+                if receiver_spent > 0 {
+                    set_context(&receiver_id, 1);
+                    contract.house_ft_transfer(
+                        game_id.clone(),
+                        ValidAccountId::try_from(partner(seed)).unwrap(),
+                        U128(receiver_spent),
+                        None,
+                    );
+                }
+                if sender_unregistered {
+                    contract.accounts.remove(&sender_id);
+                }
+                let promise_result = match unused {
+                    Some(unused) => PromiseResult::Successful(json!(U128(unused)).to_string().into_bytes()),
+                    None => PromiseResult::Failed,
+                };
+                let mut context = get_context(vec![], false, 0, 1000 * ONE_NEAR, CONTRACT_ACCOUNT.to_string());
+                context.storage_usage = env::storage_usage();
+                testing_env_with_promise_results(context, promise_result);
+                let used = contract.house_ft_resolve_transfer(game_id.clone(), sender_id.clone(), receiver_id.clone(), U128(amount));
+
+                // 4. Return the shares the receiver kept
+                assert_eq!(used, U128(expected_used));
+                let refund = amount - receiver_spent - balance(&contract, &receiver_id);
+                let game = contract.games.get(&game_id).unwrap();
+                if sender_unregistered {
+                    // 3. Burn refunds of senders that unregistered in the meantime
+                    assert_eq!(game.total_shares, HOUSE_FUNDS + DEPOSIT - refund);
+                } else {
+                    // 2. Send the shares the receiver did not use back to the sender
+                    assert_eq!(refund, amount - expected_used);
+                    assert_eq!(balance(&contract, &sender_id), DEPOSIT - expected_used);
+                    assert_eq!(game.total_shares, HOUSE_FUNDS + DEPOSIT);
+                }
+            }
+        }
+
+        let test_cases = [
+            // 2. Send the shares the receiver did not use back to the sender
+            (Some(100), 0, false, 200, None),
+            (Some(300), 0, false, 0, None),
+            //    a. all of them if ft_on_transfer failed
+            (None, 0, false, 0, None),
+            //    b. never more than were sent or than the receiver still holds
+            (Some(DEPOSIT), 0, false, 0, None),
+            (Some(300), 250, false, 250, None),
+            // 3. Burn refunds of senders that unregistered in the meantime
+            (Some(100), 0, true, 300, None),
+            // 4. Return the shares the receiver kept
+            (Some(0), 0, false, 300, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            counter += 1;
+        });
+    }
+
+    /// house_ft_total_supply, house_ft_metadata
+    /// methods must:
+    /// 1. Count every share issued, including the partner's
+    /// 2. Describe the shares with the symbol, decimals and icon of the game's token
+    #[test]
+    fn test_house_ft_views() {
+        let (contract, game_id) = sample_house_shares_contract(0);
+
+        // 1. Count every share issued, including the partner's
+        assert_eq!(contract.house_ft_total_supply(game_id.clone()), U128(HOUSE_FUNDS + DEPOSIT));
+
+        // 2. Describe the shares with the symbol, decimals and icon of the game's token
+        let token = contract.tokens.get(&"0-token.testnet".to_string()).unwrap();
+        let metadata = contract.house_ft_metadata(game_id);
+        metadata.assert_valid();
+        assert_eq!(metadata.symbol, format!("HOUSE-{}", token.symbol));
+        assert_eq!(metadata.decimals, token.decimals);
+        assert_eq!(metadata.icon, token.icon);
+    }
+}
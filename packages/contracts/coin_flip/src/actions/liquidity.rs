@@ -0,0 +1,550 @@
+use crate::*;
+use crate::events::*;
+use crate::house_withdrawal::MAX_QUEUED_WITHDRAWALS;
+
+#[near_bindgen]
+impl Contract {
+    //burns house shares of a game held by the caller and queues their value in the game's token,
+    //credited through execute_liquidity_withdrawal once the game's withdrawal_delay has passed
+    //like any other exit from the bankroll. Share value follows the house wins and losses since
+    //they were minted, funds reserved for pending bets can only be withdrawn once those bets
+    //settle. Returns the withdrawal id
+    pub fn withdraw_liquidity(&mut self, game_code: GameId, shares: U128) -> U128 {
+        self.assert_panic_button();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let held = account.house_shares.get(&game_code).unwrap_or(0);
+        assert!(shares.0 > 0 && shares.0 <= held, "{}", ERR_901);
+        let mut withdrawals = account.house_withdrawals.get(&game_code).unwrap_or_default();
+        assert!(withdrawals.len() < MAX_QUEUED_WITHDRAWALS, "{}", ERR_306);
+        let amount = game
+            .withdraw_liquidity(shares.0)
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.internal_update_game(&game_code, &game);
+
+        if held == shares.0 {
+            account.house_shares.remove(&game_code);
+        } else {
+            account.house_shares.insert(&game_code, &(held - shares.0));
+        }
+        let withdrawal = HouseWithdrawal {
+            withdrawal_id: self.next_id,
+            provider: Some(account_id.clone()),
+            amount,
+            unlocks_at: env::block_timestamp() + game.withdrawal_delay,
+        };
+        self.next_id += 1;
+        withdrawals.push(withdrawal.clone());
+        account.house_withdrawals.insert(&game_code, &withdrawals);
+        ContractEvent::LiquidityWithdrawn(LiquidityData {
+            game_code: &game_code,
+            account_id: &account_id,
+            token_contract: &game.partner_token,
+            amount: U128(amount),
+            shares,
+        })
+        .emit();
+        ContractEvent::HouseWithdrawalQueued(HouseWithdrawalData {
+            game_code: &game_code,
+            withdrawal: &withdrawal,
+        })
+        .emit();
+
+        // providers pay for the storage of their queued withdrawals
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        U128(withdrawal.withdrawal_id)
+    }
+
+    //credits a queued withdrawal of liquidity to the provider once its delay has passed
+    pub fn execute_liquidity_withdrawal(&mut self, game_code: GameId, withdrawal_id: U128) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let game = self.internal_get_game(&game_code).expect(ERR_002);
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let withdrawal = remove_liquidity_withdrawal(&mut account, &game_code, withdrawal_id.0);
+        assert!(env::block_timestamp() >= withdrawal.unlocks_at, "{}", ERR_305);
+
+        let credits = account.balances.get(&game.partner_token).unwrap_or(0);
+        account.balances.insert(&game.partner_token, &(credits + withdrawal.amount));
+        ContractEvent::HouseWithdrawalExecuted(HouseWithdrawalData {
+            game_code: &game_code,
+            withdrawal: &withdrawal,
+        })
+        .emit();
+
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        U128(withdrawal.amount)
+    }
+
+    //cancels a queued withdrawal of liquidity, its amount is provided back to the game at the
+    //current share value. Returns the shares minted
+    pub fn cancel_liquidity_withdrawal(&mut self, game_code: GameId, withdrawal_id: U128) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let withdrawal = remove_liquidity_withdrawal(&mut account, &game_code, withdrawal_id.0);
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        let shares = game
+            .provide_liquidity(withdrawal.amount)
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.internal_deposit_house_shares(&game_code, &mut game, &account_id, shares);
+        self.internal_update_game(&game_code, &game);
+        ContractEvent::HouseWithdrawalCancelled(HouseWithdrawalData {
+            game_code: &game_code,
+            withdrawal: &withdrawal,
+        })
+        .emit();
+        ContractEvent::LiquidityProvided(LiquidityData {
+            game_code: &game_code,
+            account_id: &account_id,
+            token_contract: &game.partner_token,
+            amount: U128(withdrawal.amount),
+            shares: U128(shares),
+        })
+        .emit();
+        U128(shares)
+    }
+}
+
+// removes a queued withdrawal from the provider's account, callers charge the storage it frees
+fn remove_liquidity_withdrawal(account: &mut Account, game_code: &GameId, withdrawal_id: u128) -> HouseWithdrawal {
+    let mut withdrawals = account.house_withdrawals.get(game_code).unwrap_or_default();
+    let index = withdrawals
+        .iter()
+        .position(|withdrawal| withdrawal.withdrawal_id == withdrawal_id)
+        .expect(ERR_304);
+    let withdrawal = withdrawals.remove(index);
+    if withdrawals.is_empty() {
+        account.house_withdrawals.remove(game_code);
+    } else {
+        account.house_withdrawals.insert(game_code, &withdrawals);
+    }
+    withdrawal
+}
+
+// methods to be called through token receiver
+impl Contract {
+    // mints house shares of a game for the deposit. Shares of the game's partner are kept
+    // in the game itself and burned through retrieve_house_funds
+    pub fn provide_house_liquidity(
+        &mut self,
+        sender_id: AccountId,
+        token_contract: AccountId,
+        amount: u128,
+        game_code: GameId,
+    ) {
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        assert_eq!(game.partner_token, token_contract, "{}", ERR_301);
+        let shares = game
+            .provide_liquidity(amount)
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.internal_deposit_house_shares(&game_code, &mut game, &sender_id, shares);
+        self.internal_update_game(&game_code, &game);
+        ContractEvent::LiquidityProvided(LiquidityData {
+            game_code: &game_code,
+            account_id: &sender_id,
+            token_contract: &token_contract,
+            amount: U128(amount),
+            shares: U128(shares),
+        })
+        .emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    const HOUSE_FUNDS: u128 = 1_000;
+    const DEPOSIT: u128 = 500;
+
+    fn partner(seed: u128) -> AccountId {
+        format!("partner-{}.testnet", seed)
+    }
+
+    fn provider(seed: u128) -> AccountId {
+        format!("provider-{}.testnet", seed)
+    }
+
+    fn set_context(account_id: &AccountId) {
+        set_context_at(account_id, 0, 0);
+    }
+
+    fn set_context_at(account_id: &AccountId, attached_deposit: u128, block_timestamp: u64) {
+        let mut context = get_context(vec![], false, attached_deposit, 1000 * ONE_NEAR, account_id.clone());
+        context.block_timestamp = block_timestamp;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+    }
+
+    // creates a fee-less game whose partner funded HOUSE_FUNDS and registers a liquidity provider
+    fn sample_liquidity_contract(seed: u128) -> (Contract, GameId, AccountId) {
+        let token = format!("{}-token.testnet", seed);
+        let game_id = "liquidity".to_string();
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &token);
+        insert_test_game(
+            &mut contract,
+            &game_id,
+            &PartneredGame {
+                partner_owner: partner(seed),
+                max_bet: 1_000,
+                ..test_game(&token)
+            },
+        );
+        contract.fund_game_house(partner(seed), token.clone(), HOUSE_FUNDS, game_id.clone());
+        contract.internal_deposit_storage_account(&provider(seed), 1000 * ONE_NEAR);
+        (contract, game_id, token)
+    }
+
+    fn provide(contract: &mut Contract, token: &AccountId, sender_id: AccountId, game_id: &GameId, amount: u128) -> U128 {
+        set_context(token);
+        contract.ft_on_transfer(
+            sender_id,
+            U128(amount),
+            json!({"type": "ProvideLiquidity", "game_id": game_id}).to_string(),
+        )
+    }
+
+    /// provide_house_liquidity
+    /// method must:
+    /// 1. Refund deposits from unregistered accounts other than the partner
+    /// 2. Refund deposits that cannot be priced or are worth no share
+    /// 3. Issue the bankroll funded so far to the partner on the first deposit
+    /// 4. Keep the partner's shares in the game
+    /// 5. Mint the provider shares at the current share value
+    #[test]
+    fn test_provide_liquidity() {
+        fn closure_generator(
+            sender: &'static str,
+            house_funds: Option<u128>,
+            amount: u128,
+            refund_reason: Option<&'static str>,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_liquidity_contract(seed);
+                // a first deposit prices shares 1:1, then the house result moves their value
+                assert_eq!(provide(&mut contract, &token, provider(seed), &game_id, DEPOSIT), U128(0));
+                if let Some(house_funds) = house_funds {
+                    let mut game = contract.games.get(&game_id).unwrap();
+                    game.house_funds = house_funds;
+                    contract.games.insert(&game_id, &game);
+                }
+                let before = contract.games.get(&game_id).unwrap();
+
+                let sender_id = match sender {
+                    "partner" => partner(seed),
+                    "provider" => provider(seed),
+                    _ => "unregistered.testnet".to_string(),
+                };
+                let result = provide(&mut contract, &token, sender_id.clone(), &game_id, amount);
+                let game = contract.games.get(&game_id).unwrap();
+                if let Some(reason) = refund_reason {
+                    assert_eq!(result, U128(amount));
+                    assert!(near_sdk::test_utils::get_logs().last().unwrap().contains(reason));
+                    assert_eq!(game.total_shares, before.total_shares);
+                    return;
+                }
+                assert_eq!(result, U128(0));
+
+                let minted = amount * before.total_shares / before.bankroll();
+                assert_eq!(game.house_funds, before.house_funds + amount);
+                assert_eq!(game.total_shares, before.total_shares + minted);
+                assert_eq!(game.partner_shares + contract.house_ft_balance_of(game_id.clone(), ValidAccountId::try_from(provider(seed)).unwrap()).0, game.total_shares);
+                if sender == "partner" {
+                    assert_eq!(game.partner_shares, HOUSE_FUNDS + minted);
+                    assert_eq!(contract.house_ft_balance_of(game_id, ValidAccountId::try_from(partner(seed)).unwrap()), U128(HOUSE_FUNDS + minted));
+                } else {
+                    assert_eq!(game.partner_shares, HOUSE_FUNDS);
+                    assert_eq!(contract.house_ft_balance_of(game_id, ValidAccountId::try_from(provider(seed)).unwrap()), U128(DEPOSIT + minted));
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Refund deposits from unregistered accounts other than the partner
+            ("unregistered", None, DEPOSIT, Some(ERR_001), None),
+            // 2. Refund deposits that cannot be priced or are worth no share
+            ("provider", Some(0), DEPOSIT, Some(ERR_903), None),
+            ("provider", Some(10_000), 1, Some(ERR_904), None),
+            // 3. Issue the bankroll funded so far to the partner on the first deposit
+            // 4. Keep the partner's shares in the game
+            ("partner", None, DEPOSIT, None, None),
+            // 5. Mint the provider shares at the current share value
+            ("provider", None, DEPOSIT, None, None),
+            ("provider", Some(3_000), 1_000, None, None),
+            ("provider", Some(750), 300, None, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// withdraw_liquidity
+    /// method must:
+    /// 1. Assert the caller holds the shares
+    /// 2. Assert house_funds not reserved for pending bets cover the withdrawal
+    /// 3. Assert the caller has less than MAX_QUEUED_WITHDRAWALS queued in the game
+    /// 4. Burn the shares and queue their value behind the game's withdrawal_delay
+    #[test]
+    fn test_withdraw_liquidity() {
+        fn closure_generator(
+            shares: u128,
+            house_funds: u128,
+            reserved_funds: u128,
+            queued: usize,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_liquidity_contract(seed);
+                provide(&mut contract, &token, provider(seed), &game_id, DEPOSIT);
+                let mut game = contract.games.get(&game_id).unwrap();
+                game.house_funds = house_funds;
+                game.reserved_funds = reserved_funds;
+                contract.games.insert(&game_id, &game);
+
+                set_context(&provider(seed));
+                for _ in 0..queued {
+                    contract.withdraw_liquidity(game_id.clone(), U128(1));
+                }
+                let withdrawal_id = contract.withdraw_liquidity(game_id.clone(), U128(shares));
+
+                // 4. Burn the shares and queue their value behind the game's withdrawal_delay
+                let value = shares * (house_funds + reserved_funds) / (HOUSE_FUNDS + DEPOSIT);
+                assert_eq!(
+                    contract.liquidity_withdrawals(game_id.clone(), provider(seed)),
+                    vec![HouseWithdrawal {
+                        withdrawal_id: withdrawal_id.0,
+                        provider: Some(provider(seed)),
+                        amount: value,
+                        unlocks_at: DEFAULT_WITHDRAWAL_DELAY,
+                    }]
+                );
+                assert_eq!(contract.get_credits(token, provider(seed)), U128(0));
+                assert_eq!(contract.house_ft_balance_of(game_id.clone(), ValidAccountId::try_from(provider(seed)).unwrap()), U128(DEPOSIT - shares));
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(game.house_funds, house_funds - value);
+                assert_eq!(game.total_shares, HOUSE_FUNDS + DEPOSIT - shares);
+                assert_eq!(game.partner_shares, HOUSE_FUNDS);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the caller holds the shares
+            (0, HOUSE_FUNDS + DEPOSIT, 0, 0, Some(ERR_901.to_string())),
+            (DEPOSIT + 1, HOUSE_FUNDS + DEPOSIT, 0, 0, Some(ERR_901.to_string())),
+            // 2. Assert house_funds not reserved for pending bets cover the withdrawal
+            (DEPOSIT, 100, 1_400, 0, Some(ERR_902.to_string())),
+            // 3. Assert the caller has less than MAX_QUEUED_WITHDRAWALS queued in the game
+            (1, HOUSE_FUNDS + DEPOSIT, 0, MAX_QUEUED_WITHDRAWALS, Some(ERR_306.to_string())),
+            // 4. Burn the shares and queue their value behind the game's withdrawal_delay
+            (DEPOSIT, HOUSE_FUNDS + DEPOSIT, 0, 0, None),
+            (DEPOSIT / 2, 3_000, 0, 0, None),
+            (DEPOSIT, 600, 300, 0, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// execute_liquidity_withdrawal
+    /// method must:
+    /// 1. Assert the withdrawal was queued by the caller
+    /// 2. Assert the withdrawal_delay has passed
+    /// 3. Remove the withdrawal from the queue and credit it to the provider
+    #[test]
+    fn test_execute_liquidity_withdrawal() {
+        fn closure_generator(caller_is_provider: bool, block_timestamp: u64, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_liquidity_contract(seed);
+                provide(&mut contract, &token, provider(seed), &game_id, DEPOSIT);
+                set_context(&provider(seed));
+                let withdrawal_id = contract.withdraw_liquidity(game_id.clone(), U128(DEPOSIT));
+
+                // withdrawals are looked up in the caller's own account
+                let caller = if caller_is_provider { provider(seed) } else { partner(seed) };
+                contract.internal_deposit_storage_account(&partner(seed), 1000 * ONE_NEAR);
+                set_context_at(&caller, 0, block_timestamp);
+                let amount = contract.execute_liquidity_withdrawal(game_id.clone(), withdrawal_id);
+
+                // 3. Remove the withdrawal from the queue and credit it to the provider
+                assert_eq!(amount, U128(DEPOSIT));
+                assert!(contract.liquidity_withdrawals(game_id.clone(), provider(seed)).is_empty());
+                assert_eq!(contract.get_credits(token, provider(seed)), U128(DEPOSIT));
+                assert_eq!(contract.games.get(&game_id).unwrap().house_funds, HOUSE_FUNDS);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the withdrawal was queued by the caller
+            (false, DEFAULT_WITHDRAWAL_DELAY, Some(ERR_304.to_string())),
+            // 2. Assert the withdrawal_delay has passed
+            (true, DEFAULT_WITHDRAWAL_DELAY - 1, Some(ERR_305.to_string())),
+            // 3. Remove the withdrawal from the queue and credit it to the provider
+            (true, DEFAULT_WITHDRAWAL_DELAY, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// cancel_liquidity_withdrawal
+    /// method must:
+    /// 1. Assert the withdrawal was queued by the caller
+    /// 2. Provide the amount back to the game at the current share value
+    #[test]
+    fn test_cancel_liquidity_withdrawal() {
+        fn closure_generator(caller_is_provider: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_liquidity_contract(seed);
+                provide(&mut contract, &token, provider(seed), &game_id, DEPOSIT);
+                set_context(&provider(seed));
+                let withdrawal_id = contract.withdraw_liquidity(game_id.clone(), U128(DEPOSIT));
+                // house doubled its funds while the withdrawal was queued
+                let mut game = contract.games.get(&game_id).unwrap();
+                game.house_funds *= 2;
+                contract.games.insert(&game_id, &game);
+
+                // withdrawals are looked up in the caller's own account
+                let caller = if caller_is_provider { provider(seed) } else { partner(seed) };
+                contract.internal_deposit_storage_account(&partner(seed), 1000 * ONE_NEAR);
+                set_context(&caller);
+                let shares = contract.cancel_liquidity_withdrawal(game_id.clone(), withdrawal_id);
+
+                // 2. Provide the amount back to the game at the current share value
+                assert_eq!(shares, U128(DEPOSIT / 2));
+                assert!(contract.liquidity_withdrawals(game_id.clone(), provider(seed)).is_empty());
+                assert_eq!(contract.house_ft_balance_of(game_id.clone(), ValidAccountId::try_from(provider(seed)).unwrap()), shares);
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(game.house_funds, 2 * HOUSE_FUNDS + DEPOSIT);
+                assert_eq!(game.total_shares, HOUSE_FUNDS + DEPOSIT / 2);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the withdrawal was queued by the caller
+            (false, Some(ERR_304.to_string())),
+            // 2. Provide the amount back to the game at the current share value
+            (true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// partner shares moved to another account
+    /// methods must:
+    /// 1. Keep the whole bankroll behind the game's withdrawal_delay when withdrawn as liquidity
+    #[test]
+    fn test_transferred_partner_shares_keep_withdrawal_delay() {
+        fn closure_generator(block_timestamp: u64, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_liquidity_contract(seed);
+                // a tiny deposit issues the whole bankroll to the partner as shares
+                provide(&mut contract, &token, partner(seed), &game_id, 1);
+                let shares = contract.house_ft_balance_of(game_id.clone(), ValidAccountId::try_from(partner(seed)).unwrap());
+                assert_eq!(shares, U128(HOUSE_FUNDS + 1));
+                set_context_at(&partner(seed), 1, 0);
+                contract.house_ft_transfer(
+                    game_id.clone(),
+                    ValidAccountId::try_from(provider(seed)).unwrap(),
+                    shares,
+                    None,
+                );
+
+                set_context(&provider(seed));
+                let withdrawal_id = contract.withdraw_liquidity(game_id.clone(), shares);
+                assert_eq!(contract.games.get(&game_id).unwrap().house_funds, 0);
+                assert_eq!(contract.get_credits(token.clone(), provider(seed)), U128(0));
+
+                // 1. Keep the whole bankroll behind the game's withdrawal_delay when withdrawn as liquidity
+                set_context_at(&provider(seed), 0, block_timestamp);
+                contract.execute_liquidity_withdrawal(game_id, withdrawal_id);
+                assert_eq!(contract.get_credits(token, provider(seed)), U128(HOUSE_FUNDS + 1));
+            }
+        }
+
+        let test_cases = [
+            // 1. Keep the whole bankroll behind the game's withdrawal_delay when withdrawn as liquidity
+            (0, Some(ERR_305.to_string())),
+            (DEFAULT_WITHDRAWAL_DELAY - 1, Some(ERR_305.to_string())),
+            (DEFAULT_WITHDRAWAL_DELAY, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// retrieve_house_funds on a game with liquidity providers
+    /// method must:
+    /// 1. Assert the partner's shares are worth the quantity
+    /// 2. Burn the partner's shares rounded up, leaving the providers' value untouched
+    #[test]
+    fn test_retrieve_house_funds_with_shares() {
+        fn closure_generator(quantity: u128, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_liquidity_contract(seed);
+                provide(&mut contract, &token, provider(seed), &game_id, DEPOSIT);
+                // house lost a third of the bankroll
+                let mut game = contract.games.get(&game_id).unwrap();
+                game.house_funds = 1_000;
+                contract.games.insert(&game_id, &game);
+                let provider_value = contract.house_share_value(game_id.clone(), U128(DEPOSIT));
+
+                set_context(&partner(seed));
+                contract.retrieve_house_funds(game_id.clone(), U128(quantity));
+
+                let game = contract.games.get(&game_id).unwrap();
+                let burned = (quantity * (HOUSE_FUNDS + DEPOSIT)).div_ceil(1_000);
+                assert_eq!(game.partner_shares, HOUSE_FUNDS - burned);
+                assert_eq!(game.total_shares, HOUSE_FUNDS + DEPOSIT - burned);
+                assert_eq!(game.house_funds, 1_000 - quantity);
+                assert!(contract.house_share_value(game_id, U128(DEPOSIT)).0 >= provider_value.0);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the partner's shares are worth the quantity
+            (667, Some(ERR_901.to_string())),
+            // 2. Burn the partner's shares rounded up, leaving the providers' value untouched
+            (666, None),
+            (101, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+}
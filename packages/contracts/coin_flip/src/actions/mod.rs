@@ -1,7 +1,13 @@
 pub mod callback;
+pub mod house_shares;
 pub mod token_receiver;
 pub mod storage_impl;
+pub mod liquidity;
 pub mod owner;
 pub mod partner;
 pub mod player;
+pub mod pvp;
+pub mod raffle;
+pub mod streak;
+pub mod tournament;
 pub mod view;
\ No newline at end of file
@@ -1,4 +1,5 @@
 use crate::*;
+use crate::events::*;
 
 #[near_bindgen]
 impl Contract {
@@ -6,6 +7,11 @@ impl Contract {
     pub fn emergency_panic(&mut self) -> bool {
         self.only_owner();
         self.panic_button = !self.panic_button;
+        ContractEvent::PanicToggle(PanicToggleData {
+            account_id: &self.owner_id,
+            panic_button: self.panic_button,
+        })
+        .emit();
         self.panic_button
     }
 
@@ -13,6 +19,11 @@ impl Contract {
     pub fn update_contract(&mut self, new_owner: AccountId) {
         self.only_owner();
 
+        ContractEvent::OwnerUpdated(OwnerUpdatedData {
+            old_owner: &self.owner_id,
+            new_owner: &new_owner,
+        })
+        .emit();
         self.owner_id = new_owner;
     }
 
@@ -23,6 +34,14 @@ impl Contract {
         let value = self.owner_balance.values_as_vector().get(token_index).unwrap();
         assert!(value > 0, "{}", ERR_203);
         self.owner_balance.insert(&key, &0);
+        ContractEvent::Withdrawal(WithdrawalData {
+            kind: WithdrawalKind::Owner,
+            receiver_id: &self.owner_id,
+            token_contract: &key,
+            amount: U128(value),
+            game_code: None,
+        })
+        .emit();
         self.safe_transfer_owner(key, value)
     }
 
@@ -33,13 +52,49 @@ impl Contract {
         let value = self.nft_balance.values_as_vector().get(token_index).unwrap();
         assert!(value > 0, "{}", ERR_203);
         self.nft_balance.insert(&key, &0);
+        ContractEvent::Withdrawal(WithdrawalData {
+            kind: WithdrawalKind::Nft,
+            receiver_id: &self.nft_account,
+            token_contract: &key,
+            amount: U128(value),
+            game_code: None,
+        })
+        .emit();
         self.safe_transfer_nft(key, value)
     }
 
-    //create new partnered game
+    //whitelists a token, its metadata is fetched and cached by token_metadata_callback
+    #[payable]
+    pub fn register_token(&mut self, token_contract: AccountId) -> Promise {
+        self.only_owner();
+        assert!(self.tokens.get(&token_contract).is_none(), "{}", ERR_502);
+        self.fetch_token_metadata(token_contract)
+    }
+
+    #[payable]
+    pub fn remove_token(&mut self, token_contract: AccountId) {
+        self.only_owner();
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        self.tokens.remove(&token_contract).expect(ERR_501);
+        ContractEvent::TokenRemoved(TokenData {
+            token_contract: &token_contract,
+            token: None,
+        })
+        .emit();
+
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+    }
+
+    //create new partnered game. game_id is an optional custom slug, if not provided
+    //the game gets the next auto incremented id. Returns the id of the created game
     #[payable]
     pub fn create_new_partner(
         &mut self,
+        game_id: Option<GameId>,
         partner_owner: AccountId,
         nft_contract: AccountId,
         token_contract: AccountId,
@@ -48,32 +103,90 @@ impl Contract {
         house_fee: U128,
         max_bet: U128,
         min_bet: U128,
-        max_odds: u8,
-        min_odds: u8,
+        max_odds: u32,
+        min_odds: u32,
+        odds_precision: Option<u32>,
+        game_type: Option<GameType>,
         nft_fee: U128,
         owner_fee: U128,
-    ) {
+        jackpot_fee: Option<U128>,
+        jackpot_trigger: Option<u32>,
+        max_streak: Option<u32>,
+        withdrawal_delay: Option<U64>,
+        max_exposure: Option<U128>,
+        drawdown_limits: Option<DrawdownLimits>,
+    ) -> GameId {
         self.only_owner();
-        assert!(!self.games.contains_key(&nft_contract), "{}", ERR_003);
+        let game_id = match game_id {
+            Some(game_id) => {
+                if let Err(err) = PartneredGame::validate_game_id(&game_id) {
+                    panic!("{}", err);
+                }
+                game_id
+            }
+            None => {
+                self.next_game_id += 1;
+                (self.next_game_id - 1).to_string()
+            }
+        };
+        assert!(self.games.get(&game_id).is_none(), "{}", ERR_003);
+        assert!(self.tokens.get(&token_contract).is_some(), "{}", ERR_501);
         let contract_id = env::current_account_id();
         let mut contract_account = self.internal_get_account(&contract_id).unwrap();
         let initial_storage = env::storage_usage();
 
-        self.nft_balance.insert(&token_contract, &0);
-        self.owner_balance.insert(&token_contract, &0);
+        // several games may share a token, keep the fees already accrued for it
+        if self.nft_balance.get(&token_contract).is_none() {
+            self.nft_balance.insert(&token_contract, &0);
+        }
+        if self.owner_balance.get(&token_contract).is_none() {
+            self.owner_balance.insert(&token_contract, &0);
+        }
 
         assert!(max_bet.0 > min_bet.0, "{}", ERR_206);
         assert!(max_odds > min_odds, "{}", ERR_206);
+        let odds_precision = odds_precision.unwrap_or(DEFAULT_ODDS_PRECISION);
+        let game_type = game_type.unwrap_or_default();
+        if let Err(err) = PartneredGame::validate_odds(odds_precision, min_odds, max_odds) {
+            panic!("{}", err);
+        }
+        if let Err(err) = game_type.validate(bet_payment_adjustment.0) {
+            panic!("{}", err);
+        }
         assert!(house_fee.0 <= FRACTIONAL_BASE, "{}", ERR_205);
         assert!(partner_fee.0 <= FRACTIONAL_BASE, "{}", ERR_205);
         assert!(nft_fee.0 <= FRACTIONAL_BASE, "{}", ERR_205);
         assert!(owner_fee.0 <= FRACTIONAL_BASE, "{}", ERR_205);
         assert!(bet_payment_adjustment.0 <= FRACTIONAL_BASE, "{}", ERR_205);
+        let jackpot_fee = jackpot_fee.map(|v| v.0).unwrap_or(0);
+        let jackpot_trigger = jackpot_trigger.unwrap_or(DEFAULT_JACKPOT_TRIGGER);
+        let max_streak = max_streak.unwrap_or(DEFAULT_MAX_STREAK);
+        let withdrawal_delay = withdrawal_delay.map(|v| v.0).unwrap_or(DEFAULT_WITHDRAWAL_DELAY);
+        // games risk their whole house_funds on a single bet unless told otherwise
+        let max_exposure = max_exposure.map(|v| v.0).unwrap_or(FRACTIONAL_BASE);
+        assert!(jackpot_fee <= FRACTIONAL_BASE, "{}", ERR_205);
+        // fees are all cut from the bet, together they cannot take more than the bet
+        assert!(
+            nft_fee.0 + owner_fee.0 + house_fee.0 + partner_fee.0 + jackpot_fee <= FRACTIONAL_BASE,
+            "{}",
+            ERR_205
+        );
+        assert!(jackpot_trigger > 1, "{}", ERR_212);
+        assert!(max_streak > 0, "{}", ERR_213);
+        assert!(withdrawal_delay <= MAX_WITHDRAWAL_DELAY, "{}", ERR_214);
+        assert!(max_exposure > 0 && max_exposure <= FRACTIONAL_BASE, "{}", ERR_215);
+        let drawdown_limits = drawdown_limits.unwrap_or_default();
+        if let Err(err) = drawdown_limits.validate() {
+            panic!("{}", err);
+        }
 
         let game_settings = PartneredGame {
             partner_owner,
+            nft_contract,
             blocked: false,
+            breaker: None,
             house_funds: 0,
+            reserved_funds: 0,
             partner_token: token_contract,
             partner_fee: partner_fee.0,
             partner_balance: 0,
@@ -82,21 +195,39 @@ impl Contract {
             house_fee: house_fee.0,
             max_bet: max_bet.0,
             min_bet: min_bet.0,
+            game_type,
+            odds_precision,
             max_odds,
             min_odds,
             nft_fee: nft_fee.0,
-            owner_fee: owner_fee.0
+            owner_fee: owner_fee.0,
+            jackpot_fee,
+            jackpot_trigger,
+            jackpot_balance: 0,
+            max_streak,
+            withdrawal_delay,
+            total_shares: 0,
+            partner_shares: 0,
+            max_exposure,
+            drawdown_limits,
+            drawdown: Drawdown::default(),
         };
-        self.games.insert(&nft_contract, &game_settings);
+        self.internal_insert_game(&game_id, &game_settings);
+        ContractEvent::GameCreated(GameData {
+            game_code: &game_id,
+            game: &game_settings,
+        })
+        .emit();
 
         contract_account.track_storage_usage(initial_storage);
         self.internal_update_account(&contract_id, &contract_account);
+        game_id
     }
 
     #[payable]
     pub fn alter_partner(
         &mut self,
-        game_id: String,
+        game_id: GameId,
         partner_owner: AccountId,
         partner_fee: U128,
         blocked: bool,
@@ -104,13 +235,21 @@ impl Contract {
         house_fee: U128,
         max_bet: U128,
         min_bet: U128,
-        max_odds: u8,
-        min_odds: u8,
+        max_odds: u32,
+        min_odds: u32,
+        odds_precision: Option<u32>,
+        game_type: Option<GameType>,
         nft_fee: U128,
         owner_fee: U128,
+        jackpot_fee: Option<U128>,
+        jackpot_trigger: Option<u32>,
+        max_streak: Option<u32>,
+        withdrawal_delay: Option<U64>,
+        max_exposure: Option<U128>,
+        drawdown_limits: Option<DrawdownLimits>,
     ) {
         self.only_owner();
-        assert!(self.games.contains_key(&game_id), "{}", ERR_002);
+        assert!(self.games.get(&game_id).is_some(), "{}", ERR_002);
         assert!(max_bet.0 > min_bet.0, "{}", ERR_206);
         assert!(max_odds > min_odds, "{}", ERR_206);
         assert!(house_fee.0 <= FRACTIONAL_BASE, "{}", ERR_205);
@@ -119,6 +258,41 @@ impl Contract {
         assert!(owner_fee.0 <= FRACTIONAL_BASE, "{}", ERR_205);
         assert!(bet_payment_adjustment.0 <= FRACTIONAL_BASE, "{}", ERR_205);
         let mut game = self.internal_get_game(&game_id).expect(ERR_002);
+        // pending bets keep the precision they were committed with
+        let odds_precision = odds_precision.unwrap_or(game.odds_precision);
+        let game_type = game_type.unwrap_or(game.game_type);
+        // the pool already accrued stays in the game whatever its new jackpot settings
+        let jackpot_fee = jackpot_fee.map(|v| v.0).unwrap_or(game.jackpot_fee);
+        let jackpot_trigger = jackpot_trigger.unwrap_or(game.jackpot_trigger);
+        // open streaks above a lowered max_streak can still be collected but not pressed
+        let max_streak = max_streak.unwrap_or(game.max_streak);
+        // withdrawals already queued keep the unlock time they were queued with
+        let withdrawal_delay = withdrawal_delay.map(|v| v.0).unwrap_or(game.withdrawal_delay);
+        let max_exposure = max_exposure.map(|v| v.0).unwrap_or(game.max_exposure);
+        assert!(jackpot_fee <= FRACTIONAL_BASE, "{}", ERR_205);
+        assert!(
+            nft_fee.0 + owner_fee.0 + house_fee.0 + partner_fee.0 + jackpot_fee <= FRACTIONAL_BASE,
+            "{}",
+            ERR_205
+        );
+        assert!(jackpot_trigger > 1, "{}", ERR_212);
+        assert!(max_streak > 0, "{}", ERR_213);
+        assert!(withdrawal_delay <= MAX_WITHDRAWAL_DELAY, "{}", ERR_214);
+        assert!(max_exposure > 0 && max_exposure <= FRACTIONAL_BASE, "{}", ERR_215);
+        // losses already counted stay, they are only checked against the new limits
+        let drawdown_limits = drawdown_limits.unwrap_or(game.drawdown_limits);
+        if let Err(err) = drawdown_limits.validate() {
+            panic!("{}", err);
+        }
+        if let Err(err) = PartneredGame::validate_odds(odds_precision, min_odds, max_odds) {
+            panic!("{}", err);
+        }
+        if let Err(err) = game_type.validate(bet_payment_adjustment.0) {
+            panic!("{}", err);
+        }
+        if game.partner_owner != partner_owner {
+            self.internal_transfer_game(&game_id, &game.partner_owner, &partner_owner);
+        }
         game.partner_owner = partner_owner;
         game.partner_fee = partner_fee.0;
         game.blocked = blocked;
@@ -126,11 +300,24 @@ impl Contract {
         game.house_fee = house_fee.0;
         game.max_bet = max_bet.0;
         game.min_bet = min_bet.0;
+        game.game_type = game_type;
+        game.odds_precision = odds_precision;
         game.max_odds = max_odds;
         game.min_odds = min_odds;
         game.nft_fee = nft_fee.0;
         game.owner_fee = owner_fee.0;
+        game.jackpot_fee = jackpot_fee;
+        game.jackpot_trigger = jackpot_trigger;
+        game.max_streak = max_streak;
+        game.withdrawal_delay = withdrawal_delay;
+        game.max_exposure = max_exposure;
+        game.drawdown_limits = drawdown_limits;
         self.internal_update_game(&game_id, &game);
+        ContractEvent::GameAltered(GameData {
+            game_code: &game_id,
+            game: &game,
+        })
+        .emit();
     }
 }
 
@@ -145,6 +332,7 @@ mod tests {
     /// 2. Assert caller deposited 1 yoctoNear
     /// 3. Toggle the bool variable panic_button
     ///    in the contract global
+    /// 4. Emit panic_toggle event
     #[test]
     fn test_emergency_panic() {
         fn closure_generator(
@@ -163,6 +351,9 @@ mod tests {
                 contract.emergency_panic();
 
                 assert_ne!(panic_button_state, contract.panic_button);
+                let logs = near_sdk::test_utils::get_logs();
+                assert!(logs[0].starts_with("EVENT_JSON:"));
+                assert!(logs[0].contains(r#""event":"panic_toggle""#));
             }
         }
 
@@ -389,16 +580,30 @@ mod tests {
     /// 4. Assert data validations
     ///    a. max_bet > min_bet
     ///    b. max_odds > min_odds
-    ///    c. fees <= FRACTION_BASE 
-    /// 5. Insert new game into LookupMap
+    ///    c. fees <= FRACTION_BASE, also all of them summed, also all of them summed 
+    ///    d. odds_precision in range and odds limits inside it
+    ///    e. jackpot_trigger > 1
+    ///    f. max_streak > 0
+    ///    g. withdrawal_delay <= MAX_WITHDRAWAL_DELAY
+    ///    h. max_exposure between 1 and FRACTIONAL_BASE
+    ///    i. drawdown limits with a max_window_loss have a loss_window
+    /// 5. Assert that game token is whitelisted
+    /// 6. Assert custom game id is a valid slug
+    ///    a. numeric ids are reserved for auto incremented games
+    /// 7. Assert game id is not taken
+    /// 8. Insert new game into LookupMap under custom or auto incremented id
+    ///    without resetting fees accrued in the same token
     #[test]
+    #[allow(clippy::type_complexity)]
     fn test_create_new_partner() {
 
         fn closure_generator(
             signer: AccountId,
             deposit: u128,
             contract_storage_balance: u128,
-            params: (U128, U128, U128, U128, U128, u8, u8, U128, U128),
+            token_registered: bool,
+            game_id: Option<&'static str>,
+            params: (U128, U128, U128, U128, U128, u32, u32, Option<u32>, U128, U128, Option<u32>, Option<u32>, Option<U64>, Option<U128>, Option<DrawdownLimits>),
             seed: u128,
         ) -> impl FnOnce() {
             move || {
@@ -410,26 +615,69 @@ mod tests {
                 // instantiate a contract variable with the counter at zero
                 let mut contract = sample_contract(seed);
                 contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), contract_storage_balance);
+                if token_registered {
+                    register_sample_token(&mut contract, &token);
+                }
+                // fees already accrued by other games in the same token
+                let accrued_fees = 10;
+                contract.nft_balance.insert(&token, &accrued_fees);
+                contract.owner_balance.insert(&token, &accrued_fees);
+                let taken_game_id = "taken-game".to_string();
+                contract.games.insert(&taken_game_id, &PartneredGame {
+                    partner_owner: partner_owner.clone(),
+                    nft_contract: nft_contract.clone(),
+                    bet_payment_adjustment: 0,
+                    max_bet: 0,
+                    min_bet: 0,
+                    max_odds: 0,
+                    min_odds: 0,
+                    ..test_game(&token)
+                });
 
-                assert!(!contract.games.contains_key(&nft_contract));
-
-                contract.create_new_partner(
-                    partner_owner.clone(),
-                    nft_contract.clone(),
-                    token.clone(),
-                    params.0,
-                    params.1,
-                    params.2,
-                    params.3,
-                    params.4,
-                    params.5,
-                    params.6,
-                    params.7,
-                    params.8,
-                );
+                let create = |contract: &mut Contract, game_id: Option<GameId>| {
+                    contract.create_new_partner(
+                        game_id,
+                        partner_owner.clone(),
+                        nft_contract.clone(),
+                        token.clone(),
+                        params.0,
+                        params.1,
+                        params.2,
+                        params.3,
+                        params.4,
+                        params.5,
+                        params.6,
+                        params.7,
+                        None,
+                        params.8,
+                        params.9,
+                        None,
+                        params.10,
+                        params.11,
+                        params.12,
+                        params.13,
+                        params.14,
+                    )
+                };
 
-                assert!(contract.games.contains_key(&nft_contract));
+                let created_id = create(&mut contract, game_id.map(|id| id.to_string()));
+                assert_eq!(created_id, game_id.unwrap_or("0"));
+                let game = contract.games.get(&created_id).unwrap();
+                assert_eq!(game.nft_contract, nft_contract);
+                assert_eq!(game.jackpot_trigger, params.10.unwrap_or(DEFAULT_JACKPOT_TRIGGER));
+                assert_eq!(game.max_streak, params.11.unwrap_or(DEFAULT_MAX_STREAK));
+                assert_eq!(game.withdrawal_delay, params.12.map(|v| v.0).unwrap_or(DEFAULT_WITHDRAWAL_DELAY));
+                assert_eq!(game.max_exposure, params.13.map(|v| v.0).unwrap_or(FRACTIONAL_BASE));
+                assert_eq!(game.drawdown_limits, params.14.unwrap_or_default());
+                assert_eq!(game.jackpot_balance, 0);
+                assert_eq!(contract.nft_balance.get(&token), Some(accrued_fees));
+                assert_eq!(contract.owner_balance.get(&token), Some(accrued_fees));
 
+                // the same collection can run several games
+                let next_id = create(&mut contract, None);
+                let expected_next_id = if game_id.is_some() { "0" } else { "1" };
+                assert_eq!(next_id, expected_next_id);
+                assert_eq!(contract.games.get(&next_id).unwrap().nft_contract, nft_contract);
             }
         }
 
@@ -439,7 +687,9 @@ mod tests {
                 SIGNER_ACCOUNT.to_string(),
                 1,
                 0,
-                (U128(0), U128(0), U128(0), U128(0), U128(0), 0, 0, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(0), U128(0), 0, 0, None, U128(0), U128(0), None, None, None, None, None),
                 Some(ERR_006.to_string()),
             ),
             // 2. Assert that caller deposits one yoctoNear
@@ -447,7 +697,9 @@ mod tests {
                 OWNER_ACCOUNT.to_string(),
                 0,
                 0,
-                (U128(0), U128(0), U128(0), U128(0), U128(0), 0, 0, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(0), U128(0), 0, 0, None, U128(0), U128(0), None, None, None, None, None),
                 Some("Requires attached deposit of exactly 1 yoctoNEAR".to_string()),
             ),
             // 3. Assert that contract has storage paid for new game
@@ -455,7 +707,9 @@ mod tests {
                 OWNER_ACCOUNT.to_string(),
                 1,
                 0,
-                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
                 Some(ERR_101.to_string()),
             ),
             // 4. Assert data validations
@@ -464,7 +718,9 @@ mod tests {
                 OWNER_ACCOUNT.to_string(),
                 1,
                 0,
-                (U128(0), U128(0), U128(0), U128(100), U128(101), 2, 1, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(101), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
                 Some(ERR_206.to_string()),
             ),
             //    b. max_odds > min_odds
@@ -472,58 +728,235 @@ mod tests {
                 OWNER_ACCOUNT.to_string(),
                 1,
                 0,
-                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 3, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 3, None, U128(0), U128(0), None, None, None, None, None),
                 Some(ERR_206.to_string()),
             ),
-            //    c. fees <= FRACTION_BASE 
+            //    d. odds_precision in range and odds limits inside it
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, Some(MAX_ODDS_PRECISION + 1), U128(0), U128(0), None, None, None, None, None),
+                Some(ERR_207.to_string()),
+            ),
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 9_999, 1, Some(9_999), U128(0), U128(0), None, None, None, None, None),
+                Some(ERR_208.to_string()),
+            ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
                 1_000 * ONE_NEAR,
-                (U128(FRACTIONAL_BASE + 1), U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 0, None, U128(0), U128(0), None, None, None, None, None),
+                Some(ERR_208.to_string()),
+            ),
+            //    c. fees <= FRACTION_BASE, also all of them summed 
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(FRACTIONAL_BASE + 1), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
                 1_000 * ONE_NEAR,
-                (U128(0), U128(FRACTIONAL_BASE + 1), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(FRACTIONAL_BASE + 1), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
                 1_000 * ONE_NEAR,
-                (U128(0), U128(0), U128(FRACTIONAL_BASE + 1), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(FRACTIONAL_BASE + 1), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
                 1_000 * ONE_NEAR,
-                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(FRACTIONAL_BASE + 1)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(FRACTIONAL_BASE + 1), None, None, None, None, None),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
                 1_000 * ONE_NEAR,
-                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, U128(FRACTIONAL_BASE + 1), U128(0)),
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(FRACTIONAL_BASE + 1), U128(0), None, None, None, None, None),
                 Some(ERR_205.to_string()),
             ),
-            // 5. Insert new game into LookupMap
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
                 1_000 * ONE_NEAR,
-                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                true,
+                None,
+                (U128(FRACTIONAL_BASE / 4 + 1), U128(0), U128(FRACTIONAL_BASE / 4), U128(100), U128(10), 2, 1, None, U128(FRACTIONAL_BASE / 4), U128(FRACTIONAL_BASE / 4), None, None, None, None, None),
+                Some(ERR_205.to_string()),
+            ),
+            //    e. jackpot_trigger > 1
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), Some(1), None, None, None, None),
+                Some(ERR_212.to_string()),
+            ),
+            //    f. max_streak > 0
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, Some(0), None, None, None),
+                Some(ERR_213.to_string()),
+            ),
+            //    g. withdrawal_delay <= MAX_WITHDRAWAL_DELAY
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, Some(U64(MAX_WITHDRAWAL_DELAY + 1)), None, None),
+                Some(ERR_214.to_string()),
+            ),
+            //    h. max_exposure between 1 and FRACTIONAL_BASE
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, Some(U128(0)), None),
+                Some(ERR_215.to_string()),
+            ),
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, Some(U128(FRACTIONAL_BASE + 1)), None),
+                Some(ERR_215.to_string()),
+            ),
+            //    i. drawdown limits with a max_window_loss have a loss_window
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, Some(DrawdownLimits { max_window_loss: 100, loss_window: 0, max_block_loss: 0 })),
+                Some(ERR_216.to_string()),
+            ),
+            // 5. Assert that game token is whitelisted
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                false,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
+                Some(ERR_501.to_string()),
+            ),
+            // 6. Assert custom game id is a valid slug
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                Some("Not A Slug"),
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
+                Some(ERR_302.to_string()),
+            ),
+            //    a. numeric ids are reserved for auto incremented games
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                Some("42"),
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
+                Some(ERR_303.to_string()),
+            ),
+            // 7. Assert game id is not taken
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                Some("taken-game"),
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
+                Some(ERR_003.to_string()),
+            ),
+            // 8. Insert new game into LookupMap under custom or auto incremented id
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                Some("my-game_1"),
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
+                None,
+            ),
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), None, None, None, None, None),
+                None,
+            ),
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 9_800, 100, Some(MAX_ODDS_PRECISION), U128(0), U128(0), None, None, None, None, None),
+                None,
+            ),
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                1_000 * ONE_NEAR,
+                true,
+                None,
+                (U128(0), U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0), Some(2), Some(1), Some(U64(3_600_000_000_000)), Some(U128(2_000)), Some(DrawdownLimits { max_window_loss: 100, loss_window: 60, max_block_loss: 50 })),
                 None,
             ),
         ];
 
         let mut counter = 0;
         IntoIterator::into_iter(test_cases).for_each(|v| {
-            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, v.5, counter), v.6);
             counter += 1;
 
         });
@@ -537,15 +970,17 @@ mod tests {
     /// 4. Assert data validations
     ///    a. max_bet > min_bet
     ///    b. max_odds > min_odds
-    ///    c. fees <= FRACTION_BASE 
+    ///    c. fees <= FRACTION_BASE, also all of them summed, also all of them summed 
+    ///    d. odds_precision in range and odds limits inside it
     /// 5. Insert new game into LookupMap
     #[test]
+    #[allow(clippy::type_complexity)]
     fn test_alter_partner() {
 
         fn closure_generator(
             signer: AccountId,
             deposit: u128,
-            params: (Option<String>, U128, bool, U128, U128, U128, U128, u8, u8, U128, U128),
+            params: (Option<String>, U128, bool, U128, U128, U128, U128, u32, u32, Option<u32>, U128, U128),
             seed: u128,
         ) -> impl FnOnce() {
             move || {
@@ -560,20 +995,11 @@ mod tests {
 
                 let game_settings = PartneredGame {
                     partner_owner: partner_owner.clone(),
-                    blocked: false,
-                    house_funds: 0,
-                    partner_token: token,
-                    partner_fee: 0,
-                    partner_balance: 0,
-        
                     bet_payment_adjustment: 10_000,
-                    house_fee: 0,
                     max_bet: 0,
                     min_bet: 0,
-                    max_odds: 200,
                     min_odds: 10,
-                    nft_fee: 0,
-                    owner_fee: 0
+                    ..test_game(&token)
                 };
                 contract.games.insert(&nft_contract, &game_settings);
 
@@ -595,10 +1021,18 @@ mod tests {
                     params.7,
                     params.8,
                     params.9,
-                    params.10
+                    None,
+                    params.10,
+                    params.11,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
                 );
 
-                assert!(contract.games.contains_key(&nft_contract));
+                assert!(contract.games.get(&nft_contract).is_some());
 
             }
         }
@@ -608,21 +1042,21 @@ mod tests {
             (
                 SIGNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(0), U128(0), U128(0), U128(0), 0, 0, U128(0), U128(0)),
+                (None, U128(0), false, U128(0), U128(0), U128(0), U128(0), 0, 0, None, U128(0), U128(0)),
                 Some(ERR_006.to_string()),
             ),
             // 2. Assert that caller deposits one yoctoNear
             (
                 OWNER_ACCOUNT.to_string(),
                 0,
-                (None, U128(0), false, U128(0), U128(0), U128(0), U128(0), 0, 0, U128(0), U128(0)),
+                (None, U128(0), false, U128(0), U128(0), U128(0), U128(0), 0, 0, None, U128(0), U128(0)),
                 Some("Requires attached deposit of exactly 1 yoctoNEAR".to_string()),
             ),
             // 3. Assert that game exists
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (Some("Other".to_string()), U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                (Some("Other".to_string()), U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0)),
                 Some(ERR_002.to_string()),
             ),
             // 4. Assert data validations
@@ -630,52 +1064,77 @@ mod tests {
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(0), U128(0), U128(100), U128(101), 2, 1, U128(0), U128(0)),
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(101), 2, 1, None, U128(0), U128(0)),
                 Some(ERR_206.to_string()),
             ),
             //    b. max_odds > min_odds
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 3, U128(0), U128(0)),
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 3, None, U128(0), U128(0)),
                 Some(ERR_206.to_string()),
             ),
-            //    c. fees <= FRACTION_BASE 
+            //    d. odds_precision in range and odds limits inside it
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, Some(1), U128(0), U128(0)),
+                Some(ERR_207.to_string()),
+            ),
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 256, 1, None, U128(0), U128(0)),
+                Some(ERR_208.to_string()),
+            ),
+            //    c. fees <= FRACTION_BASE, also all of them summed 
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                (None, U128(FRACTIONAL_BASE + 1), false, U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0)),
+                Some(ERR_205.to_string()),
+            ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(FRACTIONAL_BASE + 1), false, U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                (None, U128(0), false, U128(FRACTIONAL_BASE + 1), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0)),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(FRACTIONAL_BASE + 1), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                (None, U128(0), false, U128(0), U128(FRACTIONAL_BASE + 1), U128(100), U128(10), 2, 1, None, U128(0), U128(0)),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(0), U128(FRACTIONAL_BASE + 1), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(FRACTIONAL_BASE + 1)),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(FRACTIONAL_BASE + 1)),
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(FRACTIONAL_BASE + 1), U128(0)),
                 Some(ERR_205.to_string()),
             ),
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, U128(FRACTIONAL_BASE + 1), U128(0)),
+                (None, U128(FRACTIONAL_BASE / 4 + 1), false, U128(0), U128(FRACTIONAL_BASE / 4), U128(100), U128(10), 2, 1, None, U128(FRACTIONAL_BASE / 4), U128(FRACTIONAL_BASE / 4)),
                 Some(ERR_205.to_string()),
             ),
             // 5. Insert new game into LookupMap
             (
                 OWNER_ACCOUNT.to_string(),
                 1,
-                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, U128(0), U128(0)),
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 2, 1, None, U128(0), U128(0)),
+                None,
+            ),
+            (
+                OWNER_ACCOUNT.to_string(),
+                1,
+                (None, U128(0), false, U128(0), U128(0), U128(100), U128(10), 9_800, 100, Some(MAX_ODDS_PRECISION), U128(0), U128(0)),
                 None,
             ),
         ];
@@ -688,4 +1147,109 @@ mod tests {
         });
     }
 
+    /// register_token
+    /// Method must:
+    /// 1. Assert caller is owner
+    /// 2. Assert that caller deposits one yoctoNear
+    /// 3. Assert token is not registered yet
+    /// 4. Send promise to fetch token metadata
+    #[test]
+    fn test_register_token() {
+        fn closure_generator(
+            signer: AccountId,
+            deposit: u128,
+            already_registered: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let token = format!("{}-token.testnet", seed);
+                let context = get_context(vec![], false, deposit, 1_000 * ONE_NEAR, signer);
+                testing_env!(context);
+                let mut contract = sample_contract(seed);
+                if already_registered {
+                    register_sample_token(&mut contract, &token);
+                }
+
+                contract.register_token(token);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert caller is owner
+            (SIGNER_ACCOUNT.to_string(), 1, false, Some(ERR_006.to_string())),
+            // 2. Assert that caller deposits one yoctoNear
+            (
+                OWNER_ACCOUNT.to_string(),
+                0,
+                false,
+                Some("Requires attached deposit of exactly 1 yoctoNEAR".to_string()),
+            ),
+            // 3. Assert token is not registered yet
+            (OWNER_ACCOUNT.to_string(), 1, true, Some(ERR_502.to_string())),
+            // 4. Send promise to fetch token metadata
+            (OWNER_ACCOUNT.to_string(), 1, false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+        });
+    }
+
+    /// remove_token
+    /// Method must:
+    /// 1. Assert caller is owner
+    /// 2. Assert that caller deposits one yoctoNear
+    /// 3. Assert token is registered
+    /// 4. Remove token from whitelist
+    #[test]
+    fn test_remove_token() {
+        fn closure_generator(
+            signer: AccountId,
+            deposit: u128,
+            is_registered: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let token = format!("{}-token.testnet", seed);
+                let context = get_context(vec![], false, deposit, 1_000 * ONE_NEAR, signer);
+                testing_env!(context);
+                let mut contract = sample_contract(seed);
+                contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1_000 * ONE_NEAR);
+                if is_registered {
+                    register_sample_token(&mut contract, &token);
+                }
+                let mut contract_account = contract.internal_get_account(&CONTRACT_ACCOUNT.to_string()).unwrap();
+                contract_account.storage_used = 1000;
+                contract.internal_update_account(&CONTRACT_ACCOUNT.to_string(), &contract_account);
+
+                contract.remove_token(token.clone());
+
+                assert!(contract.tokens.get(&token).is_none());
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert caller is owner
+            (SIGNER_ACCOUNT.to_string(), 1, true, Some(ERR_006.to_string())),
+            // 2. Assert that caller deposits one yoctoNear
+            (
+                OWNER_ACCOUNT.to_string(),
+                0,
+                true,
+                Some("Requires attached deposit of exactly 1 yoctoNEAR".to_string()),
+            ),
+            // 3. Assert token is registered
+            (OWNER_ACCOUNT.to_string(), 1, false, Some(ERR_501.to_string())),
+            // 4. Remove token from whitelist
+            (OWNER_ACCOUNT.to_string(), 1, true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+        });
+    }
 }
@@ -1,8 +1,10 @@
 use crate::*;
+use crate::events::*;
+use crate::house_withdrawal::MAX_QUEUED_WITHDRAWALS;
 
 #[near_bindgen]
 impl Contract {
-    pub fn retrieve_partner_balance(&mut self, game_code: String) -> Promise {
+    pub fn retrieve_partner_balance(&mut self, game_code: GameId) -> Promise {
         let mut game = self.internal_get_game(&game_code).expect(ERR_003);
         assert!(
             game.partner_owner == env::predecessor_account_id(),
@@ -13,10 +15,21 @@ impl Contract {
         let balance = game.partner_balance;
         game.partner_balance = 0;
         self.internal_update_game(&game_code, &game);
+        ContractEvent::Withdrawal(WithdrawalData {
+            kind: WithdrawalKind::Partner,
+            receiver_id: &game.partner_owner,
+            token_contract: &game.partner_token,
+            amount: U128(balance),
+            game_code: Some(&game_code),
+        })
+        .emit();
         self.safe_transfer_project(game.partner_token, balance, game_code, game.partner_owner)
     }
 
-    pub fn retrieve_house_funds(&mut self, game_code: String, quantity: U128) -> Promise {
+    //queues a withdrawal of house funds, executable through execute_house_withdrawal once
+    //the game's withdrawal_delay has passed. Queued funds stop covering bets at once so that
+    //players get notice before the bankroll shrinks. Returns the withdrawal id
+    pub fn retrieve_house_funds(&mut self, game_code: GameId, quantity: U128) -> U128 {
         let mut game = self.internal_get_game(&game_code).expect(ERR_003);
         assert!(
             game.partner_owner == env::predecessor_account_id(),
@@ -26,19 +39,525 @@ impl Contract {
 
         let balance = game.house_funds;
         assert!(balance >= quantity.0, "{}", ERR_401);
+        let mut withdrawals = self.house_withdrawals.get(&game_code).unwrap_or_default();
+        assert!(withdrawals.len() < MAX_QUEUED_WITHDRAWALS, "{}", ERR_306);
 
+        // queued withdrawals are paid for by the contract like the games they belong to
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        // once liquidity is provided the partner can only take out what their shares are worth
+        if game.total_shares > 0 {
+            let burned = game.shares_for_withdrawal(quantity.0);
+            assert!(burned <= game.partner_shares, "{}", ERR_901);
+            game.partner_shares -= burned;
+            game.total_shares -= burned;
+        }
         game.house_funds -= quantity.0;
         self.internal_update_game(&game_code, &game);
-        self.safe_transfer_house_funds(game.partner_token, balance, game_code, game.partner_owner)
+
+        let withdrawal = HouseWithdrawal {
+            withdrawal_id: self.next_id,
+            provider: None,
+            amount: quantity.0,
+            unlocks_at: env::block_timestamp() + game.withdrawal_delay,
+        };
+        self.next_id += 1;
+        withdrawals.push(withdrawal.clone());
+        self.house_withdrawals.insert(&game_code, &withdrawals);
+        ContractEvent::HouseWithdrawalQueued(HouseWithdrawalData {
+            game_code: &game_code,
+            withdrawal: &withdrawal,
+        })
+        .emit();
+
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+        U128(withdrawal.withdrawal_id)
+    }
+
+    //lifts the block of a game tripped by its circuit breaker and restarts the count of house
+    //losses against its drawdown_limits. Called by the owner it also lifts the owner's block
+    pub fn unblock_game(&mut self, game_code: GameId) {
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        let account_id = env::predecessor_account_id();
+        let is_owner = account_id == self.owner_id;
+        assert!(account_id == game.partner_owner || is_owner, "{}", ERR_307);
+        assert!(game.is_blocked(), "{}", ERR_308);
+        assert!(!game.blocked || is_owner, "{}", ERR_309);
+
+        game.blocked = false;
+        game.breaker = None;
+        game.drawdown.reset();
+        self.internal_update_game(&game_code, &game);
+        ContractEvent::GameUnblocked(GameUnblockedData {
+            game_code: &game_code,
+            account_id: &account_id,
+        })
+        .emit();
+    }
+
+    //sends a queued house withdrawal to the partner once its delay has passed. Liquidity
+    //withdrawals go through execute_liquidity_withdrawal
+    pub fn execute_house_withdrawal(&mut self, game_code: GameId, withdrawal_id: U128) -> Promise {
+        let game = self.internal_get_game(&game_code).expect(ERR_002);
+        assert!(
+            game.partner_owner == env::predecessor_account_id(),
+            "{}",
+            ERR_004
+        );
+        let withdrawal = self.internal_remove_house_withdrawal(&game_code, withdrawal_id.0);
+        assert!(env::block_timestamp() >= withdrawal.unlocks_at, "{}", ERR_305);
+
+        ContractEvent::HouseWithdrawalExecuted(HouseWithdrawalData {
+            game_code: &game_code,
+            withdrawal: &withdrawal,
+        })
+        .emit();
+        ContractEvent::Withdrawal(WithdrawalData {
+            kind: WithdrawalKind::HouseFunds,
+            receiver_id: &game.partner_owner,
+            token_contract: &game.partner_token,
+            amount: U128(withdrawal.amount),
+            game_code: Some(&game_code),
+        })
+        .emit();
+        self.safe_transfer_house_funds(game.partner_token, withdrawal.amount, game_code, game.partner_owner)
+    }
+
+    //cancels a queued house withdrawal, its amount goes back to the house funds
+    pub fn cancel_house_withdrawal(&mut self, game_code: GameId, withdrawal_id: U128) {
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        assert!(
+            game.partner_owner == env::predecessor_account_id(),
+            "{}",
+            ERR_004
+        );
+        let withdrawal = self.internal_remove_house_withdrawal(&game_code, withdrawal_id.0);
+        game.return_partner_funds(withdrawal.amount);
+        self.internal_update_game(&game_code, &game);
+        ContractEvent::HouseWithdrawalCancelled(HouseWithdrawalData {
+            game_code: &game_code,
+            withdrawal: &withdrawal,
+        })
+        .emit();
+    }
+}
+
+impl Contract {
+    // removes a queued withdrawal of the partner, releasing its storage to the contract
+    pub fn internal_remove_house_withdrawal(&mut self, game_code: &GameId, withdrawal_id: u128) -> HouseWithdrawal {
+        let mut withdrawals = self.house_withdrawals.get(game_code).unwrap_or_default();
+        let index = withdrawals
+            .iter()
+            .position(|withdrawal| withdrawal.withdrawal_id == withdrawal_id)
+            .expect(ERR_304);
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).expect(ERR_001);
+        let initial_storage = env::storage_usage();
+
+        let withdrawal = withdrawals.remove(index);
+        if withdrawals.is_empty() {
+            self.house_withdrawals.remove(game_code);
+        } else {
+            self.house_withdrawals.insert(game_code, &withdrawals);
+        }
+
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+        withdrawal
     }
 }
 
 // methods to be called through token receiver
 impl Contract {
-    pub fn fund_game_house(&mut self, token_contract: AccountId, amount: u128, game_code: String) {
+    pub fn fund_game_house(
+        &mut self,
+        sender_id: AccountId,
+        token_contract: AccountId,
+        amount: u128,
+        game_code: GameId,
+    ) {
         let mut game = self.internal_get_game(&game_code).expect(ERR_003);
         assert_eq!(game.partner_token, token_contract, "{}", ERR_301);
         game.house_funds += amount;
         self.internal_update_game(&game_code, &game);
+        ContractEvent::HouseFunded(HouseFundedData {
+            game_code: &game_code,
+            sender_id: &sender_id,
+            token_contract: &token_contract,
+            amount: U128(amount),
+        })
+        .emit();
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    const HOUSE_FUNDS: u128 = 1_000;
+    const DELAY: u64 = 1_000;
+    const QUEUED_AT: u64 = 500;
+
+    fn partner(seed: u128) -> AccountId {
+        format!("partner-{}.testnet", seed)
+    }
+
+    fn set_context(account_id: &AccountId, block_timestamp: u64) {
+        let mut context = get_context(vec![], false, 0, 1000 * ONE_NEAR, account_id.clone());
+        context.block_timestamp = block_timestamp;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+    }
+
+    // creates a fee-less even odds game funded with HOUSE_FUNDS by its partner
+    fn sample_partner_contract(seed: u128) -> (Contract, GameId) {
+        let token = format!("{}-token.testnet", seed);
+        let game_id = "house".to_string();
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.game_count = seed;
+        contract.next_id = seed;
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &token);
+        insert_test_game(
+            &mut contract,
+            &game_id,
+            &PartneredGame {
+                partner_owner: partner(seed),
+                max_bet: 1_000,
+                withdrawal_delay: DELAY,
+                ..test_game(&token)
+            },
+        );
+        contract.fund_game_house(partner(seed), token, HOUSE_FUNDS, game_id.clone());
+        (contract, game_id)
     }
-}
\ No newline at end of file
+
+    /// retrieve_house_funds
+    /// method must:
+    /// 1. Assert caller is the game's partner
+    /// 2. Assert house_funds cover the quantity
+    /// 3. Assert the game has less than MAX_QUEUED_WITHDRAWALS queued withdrawals
+    /// 4. Queue the withdrawal until the game's withdrawal_delay has passed
+    /// 5. Stop counting queued funds toward the cover check at once
+    #[test]
+    fn test_retrieve_house_funds() {
+        fn closure_generator(caller_is_partner: bool, quantity: u128, queued: usize, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id) = sample_partner_contract(seed);
+                set_context(&partner(seed), QUEUED_AT);
+                for _ in 0..queued {
+                    contract.retrieve_house_funds(game_id.clone(), U128(1));
+                }
+                let caller = if caller_is_partner { partner(seed) } else { SIGNER_ACCOUNT.to_string() };
+                set_context(&caller, QUEUED_AT);
+                let choice = BetChoice::new(128, None, None);
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(contract.internal_check_cover(&game, 400, &choice), Ok(()));
+
+                let withdrawal_id = contract.retrieve_house_funds(game_id.clone(), U128(quantity));
+
+                // 4. Queue the withdrawal until the game's withdrawal_delay has passed
+                assert_eq!(withdrawal_id, U128(seed + queued as u128));
+                let withdrawals = contract.house_withdrawals(game_id.clone());
+                assert_eq!(
+                    withdrawals.last(),
+                    Some(&HouseWithdrawal {
+                        provider: None,
+                        withdrawal_id: withdrawal_id.0,
+                        amount: quantity,
+                        unlocks_at: QUEUED_AT + DELAY,
+                    })
+                );
+                // 5. Stop counting queued funds toward the cover check at once
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(game.house_funds, HOUSE_FUNDS - queued as u128 - quantity);
+                assert_eq!(contract.internal_check_cover(&game, 400, &choice), Err(ERR_407.to_string()));
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert caller is the game's partner
+            (false, 500, 0, Some(ERR_004.to_string())),
+            // 2. Assert house_funds cover the quantity
+            (true, HOUSE_FUNDS + 1, 0, Some(ERR_401.to_string())),
+            // 3. Assert the game has less than MAX_QUEUED_WITHDRAWALS queued withdrawals
+            (true, 500, MAX_QUEUED_WITHDRAWALS, Some(ERR_306.to_string())),
+            // 4. Queue the withdrawal until the game's withdrawal_delay has passed
+            // 5. Stop counting queued funds toward the cover check at once
+            (true, 500, 0, None),
+            (true, 500, MAX_QUEUED_WITHDRAWALS - 1, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// execute_house_withdrawal
+    /// method must:
+    /// 1. Assert caller is the game's partner
+    /// 2. Assert the withdrawal is queued
+    /// 3. Assert the withdrawal_delay has passed
+    /// 4. Remove the withdrawal from the queue and send it
+    #[test]
+    fn test_execute_house_withdrawal() {
+        fn closure_generator(caller_is_partner: bool, known_id: bool, block_timestamp: u64, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id) = sample_partner_contract(seed);
+                set_context(&partner(seed), QUEUED_AT);
+                let withdrawal_id = contract.retrieve_house_funds(game_id.clone(), U128(400));
+                contract.retrieve_house_funds(game_id.clone(), U128(100));
+
+                let caller = if caller_is_partner { partner(seed) } else { SIGNER_ACCOUNT.to_string() };
+                set_context(&caller, block_timestamp);
+                let id = if known_id { withdrawal_id } else { U128(withdrawal_id.0 + 10) };
+                contract.execute_house_withdrawal(game_id.clone(), id);
+
+                // 4. Remove the withdrawal from the queue and send it
+                let withdrawals = contract.house_withdrawals(game_id.clone());
+                assert_eq!(withdrawals.len(), 1);
+                assert_eq!(withdrawals[0].amount, 100);
+                assert_eq!(contract.games.get(&game_id).unwrap().house_funds, HOUSE_FUNDS - 500);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert caller is the game's partner
+            (false, true, QUEUED_AT + DELAY, Some(ERR_004.to_string())),
+            // 2. Assert the withdrawal is queued
+            (true, false, QUEUED_AT + DELAY, Some(ERR_304.to_string())),
+            // 3. Assert the withdrawal_delay has passed
+            (true, true, QUEUED_AT + DELAY - 1, Some(ERR_305.to_string())),
+            // 4. Remove the withdrawal from the queue and send it
+            (true, true, QUEUED_AT + DELAY, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// cancel_house_withdrawal
+    /// method must:
+    /// 1. Assert caller is the game's partner
+    /// 2. Assert the withdrawal is queued
+    /// 3. Return the amount to house_funds, buying back partner shares on games with liquidity providers
+    #[test]
+    fn test_cancel_house_withdrawal() {
+        fn closure_generator(caller_is_partner: bool, known_id: bool, with_provider: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id) = sample_partner_contract(seed);
+                if with_provider {
+                    let provider = "provider.testnet".to_string();
+                    contract.internal_deposit_storage_account(&provider, 1000 * ONE_NEAR);
+                    let token = contract.games.get(&game_id).unwrap().partner_token;
+                    contract.provide_house_liquidity(provider, token, 1_000, game_id.clone());
+                }
+                set_context(&partner(seed), QUEUED_AT);
+                let withdrawal_id = contract.retrieve_house_funds(game_id.clone(), U128(400));
+                let queued = contract.games.get(&game_id).unwrap();
+
+                let caller = if caller_is_partner { partner(seed) } else { SIGNER_ACCOUNT.to_string() };
+                set_context(&caller, QUEUED_AT);
+                let id = if known_id { withdrawal_id } else { U128(withdrawal_id.0 + 10) };
+                contract.cancel_house_withdrawal(game_id.clone(), id);
+
+                // 3. Return the amount to house_funds, buying back partner shares on games with liquidity providers
+                assert!(contract.house_withdrawals(game_id.clone()).is_empty());
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(game.house_funds, queued.house_funds + 400);
+                if with_provider {
+                    assert_eq!(game.partner_shares, HOUSE_FUNDS);
+                    assert_eq!(game.total_shares, HOUSE_FUNDS + 1_000);
+                } else {
+                    assert_eq!(game.total_shares, 0);
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert caller is the game's partner
+            (false, true, false, Some(ERR_004.to_string())),
+            // 2. Assert the withdrawal is queued
+            (true, false, false, Some(ERR_304.to_string())),
+            // 3. Return the amount to house_funds, buying back partner shares on games with liquidity providers
+            (true, true, false, None),
+            (true, true, true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    // plays likely winning bets on a game whose breaker trips on any house loss until it
+    // is blocked, returns the player
+    // makes any house loss trip the breaker and funds a player to bet against it
+    fn arm_breaker(contract: &mut Contract, game_id: &GameId, seed: u128) -> AccountId {
+        let player = format!("player-{}.testnet", seed);
+        let mut game = contract.games.get(game_id).unwrap();
+        game.drawdown_limits = DrawdownLimits {
+            max_window_loss: 0,
+            loss_window: 0,
+            max_block_loss: 1,
+        };
+        contract.games.insert(game_id, &game);
+        contract.internal_deposit_storage_account(&player, 1000 * ONE_NEAR);
+        let mut account = contract.internal_get_account(&player).unwrap();
+        account.balances.insert(&game.partner_token, &10_000);
+        contract.internal_update_account(&player, &account);
+
+        set_context(&player, QUEUED_AT);
+        player
+    }
+
+    fn trip_breaker(contract: &mut Contract, game_id: &GameId, seed: u128) -> AccountId {
+        let player = arm_breaker(contract, game_id, seed);
+        while contract.games.get(game_id).unwrap().breaker.is_none() {
+            contract.play(game_id.clone(), U128(100), 200, "_bet_type".to_string(), None, None, None);
+        }
+        player
+    }
+
+    /// circuit breaker
+    /// play must:
+    /// 1. Block the game with an event once a bet takes house losses past its drawdown_limits
+    /// 2. Reject bets on a blocked game before rolling them
+    #[test]
+    fn test_circuit_breaker() {
+        let (mut contract, game_id) = sample_partner_contract(0);
+        let player = trip_breaker(&mut contract, &game_id, 0);
+
+        // 1. Block the game with an event once a bet takes house losses past its drawdown_limits
+        let game = contract.games.get(&game_id).unwrap();
+        assert_eq!(game.breaker, Some(DrawdownBreach::Block));
+        assert!(!game.blocked);
+        assert!(game.drawdown.block_loss > game.drawdown_limits.max_block_loss);
+        let logs = near_sdk::test_utils::get_logs();
+        let blocked_log = logs.iter().find(|log| log.contains("\"event\":\"game_blocked\"")).unwrap();
+        assert!(blocked_log.contains("\"breach\":\"block\""));
+
+        // 2. Reject bets on a blocked game before rolling them
+        let bet_count = contract.game_count;
+        let credits = contract.get_credits(game.partner_token.clone(), player.clone());
+        let rejected = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.play(game_id.clone(), U128(100), 200, "_bet_type".to_string(), None, None, None)
+        }));
+        assert!(rejected.is_err());
+        assert_eq!(contract.game_count, bet_count);
+        assert_eq!(contract.get_credits(game.partner_token.clone(), player), credits);
+        let reason = contract.internal_check_bet(&game, 1_000, 100, &BetChoice::new(200, None, None));
+        assert_eq!(reason, Err(ERR_418.to_string()));
+    }
+
+    /// circuit breaker
+    /// play_batch must:
+    /// 1. Stop at the bet that trips the breaker, keeping the block and the bets settled before it
+    #[test]
+    fn test_circuit_breaker_batch() {
+        // first bet id of a batch whose first house loss comes after a few house wins
+        const FIRST_BET_ID: u128 = 5;
+        let (mut contract, game_id) = sample_partner_contract(0);
+        let player = arm_breaker(&mut contract, &game_id, 0);
+        contract.game_count = FIRST_BET_ID;
+        let bets = (0..MAX_BATCH_BETS)
+            .map(|_| BatchBet {
+                bet_size: U128(100),
+                odds: 200,
+                direction: None,
+                target_multiplier: None,
+            })
+            .collect();
+        let receipts = contract.play_batch(game_id.clone(), bets);
+
+        // 1. Stop at the bet that trips the breaker, keeping the block and the bets settled before it
+        assert!(receipts.len() > 1 && receipts.len() < MAX_BATCH_BETS);
+        let (tripping, settled) = receipts.split_last().unwrap();
+        assert!(settled.iter().all(|receipt| receipt.payout == 0));
+        assert!(tripping.payout > 0);
+        let game = contract.games.get(&game_id).unwrap();
+        assert_eq!(game.breaker, Some(DrawdownBreach::Block));
+        assert_eq!(contract.game_count, FIRST_BET_ID + receipts.len() as u128);
+        let credits = 10_000 - 100 * receipts.len() as u128 + tripping.payout;
+        assert_eq!(contract.get_credits(game.partner_token, player), U128(credits));
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs.iter().any(|log| log.contains("\"event\":\"game_blocked\"")));
+    }
+
+    /// unblock_game
+    /// method must:
+    /// 1. Assert caller is the game's partner or the owner
+    /// 2. Assert the game is blocked
+    /// 3. Assert only the owner lifts a block set by the owner
+    /// 4. Let bets in again, restarting the count of house losses
+    #[test]
+    fn test_unblock_game() {
+        fn closure_generator(caller: &'static str, tripped: bool, owner_blocked: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id) = sample_partner_contract(seed);
+                let player = if tripped {
+                    trip_breaker(&mut contract, &game_id, seed)
+                } else {
+                    arm_breaker(&mut contract, &game_id, seed)
+                };
+                if owner_blocked {
+                    let mut game = contract.games.get(&game_id).unwrap();
+                    game.blocked = true;
+                    contract.games.insert(&game_id, &game);
+                }
+
+                let caller = match caller {
+                    "partner" => partner(seed),
+                    "owner" => OWNER_ACCOUNT.to_string(),
+                    _ => SIGNER_ACCOUNT.to_string(),
+                };
+                set_context(&caller, QUEUED_AT);
+                contract.unblock_game(game_id.clone());
+
+                // 4. Let bets in again, restarting the count of house losses
+                let game = contract.games.get(&game_id).unwrap();
+                assert!(!game.is_blocked());
+                assert_eq!((game.drawdown.window_loss, game.drawdown.block_loss), (0, 0));
+                assert!(near_sdk::test_utils::get_logs()[0].contains("\"event\":\"game_unblocked\""));
+                set_context(&player, QUEUED_AT);
+                contract.play(game_id, U128(100), 200, "_bet_type".to_string(), None, None, None);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert caller is the game's partner or the owner
+            ("other", true, false, Some(ERR_307.to_string())),
+            // 2. Assert the game is blocked
+            ("partner", false, false, Some(ERR_308.to_string())),
+            // 3. Assert only the owner lifts a block set by the owner
+            ("partner", false, true, Some(ERR_309.to_string())),
+            ("partner", true, true, Some(ERR_309.to_string())),
+            // 4. Let bets in again, restarting the count of house losses
+            ("partner", true, false, None),
+            ("owner", true, false, None),
+            ("owner", false, true, None),
+            ("owner", true, true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+}
@@ -1,107 +1,545 @@
 use crate::*;
+use crate::events::*;
+use crate::auto_play::AUTO_BET_GAS;
+use crate::random::random_value;
 
 #[near_bindgen]
 impl Contract {
     pub fn retrieve_credits(&mut self, token_contract: AccountId, amount: U128) -> Promise {
         self.assert_panic_button();
-        let account_id = env::predecessor_account_id();
-        let initial_storage = env::storage_usage();
-        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
-        let current_balance = account.balances.remove(&token_contract).unwrap_or(0);
-        assert!(current_balance >= amount.0, "{}", ERR_401);
-        let new_balance = current_balance - amount.0;
-        if new_balance > 0 {
-            account.balances.insert(&token_contract, &new_balance);
-        }
-        self.internal_update_account_storage_check(&account_id, account, initial_storage);
-        self.safe_transfer_user(token_contract, amount.0, account_id)
+        self.internal_retrieve_credits(env::predecessor_account_id(), token_contract, amount.0)
     }
 
     //plays the game, user can choose the game collection to play within, size of the bet,
     //the odds that they eant to take (the smallet the odds, the greater prize).
+    //odds are the number of winning rolls out of the game's odds_precision, direction picks
+    //whether the bet wins rolling under or over, defaults to under.
+    //crash games ignore odds and pay target_multiplier (base 10e-5) if the crash point reaches it.
     //_bet_type is a dummy param for indexers to display the bet choice the user made, but are
-    //irrelevant for game logic
+    //irrelevant for game logic.
+    //with start_streak a win is held in a streak of the game, to be pressed or collected
     pub fn play(
         &mut self,
-        game_code: AccountId,
+        game_code: GameId,
         bet_size: U128,
-        odds: u8,
+        odds: u32,
         _bet_type: String,
-    ) -> bool {
+        direction: Option<RollDirection>,
+        target_multiplier: Option<U128>,
+        start_streak: Option<bool>,
+    ) -> BetReceipt {
         self.assert_panic_button();
+        let choice = BetChoice::new(odds, direction, target_multiplier);
+        if start_streak.unwrap_or(false) {
+            return self.internal_play_streak(env::predecessor_account_id(), game_code, bet_size.0, choice);
+        }
+        self.internal_play(env::predecessor_account_id(), game_code, bet_size.0, choice)
+    }
+
+    //plays several bets on the same game in a single call. Bets are settled in order, each
+    //one with its own randomness and house cover check, as if play was called once per bet.
+    //account, game, fee balances and receipts are only written once, at the end of the batch.
+    //When a bet trips the game's circuit breaker the batch stops there and returns the
+    //receipts settled so far
+    pub fn play_batch(&mut self, game_code: GameId, bets: Vec<BatchBet>) -> Vec<BetReceipt> {
+        self.assert_panic_button();
+        assert!(!bets.is_empty() && bets.len() <= MAX_BATCH_BETS, "{}", ERR_412);
+        let choices: Vec<(u128, BetChoice)> = bets.iter().map(|bet| (bet.bet_size.0, bet.choice())).collect();
+        self.internal_play_batch(env::predecessor_account_id(), game_code, &choices)
+    }
+
+    //runs a betting strategy without signing every bet. Bets are settled as in play until
+    //max_bets, stop_loss or take_profit are reached, the next bet would be rejected or the
+    //gas left cannot pay for another bet. Returns the receipts and why the run stopped
+    pub fn auto_play(&mut self, game_code: GameId, strategy: AutoPlayStrategy) -> AutoPlayReport {
+        self.assert_panic_button();
+        if let Err(error) = strategy.validate() {
+            panic!("{}", error);
+        }
+        self.internal_auto_play(env::predecessor_account_id(), game_code, &strategy)
+    }
+
+    //first step of a commit-reveal bet. Charges the bet and the game fees right away and
+    //reserves the possible prize from house_funds, but the outcome is only decided by
+    //reveal_bet, using the random seed of a block that did not exist at commit time.
+    //returns the id of the bet to be revealed
+    pub fn commit_bet(
+        &mut self,
+        game_code: GameId,
+        bet_size: U128,
+        odds: u32,
+        _bet_type: String,
+        direction: Option<RollDirection>,
+        target_multiplier: Option<U128>,
+    ) -> U128 {
+        self.assert_panic_button();
+        let choice = BetChoice::new(odds, direction, target_multiplier);
 
-        // check that user has credits
         let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
 
         let mut account = self.internal_get_account(&account_id).expect(ERR_001);
         let mut game = self.internal_get_game(&game_code).expect(ERR_002);
-        let mut credits = account.balances.get(&game.partner_token).unwrap_or(0);
-        assert!(credits >= bet_size.0, "{}", ERR_402);
-        assert!(
-            bet_size.0 >= game.min_bet,
-            "{}. Minimum is {}",
-            ERR_403,
-            game.min_bet
-        );
-        assert!(
-            bet_size.0 <= game.max_bet,
-            "{}. Maximum is {}",
-            ERR_404,
-            game.max_bet
-        );
-        assert!(
-            odds >= game.min_odds,
-            "{}. Minimum is {}",
-            ERR_405,
-            game.min_bet
+        let credits = account.balances.get(&game.partner_token).unwrap_or(0);
+        self.internal_assert_bet(&game, credits, bet_size.0, &choice);
+
+        let fees = self.internal_charge_fees(&game_code, &mut game, bet_size.0);
+        let net_bet = bet_size.0 - fees.total();
+
+        // lock house exposure before the outcome is known
+        let potential_win = game.max_payout(net_bet, &choice);
+        assert!(game.house_funds >= potential_win, "{}", ERR_407);
+        game.house_funds -= potential_win;
+        game.reserved_funds += potential_win;
+
+        let bet_id = self.game_count;
+        self.pending_bets.insert(
+            &bet_id,
+            &PendingBet {
+                account_id: account_id.clone(),
+                game_code: game_code.clone(),
+                bet_size: bet_size.0,
+                net_bet,
+                odds,
+                direction: choice.direction,
+                target_multiplier: choice.target_multiplier,
+                game_parameters: GameParameters::from(&game),
+                potential_win,
+                commit_block: env::block_index(),
+            },
         );
+        self.game_count += 1;
+        ContractEvent::BetCommitted(BetCommittedData {
+            bet_id: U128(bet_id),
+            account_id: &account_id,
+            game_code: &game_code,
+            bet_size,
+            odds,
+            direction: choice.direction,
+            target_multiplier: U128(choice.target_multiplier),
+            potential_win: U128(potential_win),
+        })
+        .emit();
+
+        account
+            .balances
+            .insert(&game.partner_token, &(credits - bet_size.0));
+        account.pending_bets += 1;
+        self.internal_update_game(&game_code, &game);
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        U128(bet_id)
+    }
+
+    //second step of a commit-reveal bet. Can be called by anyone once REVEAL_DELAY_BLOCKS
+    //have passed since commit_bet and until REVEAL_DEADLINE_BLOCKS have, prize is always
+    //credited to the player that committed
+    pub fn reveal_bet(&mut self, bet_id: U128) -> BetReceipt {
+        self.assert_panic_button();
+
+        let bet = self.pending_bets.get(&bet_id.0).expect(ERR_408);
         assert!(
-            odds <= game.max_odds,
-            "{}. Maximum is {}",
-            ERR_406,
-            game.max_bet
+            env::block_index() >= bet.commit_block + REVEAL_DELAY_BLOCKS,
+            "{}. Bet can be revealed from block {}",
+            ERR_409,
+            bet.commit_block + REVEAL_DELAY_BLOCKS
         );
+        let deadline = bet.commit_block + REVEAL_DEADLINE_BLOCKS;
+        assert!(env::block_index() < deadline, "{}. Bet expired at block {}", ERR_419, deadline);
 
-        // charge dev and nft fees
-        let mut net_bet = bet_size.0;
-        let nft_cut = (net_bet * game.nft_fee) / FRACTIONAL_BASE;
-        let owner_cut = (net_bet * game.owner_fee) / FRACTIONAL_BASE;
-        let house_cut = (net_bet * game.house_fee) / FRACTIONAL_BASE;
-        let partner_cut = (net_bet * game.partner_fee) / FRACTIONAL_BASE;
-        net_bet = net_bet - nft_cut - owner_cut - house_cut - partner_cut;
-        let nft_balance = self.nft_balance.get(&game.partner_token).unwrap_or(0);
-        self.nft_balance
-            .insert(&game.partner_token, &(nft_balance + nft_cut));
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(&bet.account_id).expect(ERR_001);
+        let mut game = self.internal_get_game(&bet.game_code).expect(ERR_002);
+        self.pending_bets.remove(&bet_id.0);
+        account.pending_bets -= 1;
 
-        let owner_balance = self.owner_balance.get(&game.partner_token).unwrap_or(0);
-        self.owner_balance
-            .insert(&game.partner_token, &(owner_balance + owner_cut));
-        game.house_funds += house_cut;
-        game.partner_balance += partner_cut;
+        let seed = env::random_seed();
+        let params = &bet.game_parameters;
+        let roll = random_value(&seed, &bet.account_id, bet_id.0, &bet.game_code, params.roll_range());
+        let (outcome, payout) = params.settle(bet.net_bet, &bet.choice(), roll);
+        let (jackpot_won, jackpot_payout) =
+            game.roll_jackpot(&seed, &bet.account_id, bet_id.0, &bet.game_code, params.jackpot_trigger);
 
-        // send off credits
-        credits = credits - bet_size.0;
-        let rand = *env::random_seed().get(0).unwrap();
-        let random_hash = u128::from_be_bytes(
-            env::keccak256(&[rand, (self.game_count % 256) as u8])[0..16]
-                .try_into()
-                .unwrap(),
-        );
-        let rand_shuffled = (random_hash % 256) as u8;
-        let outcome = rand_shuffled < odds;
-        if outcome {
-            let won_value = (((net_bet * 256) / (odds as u128)) * game.bet_payment_adjustment)
-                / FRACTIONAL_BASE;
-            credits = credits + won_value;
-            assert!(game.house_funds >= won_value, "{}", ERR_407);
-            game.house_funds -= won_value;
+        // the part of the reserve not paid out goes back to the house
+        let mut credits = account.balances.get(&game.partner_token).unwrap_or(0);
+        game.reserved_funds -= bet.potential_win;
+        game.house_funds += bet.potential_win - payout;
+        let fees = FeeCuts::new(bet.bet_size, &bet.game_parameters);
+        self.internal_record_house_result(&bet.game_code, &mut game, fees.house_cut, payout);
+        if payout + jackpot_payout > 0 {
+            credits += payout + jackpot_payout;
+            account.balances.insert(&game.partner_token, &credits);
+        }
+
+        let receipt = BetReceipt {
+            bet_id: bet_id.0,
+            account_id: bet.account_id.clone(),
+            game_code: bet.game_code.clone(),
+            token_contract: game.partner_token.clone(),
+            bet_size: bet.bet_size,
+            net_bet: bet.net_bet,
+            fees,
+            seed: Base64VecU8(seed),
+            roll,
+            odds: bet.odds,
+            direction: bet.direction,
+            target_multiplier: bet.target_multiplier,
+            game_parameters: bet.game_parameters,
+            outcome,
+            payout,
+            jackpot_won,
+            jackpot_payout,
+            credits,
+            block_index: env::block_index(),
+        };
+        self.bets.insert(&bet_id.0, &receipt);
+        ContractEvent::Bet(BetData::from(&receipt)).emit();
+        self.internal_score_tournaments(&bet.game_code, std::slice::from_ref(&receipt));
+
+        self.internal_update_game(&bet.game_code, &game);
+        self.internal_update_account_storage_check(&bet.account_id, account, initial_storage);
+        receipt
+    }
+
+    //settles a commit-reveal bet that was not revealed before REVEAL_DEADLINE_BLOCKS as a loss,
+    //callable by anyone. Its reserved funds go back to the house, so that bets of unregistered
+    //players or players holding back a losing reveal do not lock them forever
+    pub fn expire_bet(&mut self, bet_id: U128) {
+        self.assert_panic_button();
+
+        let bet = self.pending_bets.get(&bet_id.0).expect(ERR_408);
+        let deadline = bet.commit_block + REVEAL_DEADLINE_BLOCKS;
+        assert!(env::block_index() >= deadline, "{}. Bet expires at block {}", ERR_420, deadline);
+
+        let initial_storage = env::storage_usage();
+        let mut game = self.internal_get_game(&bet.game_code).expect(ERR_002);
+        self.pending_bets.remove(&bet_id.0);
+        game.reserved_funds -= bet.potential_win;
+        game.house_funds += bet.potential_win;
+        let fees = FeeCuts::new(bet.bet_size, &bet.game_parameters);
+        self.internal_record_house_result(&bet.game_code, &mut game, fees.house_cut, 0);
+        ContractEvent::BetExpired(BetExpiredData {
+            bet_id,
+            account_id: &bet.account_id,
+            game_code: &bet.game_code,
+            bet_size: U128(bet.bet_size),
+        })
+        .emit();
+
+        self.internal_update_game(&bet.game_code, &game);
+        // players get back the storage they paid for the pending bet
+        if let Some(mut account) = self.internal_get_account(&bet.account_id) {
+            account.pending_bets -= 1;
+            self.internal_update_account_storage_check(&bet.account_id, account, initial_storage);
         }
+    }
+}
+
+// bet helpers shared by all game modes
+impl Contract {
+    pub fn internal_play(
+        &mut self,
+        account_id: AccountId,
+        game_code: GameId,
+        bet_size: u128,
+        choice: BetChoice,
+    ) -> BetReceipt {
+        self.internal_play_batch(account_id, game_code, &[(bet_size, choice)])
+            .pop()
+            .unwrap()
+    }
+
+    // settles bets of (bet_size, choice) in order, loading and saving account and game once
+    fn internal_play_batch(
+        &mut self,
+        account_id: AccountId,
+        game_code: GameId,
+        bets: &[(u128, BetChoice)],
+    ) -> Vec<BetReceipt> {
+        let initial_storage = env::storage_usage();
+
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        let mut credits = account.balances.get(&game.partner_token).unwrap_or(0);
+        let mut receipts: Vec<BetReceipt> = Vec::with_capacity(bets.len());
+        for (bet_size, choice) in bets {
+            receipts.push(self.internal_settle_bet(&account_id, &game_code, &mut game, &mut credits, *bet_size, choice));
+            // settling another bet would panic with ERR_418 and revert the block along with it
+            if game.is_blocked() {
+                break;
+            }
+        }
+        self.internal_record_bets(&game_code, &game, &receipts);
+
+        account.balances.insert(&game.partner_token, &credits);
+        self.internal_update_game(&game_code, &game);
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        receipts
+    }
+
+    fn internal_auto_play(
+        &mut self,
+        account_id: AccountId,
+        game_code: GameId,
+        strategy: &AutoPlayStrategy,
+    ) -> AutoPlayReport {
+        let initial_storage = env::storage_usage();
+
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        let mut credits = account.balances.get(&game.partner_token).unwrap_or(0);
+        let choice = strategy.choice();
+
+        let mut receipts: Vec<BetReceipt> = Vec::new();
+        let mut total_bet = 0;
+        let mut total_payout = 0;
+        let stop = loop {
+            let last = receipts.last().map(|receipt| (receipt.bet_size, receipt.outcome));
+            let bet_size = strategy.next_bet_size(last);
+            if let Some(stop) = strategy.limit_reached(receipts.len(), total_bet, total_payout, bet_size) {
+                break stop;
+            }
+            if env::prepaid_gas() - env::used_gas() < AUTO_BET_GAS {
+                break AutoPlayStop::OutOfGas;
+            }
+            // a win the house cannot pay would revert the whole run, check it before rolling
+            if let Err(error) = self
+                .internal_check_bet(&game, credits, bet_size, &choice)
+                .and_then(|_| self.internal_check_cover(&game, bet_size, &choice))
+            {
+                break AutoPlayStop::BetRejected { error };
+            }
+
+            let receipt = self.internal_settle_bet(&account_id, &game_code, &mut game, &mut credits, bet_size, &choice);
+            total_bet += receipt.bet_size;
+            total_payout += receipt.payout + receipt.jackpot_payout;
+            receipts.push(receipt);
+        };
+        self.internal_record_bets(&game_code, &game, &receipts);
 
         account.balances.insert(&game.partner_token, &credits);
-        self.internal_update_account(&account_id, &account);
         self.internal_update_game(&game_code, &game);
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        AutoPlayReport {
+            receipts,
+            stop,
+            total_bet,
+            total_payout,
+            credits,
+        }
+    }
+
+    // charges, rolls and pays a single bet against in memory credits and game, nothing is
+    // written until the caller stores the receipts with internal_record_bets.
+    // The bet id doubles as nonce so bets of the same block get independent rolls
+    pub fn internal_settle_bet(
+        &mut self,
+        account_id: &AccountId,
+        game_code: &GameId,
+        game: &mut PartneredGame,
+        credits: &mut u128,
+        bet_size: u128,
+        choice: &BetChoice,
+    ) -> BetReceipt {
+        // check that user has credits
+        self.internal_assert_bet(game, *credits, bet_size, choice);
+
+        // nft and owner cuts of the whole batch are credited by internal_record_bets
+        let fees = FeeCuts::new(bet_size, &GameParameters::from(&*game));
+        game.accrue_fees(&fees);
+        let net_bet = bet_size - fees.total();
+
+        // send off credits
+        *credits -= bet_size;
+        let bet_id = self.game_count;
+        let seed = env::random_seed();
+        let params = GameParameters::from(&*game);
+        let roll = random_value(&seed, account_id, bet_id, game_code, params.roll_range());
+        let (outcome, payout) = params.settle(net_bet, choice, roll);
+        if payout > 0 {
+            *credits += payout;
+            assert!(game.house_funds >= payout, "{}", ERR_407);
+            game.house_funds -= payout;
+        }
+        self.internal_record_house_result(game_code, game, fees.house_cut, payout);
+        // the jackpot is paid from its own pool, house_funds never cover it
+        let (jackpot_won, jackpot_payout) =
+            game.roll_jackpot(&seed, account_id, bet_id, game_code, params.jackpot_trigger);
+        *credits += jackpot_payout;
+
+        let receipt = BetReceipt {
+            bet_id,
+            account_id: account_id.clone(),
+            game_code: game_code.clone(),
+            token_contract: game.partner_token.clone(),
+            bet_size,
+            net_bet,
+            fees,
+            seed: Base64VecU8(seed),
+            roll,
+            odds: choice.odds,
+            direction: choice.direction,
+            target_multiplier: choice.target_multiplier,
+            game_parameters: params,
+            outcome,
+            payout,
+            jackpot_won,
+            jackpot_payout,
+            credits: *credits,
+            block_index: env::block_index(),
+        };
         self.game_count += 1;
-        outcome
+        receipt
+    }
+
+    // stores the receipts of bets settled in memory and scores them in running tournaments.
+    // Their nft and owner cuts are added up and credited with a single write per balance
+    pub fn internal_record_bets(&mut self, game_code: &GameId, game: &PartneredGame, receipts: &[BetReceipt]) {
+        if receipts.is_empty() {
+            return;
+        }
+        let mut fees = FeeCuts::default();
+        for receipt in receipts {
+            fees.add(&receipt.fees);
+            self.bets.insert(&receipt.bet_id, receipt);
+            ContractEvent::Bet(BetData::from(receipt)).emit();
+        }
+        self.internal_credit_fees(game_code, game, &fees);
+        self.internal_score_tournaments(game_code, receipts);
+    }
+
+    fn internal_retrieve_credits(
+        &mut self,
+        account_id: AccountId,
+        token_contract: AccountId,
+        amount: u128,
+    ) -> Promise {
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let current_balance = account.balances.remove(&token_contract).unwrap_or(0);
+        assert!(current_balance >= amount, "{}", ERR_401);
+        let new_balance = current_balance - amount;
+        if new_balance > 0 {
+            account.balances.insert(&token_contract, &new_balance);
+        }
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        ContractEvent::Withdrawal(WithdrawalData {
+            kind: WithdrawalKind::Credits,
+            receiver_id: &account_id,
+            token_contract: &token_contract,
+            amount: U128(amount),
+            game_code: None,
+        })
+        .emit();
+        self.safe_transfer_user(token_contract, amount, account_id)
+    }
+
+    // returns why a bet breaks the game limits, if it does
+    pub fn internal_check_bet(
+        &self,
+        game: &PartneredGame,
+        credits: u128,
+        bet_size: u128,
+        choice: &BetChoice,
+    ) -> Result<(), String> {
+        if game.is_blocked() {
+            return Err(ERR_418.to_string());
+        }
+        if credits < bet_size {
+            return Err(ERR_402.to_string());
+        }
+        if bet_size < game.min_bet {
+            return Err(format!("{}. Minimum is {}", ERR_403, game.min_bet));
+        }
+        if bet_size > game.max_bet {
+            return Err(format!("{}. Maximum is {}", ERR_404, game.max_bet));
+        }
+        match game.game_type {
+            GameType::Dice => {
+                if choice.odds < game.min_odds {
+                    return Err(format!("{}. Minimum is {}", ERR_405, game.min_odds));
+                }
+                if choice.odds > game.max_odds {
+                    return Err(format!("{}. Maximum is {}", ERR_406, game.max_odds));
+                }
+            }
+            // wheel games ignore the choice of a bet
+            GameType::Wheel { .. } => {}
+            GameType::Crash { max_multiplier } => {
+                if choice.target_multiplier <= FRACTIONAL_BASE || choice.target_multiplier > max_multiplier {
+                    return Err(format!("{}. Maximum is {}", ERR_411, max_multiplier));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // returns ERR_407 if the house could not pay the largest prize of a bet and ERR_417,
+    // with the largest bet accepted, if that prize goes over the game's exposure_limit
+    pub fn internal_check_cover(&self, game: &PartneredGame, bet_size: u128, choice: &BetChoice) -> Result<(), String> {
+        match game.check_cover(bet_size, choice) {
+            Ok(()) => Ok(()),
+            Err(ERR_417) => Err(format!("{}. Maximum is {}", ERR_417, game.effective_max_bet(choice))),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    // bets are checked against the house cover before being rolled, never after the outcome is known
+    fn internal_assert_bet(&self, game: &PartneredGame, credits: u128, bet_size: u128, choice: &BetChoice) {
+        if let Err(reason) = self
+            .internal_check_bet(game, credits, bet_size, choice)
+            .and_then(|_| self.internal_check_cover(game, bet_size, choice))
+        {
+            panic!("{}", reason);
+        }
+    }
+
+    // trips the game's circuit breaker when the result of a bet takes house losses past its
+    // drawdown_limits. play_batch and auto_play settle no more bets in the same call
+    fn internal_record_house_result(&self, game_code: &str, game: &mut PartneredGame, gain: u128, loss: u128) {
+        if let Some(breach) = game.record_house_result(gain, loss, env::block_timestamp(), env::block_index()) {
+            ContractEvent::GameBlocked(GameBlockedData {
+                game_code,
+                breach,
+                drawdown: &game.drawdown,
+            })
+            .emit();
+        }
+    }
+
+    // splits nft, owner, house and partner cuts from the bet
+    fn internal_charge_fees(
+        &mut self,
+        game_code: &str,
+        game: &mut PartneredGame,
+        bet_size: u128,
+    ) -> FeeCuts {
+        let fees = FeeCuts::new(bet_size, &GameParameters::from(&*game));
+        self.internal_accrue_fees(game_code, game, &fees);
+        fees
+    }
+
+    // credits already split cuts to the nft, owner, house and partner balances
+    pub fn internal_accrue_fees(&mut self, game_code: &str, game: &mut PartneredGame, fees: &FeeCuts) {
+        game.accrue_fees(fees);
+        self.internal_credit_fees(game_code, game, fees);
+    }
+
+    // credits the nft and owner cuts, kept by token across games
+    fn internal_credit_fees(&mut self, game_code: &str, game: &PartneredGame, fees: &FeeCuts) {
+        let nft_balance = self.nft_balance.get(&game.partner_token).unwrap_or(0);
+        self.nft_balance
+            .insert(&game.partner_token, &(nft_balance + fees.nft_cut));
+
+        let owner_balance = self.owner_balance.get(&game.partner_token).unwrap_or(0);
+        self.owner_balance
+            .insert(&game.partner_token, &(owner_balance + fees.owner_cut));
+
+        ContractEvent::FeesAccrued(FeesAccruedData {
+            game_code,
+            token_contract: &game.partner_token,
+            fees,
+        })
+        .emit();
     }
 }
 
@@ -124,6 +562,36 @@ impl Contract {
             .insert(&token_contract, &(credits + amount));
 
         self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        ContractEvent::Deposit(DepositData {
+            account_id: &account_id,
+            token_contract: &token_contract,
+            amount: U128(amount),
+        })
+        .emit();
+    }
+
+    // deposits the transferred tokens and bets all of them in a single call,
+    // winnings, jackpot included, can optionally be sent straight back to the player
+    pub fn user_play_transfer(
+        &mut self,
+        account_id: AccountId,
+        token_contract: AccountId,
+        amount: u128,
+        game_code: GameId,
+        choice: BetChoice,
+        withdraw_winnings: bool,
+    ) -> BetReceipt {
+        self.assert_panic_button();
+        let game = self.internal_get_game(&game_code).expect(ERR_002);
+        assert_eq!(game.partner_token, token_contract, "{}", ERR_301);
+
+        self.user_deposit_balance(account_id.clone(), token_contract.clone(), amount);
+        let receipt = self.internal_play(account_id.clone(), game_code, amount, choice);
+        let winnings = receipt.payout + receipt.jackpot_payout;
+        if withdraw_winnings && winnings > 0 {
+            self.internal_retrieve_credits(account_id, token_contract, winnings);
+        }
+        receipt
     }
 }
 
@@ -131,6 +599,7 @@ impl Contract {
 mod tests {
     use super::*;
     use crate::tests::*;
+    use crate::partnered_game::MAX_GAME_ID_LENGTH;
 
     /// retrieve_credits
     /// method must:
@@ -200,12 +669,15 @@ mod tests {
     /// 4. Assert bet and odds are within game limits
     /// 5. Charge all game fees
     /// 6. Increase balance of user correctly if they win
-    /// 7. Return true for won games and false for lost
+    /// 7. Return and store a receipt with the bet result
+    /// 8. Roll over or under the odds with the game's odds_precision
     #[test]
     fn test_play() {
         fn closure_generator(
             bet_size: u128,
-            odds: u8,
+            odds: u32,
+            odds_precision: u32,
+            direction: Option<RollDirection>,
             user_balance: u128,
             panic_button_state: bool,
             seed: u128,
@@ -220,8 +692,11 @@ mod tests {
                 let house_fee = 100;
                 let owner_fee = 300;
                 let nft_fee = 500;
-                let max_odds = 200;
-                let min_odds = 100;
+                let (min_odds, max_odds) = if odds_precision == DEFAULT_ODDS_PRECISION {
+                    (100, 200)
+                } else {
+                    (100, odds_precision - 100)
+                };
                 let max_bet = 100;
                 let min_bet = 10;
                 let bet_payment_adjustment = 10000;
@@ -236,29 +711,31 @@ mod tests {
                 contract.internal_update_account(&user, &account);
 
                 let game_settings = PartneredGame {
-                    partner_owner: "anyone".to_string(),
-                    blocked: false,
                     house_funds: 1_000_000,
-                    partner_token: base_token.clone(),
                     partner_fee,
-                    partner_balance: 0,
                     bet_payment_adjustment,
                     house_fee,
                     max_bet,
                     min_bet,
+                    odds_precision,
                     max_odds,
                     min_odds,
                     nft_fee,
                     owner_fee,
+                    ..test_game(&base_token)
                 };
                 contract.games.insert(&game_id, &game_settings);
 
-                let result = contract.play(
+                let receipt = contract.play(
                     game_id.clone(),
                     U128(bet_size),
                     odds,
                     "_bet_type".to_string(),
+                    direction,
+                    None,
+                    None,
                 );
+                let result = receipt.outcome;
                 let partner_fee_calc = (bet_size * partner_fee) / FRACTIONAL_BASE;
                 let owner_fee_calc = (bet_size * owner_fee) / FRACTIONAL_BASE;
                 let nft_fee_calc = (bet_size * nft_fee) / FRACTIONAL_BASE;
@@ -267,6 +744,25 @@ mod tests {
                 let new_game = contract.games.get(&game_id).unwrap();
                 assert_eq!(partner_fee_calc, new_game.partner_balance);
 
+                // receipt is returned and stored
+                assert_eq!(receipt.bet_id, seed);
+                assert_eq!(receipt.fees.partner_cut, partner_fee_calc);
+                assert_eq!(receipt.fees.owner_cut, owner_fee_calc);
+                assert_eq!(receipt.fees.nft_cut, nft_fee_calc);
+                assert_eq!(receipt.fees.house_cut, house_fee_calc);
+                assert_eq!(receipt.net_bet, bet_size - receipt.fees.total());
+                assert!(receipt.roll < odds_precision as u128);
+                assert_eq!(receipt.direction, direction.unwrap_or_default());
+                assert_eq!(receipt.game_parameters.odds_precision, odds_precision);
+                assert_eq!(
+                    receipt.outcome,
+                    receipt.direction.wins(receipt.roll, odds, odds_precision)
+                );
+                assert_eq!(receipt.credits, user_balance - bet_size + receipt.payout);
+                let stored = contract.view_bet(U128(seed));
+                assert_eq!(stored.credits, receipt.credits);
+                assert_eq!(stored.payout, receipt.payout);
+
                 assert_eq!(
                     owner_fee_calc,
                     contract.owner_balance.get(&base_token).unwrap()
@@ -280,7 +776,8 @@ mod tests {
                         - owner_fee_calc
                         - nft_fee_calc
                         - house_fee_calc;
-                    let won_value = (((net_bet * 256) / (odds as u128)) * bet_payment_adjustment)
+                    let won_value = (((net_bet * odds_precision as u128) / (odds as u128))
+                        * bet_payment_adjustment)
                         / FRACTIONAL_BASE;
                     assert_eq!(
                         user_balance + won_value - bet_size,
@@ -307,29 +804,913 @@ mod tests {
 
         let test_cases = [
             // 1. Assert panic button
-            (0, 1, 0, true, Some(ERR_007.to_string())),
+            (0, 1, 256, None, 0, true, Some(ERR_007.to_string())),
             // 2. Assert user account exists and has balance
-            (10, 1, 5, false, Some(ERR_402.to_string())),
+            (10, 1, 256, None, 5, false, Some(ERR_402.to_string())),
             // 3. Assert bet and odds are within game limits
-            (10, 255, 15, false, Some(ERR_406.to_string())),
-            (10, 1, 15, false, Some(ERR_405.to_string())),
-            (1, 100, 15, false, Some(ERR_403.to_string())),
-            (1000, 1, 1500000, false, Some(ERR_404.to_string())),
+            (10, 255, 256, None, 15, false, Some(ERR_406.to_string())),
+            (10, 1, 256, None, 15, false, Some(ERR_405.to_string())),
+            (1, 100, 256, None, 15, false, Some(ERR_403.to_string())),
+            (1000, 1, 256, None, 1500000, false, Some(ERR_404.to_string())),
             // 5. Charge all game fees
             // 6. Increase balance of user correctly if they win
-            // 7. Return true for won games and false for lost
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
-            (100, 128, 10000, false, None),
+            // 7. Return and store a receipt with the bet result
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            (100, 128, 256, None, 10000, false, None),
+            // 8. Roll over or under the odds with the game's odds_precision
+            (100, 128, 256, Some(RollDirection::Over), 10000, false, None),
+            (100, 128, 256, Some(RollDirection::Over), 10000, false, None),
+            (100, 9_800, 10_000, Some(RollDirection::Over), 10000, false, None),
+            (100, 9_800, 10_000, Some(RollDirection::Under), 10000, false, None),
+            (100, 100, 10_000, Some(RollDirection::Over), 10000, false, None),
+            (100, 100, 10_000, Some(RollDirection::Under), 10000, false, None),
+            (100, 5_000, 10_000, None, 10000, false, None),
+            (100, 99, 10_000, None, 10000, false, Some(ERR_405.to_string())),
+            (100, 9_901, 10_000, None, 10000, false, Some(ERR_406.to_string())),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, v.5, counter), v.6);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    const GAME_TYPE_HOUSE_FUNDS: u128 = 1_000_000;
+    const GAME_TYPE_USER_BALANCE: u128 = 10_000;
+    const GAME_TYPE_BET_SIZE: u128 = 1_000;
+    const GAME_TYPE_HOUSE_FEE: u128 = 1_000;
+
+    // creates a game of game_type through create_new_partner and funds a player for it,
+    // leaves the player as the signer with the seed as random_seed
+    fn sample_game_type_contract(game_type: GameType, game_id: &GameId, user: &AccountId, seed: u128) -> Contract {
+        let base_token = format!("{}-token.testnet", seed);
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.game_count = seed;
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &base_token);
+        contract.create_new_partner(
+            Some(game_id.clone()),
+            "anyone".to_string(),
+            "nft.testnet".to_string(),
+            base_token.clone(),
+            U128(0),
+            U128(FRACTIONAL_BASE * 95 / 100),
+            U128(GAME_TYPE_HOUSE_FEE),
+            U128(GAME_TYPE_BET_SIZE),
+            U128(10),
+            200,
+            100,
+            None,
+            Some(game_type),
+            U128(0),
+            U128(0),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let mut game = contract.games.get(game_id).unwrap();
+        game.house_funds = GAME_TYPE_HOUSE_FUNDS;
+        contract.games.insert(game_id, &game);
+
+        let mut context = get_context(vec![], false, 0, 1000, user.clone());
+        context.random_seed = seed.to_be_bytes().to_vec();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.internal_deposit_storage_account(user, 1000 * ONE_NEAR);
+        let mut account = contract.internal_get_account(user).unwrap();
+        account.balances.insert(&base_token, &GAME_TYPE_USER_BALANCE);
+        contract.internal_update_account(user, &account);
+        contract
+    }
+
+    // asserts the credits and house funds moved by a settled bet of GAME_TYPE_BET_SIZE
+    fn assert_game_type_settlement(contract: &Contract, game_id: &GameId, receipt: &BetReceipt) {
+        let house_cut = (GAME_TYPE_BET_SIZE * GAME_TYPE_HOUSE_FEE) / FRACTIONAL_BASE;
+        assert_eq!(
+            receipt.credits,
+            GAME_TYPE_USER_BALANCE - GAME_TYPE_BET_SIZE + receipt.payout + receipt.jackpot_payout
+        );
+        assert_eq!(
+            contract.games.get(game_id).unwrap().house_funds,
+            GAME_TYPE_HOUSE_FUNDS + house_cut - receipt.payout
+        );
+        assert!(contract.verify_bet(U128(receipt.bet_id)).matches_record);
+    }
+
+    /// play_batch
+    /// method must:
+    /// 1. Assert the batch has between 1 and MAX_BATCH_BETS bets
+    /// 2. Check every bet against the game limits, reverting the whole batch
+    /// 3. Settle bets in order with sequential bet ids
+    /// 4. Leave credits and house funds as if each bet was played on its own
+    #[test]
+    fn test_play_batch() {
+        fn closure_generator(bets: Vec<(u128, u32, Option<RollDirection>)>, seed: u128) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let game_id = "batch".to_string();
+                let mut contract = sample_game_type_contract(GameType::Dice, &game_id, &user, seed);
+                let token = contract.games.get(&game_id).unwrap().partner_token;
+
+                let batch = bets
+                    .iter()
+                    .map(|(bet_size, odds, direction)| BatchBet {
+                        bet_size: U128(*bet_size),
+                        odds: *odds,
+                        direction: *direction,
+                        target_multiplier: None,
+                    })
+                    .collect();
+                let receipts = contract.play_batch(game_id.clone(), batch);
+
+                assert_eq!(receipts.len(), bets.len());
+                assert_eq!(contract.game_count, seed + bets.len() as u128);
+                let mut credits = GAME_TYPE_USER_BALANCE;
+                let mut house_funds = GAME_TYPE_HOUSE_FUNDS;
+                for (index, (receipt, (bet_size, odds, direction))) in receipts.iter().zip(bets.iter()).enumerate() {
+                    assert_eq!(receipt.bet_id, seed + index as u128);
+                    assert_eq!(receipt.bet_size, *bet_size);
+                    assert_eq!(receipt.odds, *odds);
+                    assert_eq!(receipt.direction, direction.unwrap_or_default());
+                    credits = credits + receipt.payout + receipt.jackpot_payout - bet_size;
+                    house_funds = house_funds + (bet_size * GAME_TYPE_HOUSE_FEE) / FRACTIONAL_BASE - receipt.payout;
+                    assert_eq!(receipt.credits, credits);
+                    assert!(contract.verify_bet(U128(receipt.bet_id)).matches_record);
+                }
+                let account = contract.internal_get_account(&user).unwrap();
+                assert_eq!(account.balances.get(&token).unwrap(), credits);
+                assert_eq!(contract.games.get(&game_id).unwrap().house_funds, house_funds);
+            }
+        }
+
+        let bet = |bet_size: u128, odds: u32| (bet_size, odds, None);
+        let test_cases = [
+            // 1. Assert the batch has between 1 and MAX_BATCH_BETS bets
+            (vec![], Some(ERR_412.to_string())),
+            (vec![bet(10, 128); MAX_BATCH_BETS + 1], Some(ERR_412.to_string())),
+            // 2. Check every bet against the game limits, reverting the whole batch
+            (vec![bet(100, 128), bet(100, 128), bet(GAME_TYPE_BET_SIZE + 1, 128)], Some(ERR_404.to_string())),
+            (vec![bet(100, 128), bet(100, 99)], Some(ERR_405.to_string())),
+            // 3. Settle bets in order with sequential bet ids
+            // 4. Leave credits and house funds as if each bet was played on its own
+            (vec![bet(100, 128)], None),
+            (vec![bet(100, 128), bet(500, 200), bet(1000, 100)], None),
+            (vec![bet(100, 128), (200, 150, Some(RollDirection::Over)), (300, 100, Some(RollDirection::Under))], None),
+            (vec![bet(10, 128); MAX_BATCH_BETS], None),
+            (vec![bet(200, 100); 5], None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+        });
+    }
+
+    /// play_batch
+    /// method must:
+    /// 1. Keep the logs of MAX_BATCH_BETS bets with maximum length ids within 16kb
+    #[test]
+    fn test_play_batch_log_size() {
+        let account_id = format!("{}.testnet", "a".repeat(56));
+        let game_id = "g".repeat(MAX_GAME_ID_LENGTH);
+        let token = format!("{}.testnet", "t".repeat(56));
+        let bet_size = 10u128.pow(30);
+
+        let context = get_context(vec![], false, 0, 1000 * ONE_NEAR, account_id.clone());
+        testing_env!(context);
+        let mut contract = sample_contract(0);
+        contract.game_count = u64::MAX as u128;
+        register_sample_token(&mut contract, &token);
+        let game = PartneredGame {
+            house_funds: 10u128.pow(36),
+            max_bet: bet_size,
+            partner_fee: 100,
+            house_fee: 100,
+            nft_fee: 100,
+            owner_fee: 100,
+            jackpot_fee: 100,
+            ..test_game(&token)
+        };
+        insert_test_game(&mut contract, &game_id, &game);
+        contract.internal_deposit_storage_account(&account_id, 1000 * ONE_NEAR);
+        let mut account = contract.internal_get_account(&account_id).unwrap();
+        account.balances.insert(&token, &(bet_size * 1_000));
+        contract.internal_update_account(&account_id, &account);
+
+        let mut context = get_context(vec![], false, 0, 1000 * ONE_NEAR, account_id.clone());
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let batch = vec![
+            BatchBet {
+                bet_size: U128(bet_size),
+                odds: 200,
+                direction: Some(RollDirection::Over),
+                target_multiplier: None,
+            };
+            MAX_BATCH_BETS
+        ];
+        let receipts = contract.play_batch(game_id, batch);
+        assert_eq!(receipts.len(), MAX_BATCH_BETS);
+
+        // 1. Keep the logs of MAX_BATCH_BETS bets with maximum length ids within 16kb
+        let log_size: usize = near_sdk::test_utils::get_logs().iter().map(|log| log.len()).sum();
+        assert!(log_size <= 16 * 1024, "{}", log_size);
+    }
+
+    /// auto_play
+    /// method must:
+    /// 1. Assert the strategy has a base_bet and between 1 and MAX_BATCH_BETS max_bets
+    /// 2. Size bets following the staking plan
+    /// 3. Stop at max_bets, stop_loss or take_profit
+    /// 4. Stop cleanly, with the error, before a bet that would be rejected
+    /// 5. Stop cleanly when gas runs out
+    /// 6. Leave credits as if each bet was played on its own
+    #[test]
+    fn test_auto_play() {
+        fn closure_generator(
+            strategy: AutoPlayStrategy,
+            user_credits: Option<u128>,
+            house_funds: Option<u128>,
+            prepaid_gas: Option<u64>,
+            expected_stop: Option<AutoPlayStop>,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let game_id = "auto".to_string();
+                let mut contract = sample_game_type_contract(GameType::Dice, &game_id, &user, seed);
+                let mut game = contract.games.get(&game_id).unwrap();
+                let token = game.partner_token.clone();
+                let initial_credits = user_credits.unwrap_or(GAME_TYPE_USER_BALANCE);
+                let mut account = contract.internal_get_account(&user).unwrap();
+                account.balances.insert(&token, &initial_credits);
+                contract.internal_update_account(&user, &account);
+                if let Some(house_funds) = house_funds {
+                    game.house_funds = house_funds;
+                    contract.games.insert(&game_id, &game);
+                }
+                if let Some(prepaid_gas) = prepaid_gas {
+                    let mut context = get_context(vec![], false, 0, 1000, user.clone());
+                    context.prepaid_gas = prepaid_gas;
+                    context.storage_usage = env::storage_usage();
+                    testing_env!(context);
+                }
+
+                let report = contract.auto_play(game_id.clone(), strategy.clone());
+
+                // rejection errors may carry the limit that was hit after the error code
+                match (&report.stop, expected_stop) {
+                    (AutoPlayStop::BetRejected { error }, Some(AutoPlayStop::BetRejected { error: expected })) => {
+                        assert!(error.starts_with(&expected), "unexpected rejection {}", error)
+                    }
+                    (stop, Some(expected)) => assert_eq!(*stop, expected),
+                    _ => {}
+                }
+                let receipts = &report.receipts;
+                assert!(receipts.len() <= strategy.max_bets as usize);
+                let mut last = None;
+                for receipt in receipts.iter() {
+                    assert_eq!(receipt.bet_size, strategy.next_bet_size(last));
+                    assert_eq!(receipt.odds, strategy.odds);
+                    assert!(contract.verify_bet(U128(receipt.bet_id)).matches_record);
+                    last = Some((receipt.bet_size, receipt.outcome));
+                }
+                assert_eq!(report.total_bet, receipts.iter().map(|receipt| receipt.bet_size).sum::<u128>());
+                assert_eq!(
+                    report.total_payout,
+                    receipts.iter().map(|receipt| receipt.payout + receipt.jackpot_payout).sum::<u128>()
+                );
+                assert_eq!(report.credits, initial_credits + report.total_payout - report.total_bet);
+                assert_eq!(contract.internal_get_account(&user).unwrap().balances.get(&token).unwrap(), report.credits);
+                let loss = report.total_bet.saturating_sub(report.total_payout);
+                if let Some(stop_loss) = strategy.stop_loss {
+                    assert!(loss <= stop_loss.0);
+                }
+                let next_bet = strategy.next_bet_size(last);
+                match report.stop {
+                    AutoPlayStop::MaxBets => assert_eq!(receipts.len(), strategy.max_bets as usize),
+                    AutoPlayStop::TakeProfit => {
+                        assert!(report.total_payout >= report.total_bet + strategy.take_profit.unwrap().0)
+                    }
+                    AutoPlayStop::StopLoss => assert!(loss + next_bet > strategy.stop_loss.unwrap().0),
+                    _ => {}
+                }
+            }
+        }
+
+        let strategy = |plan: StakingPlan,
+                        base_bet: u128,
+                        stop_loss: Option<u128>,
+                        take_profit: Option<u128>,
+                        max_bets: u32| AutoPlayStrategy {
+            plan,
+            base_bet: U128(base_bet),
+            odds: 128,
+            direction: None,
+            target_multiplier: None,
+            stop_loss: stop_loss.map(U128),
+            take_profit: take_profit.map(U128),
+            max_bets,
+        };
+        let rejected = |error: &str| Some(AutoPlayStop::BetRejected { error: error.to_string() });
+        let test_cases = [
+            // 1. Assert the strategy has a base_bet and between 1 and MAX_BATCH_BETS max_bets
+            (strategy(StakingPlan::Fixed, 0, None, None, 5), None, None, None, None, Some(ERR_413.to_string())),
+            (strategy(StakingPlan::Fixed, 10, None, None, 0), None, None, None, None, Some(ERR_413.to_string())),
+            (
+                strategy(StakingPlan::Fixed, 10, None, None, MAX_BATCH_BETS as u32 + 1),
+                None, None, None, None, Some(ERR_413.to_string()),
+            ),
+            // 2. Size bets following the staking plan
+            // 3. Stop at max_bets, stop_loss or take_profit
+            (strategy(StakingPlan::Fixed, 100, None, None, 10), None, None, None, Some(AutoPlayStop::MaxBets), None),
+            (strategy(StakingPlan::Martingale, 10, None, None, 6), None, None, None, None, None),
+            (strategy(StakingPlan::AntiMartingale, 10, None, None, 6), None, None, None, None, None),
+            (strategy(StakingPlan::Martingale, 10, Some(100), None, MAX_BATCH_BETS as u32), None, None, None, None, None),
+            (strategy(StakingPlan::Fixed, 100, Some(250), Some(50), MAX_BATCH_BETS as u32), None, None, None, None, None),
+            (strategy(StakingPlan::Fixed, 100, Some(50), None, 5), None, None, None, Some(AutoPlayStop::StopLoss), None),
+            (strategy(StakingPlan::Fixed, 100, None, Some(0), 5), None, None, None, Some(AutoPlayStop::TakeProfit), None),
+            // 4. Stop cleanly, with the error, before a bet that would be rejected
+            (strategy(StakingPlan::Fixed, 100, None, None, 5), Some(99), None, None, rejected(ERR_402), None),
+            (strategy(StakingPlan::Fixed, 100, None, None, 5), None, Some(0), None, rejected(ERR_407), None),
+            (
+                strategy(StakingPlan::Fixed, GAME_TYPE_BET_SIZE + 1, None, None, 5),
+                None, None, None, rejected(ERR_404), None,
+            ),
+            // 5. Stop cleanly when gas runs out
+            (strategy(StakingPlan::Fixed, 100, None, None, 5), None, None, Some(AUTO_BET_GAS / 2), Some(AutoPlayStop::OutOfGas), None),
+            // 6. Leave credits as if each bet was played on its own
+            (strategy(StakingPlan::Fixed, 1000, None, None, MAX_BATCH_BETS as u32), Some(3_000), None, None, None, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, counter), v.5);
+            counter += 1;
+        });
+    }
+
+    /// play on a wheel game
+    /// method must:
+    /// 1. Only create wheel games whose payout table is valid
+    /// 2. Ignore the odds of the bet
+    /// 3. Pay the multiplier of the segment drawn by the roll
+    /// 4. Store a receipt that verifies against the payout table
+    #[test]
+    fn test_play_wheel() {
+        fn closure_generator(segments: Vec<WheelSegment>, seed: u128) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let game_id = "wheel".to_string();
+                let game_type = GameType::Wheel { segments: segments.clone() };
+                let mut contract = sample_game_type_contract(game_type, &game_id, &user, seed);
+
+                // odds outside of max_odds are accepted, wheel games ignore them
+                let receipt = contract.play(
+                    game_id.clone(),
+                    U128(GAME_TYPE_BET_SIZE),
+                    0,
+                    "_bet_type".to_string(),
+                    None,
+                    None,
+                    None,
+                );
+
+                assert!(receipt.roll < GameType::table_weight(&segments));
+                let segment = &segments[GameType::segment_index(&segments, receipt.roll)];
+                assert_eq!(receipt.payout, (receipt.net_bet * segment.multiplier) / FRACTIONAL_BASE);
+                assert_eq!(receipt.outcome, receipt.payout > 0);
+                assert_game_type_settlement(&contract, &game_id, &receipt);
+            }
+        }
+
+        let segment = |weight: u32, multiplier: u128| WheelSegment { weight, multiplier };
+        // 0x, 0.5x, 2x and 10x segments with expected value 0.95x
+        let wheel = vec![
+            segment(50, 0),
+            segment(30, FRACTIONAL_BASE / 2),
+            segment(15, 2 * FRACTIONAL_BASE),
+            segment(5, 10 * FRACTIONAL_BASE),
+        ];
+        let test_cases = [
+            // 1. Only create wheel games whose payout table is valid
+            (vec![], Some(ERR_210.to_string())),
+            (vec![segment(1, 0), segment(1, 2 * FRACTIONAL_BASE)], Some(ERR_209.to_string())),
+            // 2. Ignore the odds of the bet
+            // 3. Pay the multiplier of the segment drawn by the roll
+            // 4. Store a receipt that verifies against the payout table
+            (wheel.clone(), None),
+            (wheel.clone(), None),
+            (wheel.clone(), None),
+            (wheel.clone(), None),
+            (wheel.clone(), None),
+            (wheel.clone(), None),
+            (wheel, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+        });
+    }
+
+    /// play on a crash game
+    /// method must:
+    /// 1. Only create crash games with a max_multiplier above 1x
+    /// 2. Assert target multiplier is above 1x and within max_multiplier
+    /// 3. Pay bet * target if the crash point reaches the target
+    /// 4. Store a receipt that verifies against the crash point
+    #[test]
+    fn test_play_crash() {
+        fn closure_generator(max_multiplier: u128, target_multiplier: u128, seed: u128) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let game_id = "crash".to_string();
+                let mut contract =
+                    sample_game_type_contract(GameType::Crash { max_multiplier }, &game_id, &user, seed);
+
+                let receipt = contract.play(
+                    game_id.clone(),
+                    U128(GAME_TYPE_BET_SIZE),
+                    0,
+                    "_bet_type".to_string(),
+                    None,
+                    Some(U128(target_multiplier)),
+                    None,
+                );
+
+                assert!(receipt.roll < CRASH_ROLL_RANGE);
+                assert_eq!(receipt.target_multiplier, target_multiplier);
+                let crash_point = GameType::crash_point(FRACTIONAL_BASE * 95 / 100, receipt.roll);
+                assert_eq!(receipt.outcome, crash_point >= target_multiplier);
+                if receipt.outcome {
+                    assert_eq!(receipt.payout, (receipt.net_bet * target_multiplier) / FRACTIONAL_BASE);
+                } else {
+                    assert_eq!(receipt.payout, 0);
+                }
+                assert_game_type_settlement(&contract, &game_id, &receipt);
+            }
+        }
+
+        let test_cases = [
+            // 1. Only create crash games with a max_multiplier above 1x
+            (FRACTIONAL_BASE, 2 * FRACTIONAL_BASE, Some(ERR_211.to_string())),
+            // 2. Assert target multiplier is above 1x and within max_multiplier
+            (100 * FRACTIONAL_BASE, FRACTIONAL_BASE, Some(ERR_411.to_string())),
+            (100 * FRACTIONAL_BASE, 101 * FRACTIONAL_BASE, Some(ERR_411.to_string())),
+            // 3. Pay bet * target if the crash point reaches the target
+            // 4. Store a receipt that verifies against the crash point
+            (100 * FRACTIONAL_BASE, 2 * FRACTIONAL_BASE, None),
+            (100 * FRACTIONAL_BASE, 2 * FRACTIONAL_BASE, None),
+            (100 * FRACTIONAL_BASE, 2 * FRACTIONAL_BASE, None),
+            (100 * FRACTIONAL_BASE, 2 * FRACTIONAL_BASE, None),
+            (100 * FRACTIONAL_BASE, FRACTIONAL_BASE * 3 / 2, None),
+            (100 * FRACTIONAL_BASE, FRACTIONAL_BASE * 3 / 2, None),
+            (100 * FRACTIONAL_BASE, 10 * FRACTIONAL_BASE, None),
+            (100 * FRACTIONAL_BASE, 100 * FRACTIONAL_BASE, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            counter += 1;
+        });
+    }
+
+    /// play on a game with a jackpot
+    /// method must:
+    /// 1. Add the jackpot cut of every bet to the pool
+    /// 2. Pay the whole pool when the jackpot trigger of the bet hits
+    /// 3. Keep the pool out of house_funds
+    /// 4. Store a receipt that verifies the jackpot trigger
+    #[test]
+    fn test_play_jackpot() {
+        use crate::random::jackpot_triggered;
+
+        fn closure_generator(jackpot_fee: u128, jackpot_trigger: u32, pool: u128, seed: u128) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let game_id = "jackpot".to_string();
+                let mut contract = sample_game_type_contract(GameType::Dice, &game_id, &user, seed);
+                let mut game = contract.games.get(&game_id).unwrap();
+                game.jackpot_fee = jackpot_fee;
+                game.jackpot_trigger = jackpot_trigger;
+                game.jackpot_balance = pool;
+                contract.games.insert(&game_id, &game);
+
+                let receipt = contract.play(
+                    game_id.clone(),
+                    U128(GAME_TYPE_BET_SIZE),
+                    128,
+                    "_bet_type".to_string(),
+                    None,
+                    None,
+                    None,
+                );
+
+                // 1. Add the jackpot cut of every bet to the pool
+                let jackpot_cut = (GAME_TYPE_BET_SIZE * jackpot_fee) / FRACTIONAL_BASE;
+                assert_eq!(receipt.fees.jackpot_cut, jackpot_cut);
+                let house_cut = (GAME_TYPE_BET_SIZE * GAME_TYPE_HOUSE_FEE) / FRACTIONAL_BASE;
+                assert_eq!(receipt.net_bet, GAME_TYPE_BET_SIZE - house_cut - jackpot_cut);
+
+                // 2. Pay the whole pool when the jackpot trigger of the bet hits
+                let triggered = jackpot_triggered(
+                    &receipt.seed.0,
+                    &user,
+                    receipt.bet_id,
+                    &game_id,
+                    jackpot_trigger,
+                );
+                assert_eq!(receipt.jackpot_won, triggered);
+                let game = contract.games.get(&game_id).unwrap();
+                if triggered {
+                    assert_eq!(receipt.jackpot_payout, pool + jackpot_cut);
+                    assert_eq!(game.jackpot_balance, 0);
+                } else {
+                    assert_eq!(receipt.jackpot_payout, 0);
+                    assert_eq!(game.jackpot_balance, pool + jackpot_cut);
+                }
+                assert_eq!(contract.view_jackpot(game_id.clone()), U128(game.jackpot_balance));
+
+                // 3. Keep the pool out of house_funds
+                // 4. Store a receipt that verifies the jackpot trigger
+                assert_game_type_settlement(&contract, &game_id, &receipt);
+                assert_eq!(contract.verify_bet(U128(receipt.bet_id)).jackpot_won, triggered);
+            }
+        }
+
+        let test_cases = [
+            (1_000, 2, 0),
+            (1_000, 2, 50_000),
+            (1_000, 2, 50_000),
+            (1_000, 2, 50_000),
+            (1_000, 2, 50_000),
+            (1_000, 2, 50_000),
+            (0, 2, 50_000),
+            (5_000, 3, 0),
+            (1_000, DEFAULT_JACKPOT_TRIGGER, 50_000),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), None);
+            counter += 1;
+        });
+    }
+
+    /// commit_bet
+    /// method must:
+    /// 1. Assert panic button
+    /// 2. Assert user account exists and has balance
+    /// 3. Assert bet and odds are within game limits
+    /// 4. Assert house can cover possible win value
+    /// 5. Charge bet size and all game fees
+    /// 6. Reserve possible win value from house funds
+    /// 7. Register pending bet and return its id
+    #[test]
+    fn test_commit_bet() {
+        fn closure_generator(
+            bet_size: u128,
+            odds: u32,
+            user_balance: u128,
+            house_funds: u128,
+            panic_button_state: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let mut context = get_context(vec![], false, 0, 1000, user.clone());
+                context.block_index = 50;
+                testing_env!(context);
+                let game_id = "teste.near".to_string();
+                let base_token = format!("{}-token.testnet", seed);
+                let house_fee = 100;
+                let bet_payment_adjustment = 10000;
+
+                let mut contract = sample_contract(seed);
+                contract.panic_button = panic_button_state;
+                contract.game_count = seed;
+
+                contract.internal_deposit_storage_account(&user, 1000 * ONE_NEAR);
+                let mut account = contract.internal_get_account(&user).unwrap();
+                account.balances.insert(&base_token, &user_balance);
+                contract.internal_update_account(&user, &account);
+
+                let game_settings = PartneredGame {
+                    house_funds,
+                    partner_fee: 1000,
+                    bet_payment_adjustment,
+                    house_fee,
+                    nft_fee: 500,
+                    owner_fee: 300,
+                    ..test_game(&base_token)
+                };
+                contract.games.insert(&game_id, &game_settings);
+
+                let bet_id = contract.commit_bet(
+                    game_id.clone(),
+                    U128(bet_size),
+                    odds,
+                    "_bet_type".to_string(),
+                    Some(RollDirection::Over),
+                    None,
+                );
+                assert_eq!(bet_id, U128(seed));
+                assert_eq!(contract.game_count, seed + 1);
+
+                let pending_bet = contract.pending_bets.get(&bet_id.0).unwrap();
+                assert_eq!(pending_bet.account_id, user);
+                assert_eq!(pending_bet.commit_block, 50);
+                assert_eq!(pending_bet.direction, RollDirection::Over);
+                assert_eq!(contract.internal_get_account(&user).unwrap().pending_bets, 1);
+                assert_eq!(
+                    pending_bet.potential_win,
+                    game_settings.max_payout(pending_bet.net_bet, &pending_bet.choice())
+                );
+
+                let house_fee_calc = (bet_size * house_fee) / FRACTIONAL_BASE;
+                let new_game = contract.games.get(&game_id).unwrap();
+                assert_eq!(new_game.reserved_funds, pending_bet.potential_win);
+                assert_eq!(
+                    new_game.house_funds,
+                    house_funds + house_fee_calc - pending_bet.potential_win
+                );
+                assert_eq!(
+                    contract
+                        .internal_get_account(&user)
+                        .unwrap()
+                        .balances
+                        .get(&base_token)
+                        .unwrap(),
+                    user_balance - bet_size
+                );
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert panic button
+            (0, 1, 0, 1_000_000, true, Some(ERR_007.to_string())),
+            // 2. Assert user account exists and has balance
+            (10, 1, 5, 1_000_000, false, Some(ERR_402.to_string())),
+            // 3. Assert bet and odds are within game limits
+            (10, 255, 15, 1_000_000, false, Some(ERR_406.to_string())),
+            (10, 1, 15, 1_000_000, false, Some(ERR_405.to_string())),
+            (1, 100, 15, 1_000_000, false, Some(ERR_403.to_string())),
+            (1000, 1, 1500000, 1_000_000, false, Some(ERR_404.to_string())),
+            // 4. Assert house can cover possible win value
+            (100, 128, 10000, 10, false, Some(ERR_407.to_string())),
+            // 5. Charge bet size and all game fees
+            // 6. Reserve possible win value from house funds
+            // 7. Register pending bet and return its id
+            (100, 128, 10000, 1_000_000, false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, counter), v.5);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// reveal_bet
+    /// method must:
+    /// 1. Assert panic button
+    /// 2. Assert pending bet exists
+    /// 3. Assert reveal delay has passed
+    /// 4. Assert reveal deadline has not passed
+    /// 5. Release reserved funds to player if they win
+    ///    or back to house if they lose
+    /// 6. Remove pending bet
+    #[test]
+    fn test_reveal_bet() {
+        fn closure_generator(
+            bet_exists: bool,
+            block_index: u64,
+            panic_button_state: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let mut context = get_context(vec![], false, 0, 1000, SIGNER_ACCOUNT.to_string());
+                context.block_index = block_index;
+                context.random_seed = seed.to_be_bytes().to_vec();
+                testing_env!(context);
+                let game_id = "teste.near".to_string();
+                let base_token = format!("{}-token.testnet", seed);
+                let user_balance = 100;
+                let house_funds = 1_000_000;
+                let potential_win = 500;
+                let bet_id = 7;
+
+                let mut contract = sample_contract(seed);
+                contract.panic_button = panic_button_state;
+
+                contract.internal_deposit_storage_account(&user, 1000 * ONE_NEAR);
+                let mut account = contract.internal_get_account(&user).unwrap();
+                account.balances.insert(&base_token, &user_balance);
+                // storage paid for the pending bet on commit_bet
+                account.storage_used = 1000;
+                account.pending_bets = 1;
+                contract.internal_update_account(&user, &account);
+
+                let game_settings = PartneredGame {
+                    house_funds,
+                    reserved_funds: potential_win,
+                    // pays 500 for a 250 net_bet at odds 128
+                    bet_payment_adjustment: FRACTIONAL_BASE,
+                    ..test_game(&base_token)
+                };
+                contract.games.insert(&game_id, &game_settings);
+
+                if bet_exists {
+                    contract.pending_bets.insert(
+                        &bet_id,
+                        &PendingBet {
+                            account_id: user.clone(),
+                            game_code: game_id.clone(),
+                            bet_size: 250,
+                            net_bet: 250,
+                            odds: 128,
+                            direction: RollDirection::Under,
+                            target_multiplier: 0,
+                            game_parameters: GameParameters::from(&game_settings),
+                            potential_win,
+                            commit_block: 10,
+                        },
+                    );
+                }
+
+                let result = contract.reveal_bet(U128(bet_id)).outcome;
+
+                assert!(contract.pending_bets.get(&bet_id).is_none());
+                assert_eq!(contract.internal_get_account(&user).unwrap().pending_bets, 0);
+                let new_game = contract.games.get(&game_id).unwrap();
+                assert_eq!(new_game.reserved_funds, 0);
+                let new_balance = contract
+                    .internal_get_account(&user)
+                    .unwrap()
+                    .balances
+                    .get(&base_token)
+                    .unwrap();
+                if result {
+                    assert_eq!(new_balance, user_balance + potential_win);
+                    assert_eq!(new_game.house_funds, house_funds);
+                } else {
+                    assert_eq!(new_balance, user_balance);
+                    assert_eq!(new_game.house_funds, house_funds + potential_win);
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert panic button
+            (true, 20, true, Some(ERR_007.to_string())),
+            // 2. Assert pending bet exists
+            (false, 20, false, Some(ERR_408.to_string())),
+            // 3. Assert reveal delay has passed
+            (true, 10, false, Some(ERR_409.to_string())),
+            (true, 10 + REVEAL_DELAY_BLOCKS - 1, false, Some(ERR_409.to_string())),
+            // 4. Assert reveal deadline has not passed
+            (true, 10 + REVEAL_DEADLINE_BLOCKS, false, Some(ERR_419.to_string())),
+            // 5. Release reserved funds to player if they win
+            //    or back to house if they lose
+            // 6. Remove pending bet
+            (true, 10 + REVEAL_DELAY_BLOCKS, false, None),
+            (true, 10 + REVEAL_DEADLINE_BLOCKS - 1, false, None),
+            (true, 20, false, None),
+            (true, 20, false, None),
+            (true, 20, false, None),
+            (true, 20, false, None),
+            (true, 20, false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// expire_bet
+    /// method must:
+    /// 1. Assert panic button
+    /// 2. Assert pending bet exists
+    /// 3. Assert reveal deadline has passed
+    /// 4. Release reserved funds back to house, the bet is lost
+    /// 5. Remove pending bet, also of players that unregistered
+    #[test]
+    fn test_expire_bet() {
+        fn closure_generator(
+            bet_exists: bool,
+            block_index: u64,
+            panic_button_state: bool,
+            registered: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let mut context = get_context(vec![], false, 0, 1000, SIGNER_ACCOUNT.to_string());
+                context.block_index = block_index;
+                testing_env!(context);
+                let game_id = "expire".to_string();
+                let base_token = format!("{}-token.testnet", seed);
+                let user_balance = 100;
+                let house_funds = 1_000_000;
+                let potential_win = 500;
+                let bet_id = 7;
+
+                let mut contract = sample_contract(seed);
+                contract.panic_button = panic_button_state;
+                if registered {
+                    contract.internal_deposit_storage_account(&user, 1000 * ONE_NEAR);
+                    let mut account = contract.internal_get_account(&user).unwrap();
+                    account.balances.insert(&base_token, &user_balance);
+                    // storage paid for the pending bet on commit_bet
+                    account.storage_used = 1000;
+                    account.pending_bets = 1;
+                    contract.internal_update_account(&user, &account);
+                }
+
+                let game_settings = PartneredGame {
+                    house_funds,
+                    reserved_funds: potential_win,
+                    ..test_game(&base_token)
+                };
+                contract.games.insert(&game_id, &game_settings);
+                if bet_exists {
+                    contract.pending_bets.insert(
+                        &bet_id,
+                        &PendingBet {
+                            account_id: user.clone(),
+                            game_code: game_id.clone(),
+                            bet_size: 250,
+                            net_bet: 250,
+                            odds: 128,
+                            direction: RollDirection::Under,
+                            target_multiplier: 0,
+                            game_parameters: GameParameters::from(&game_settings),
+                            potential_win,
+                            commit_block: 10,
+                        },
+                    );
+                }
+                let mut context = get_context(vec![], false, 0, 1000, SIGNER_ACCOUNT.to_string());
+                context.block_index = block_index;
+                context.storage_usage = env::storage_usage();
+                testing_env!(context);
+
+                contract.expire_bet(U128(bet_id));
+
+                // 4. Release reserved funds back to house, the bet is lost
+                let new_game = contract.games.get(&game_id).unwrap();
+                assert_eq!(new_game.reserved_funds, 0);
+                assert_eq!(new_game.house_funds, house_funds + potential_win);
+                assert!(near_sdk::test_utils::get_logs()[0].contains("\"event\":\"bet_expired\""));
+                if let Some(account) = contract.internal_get_account(&user) {
+                    assert_eq!(account.balances.get(&base_token), Some(user_balance));
+                    assert!(account.storage_used < 1000);
+                    assert_eq!(account.pending_bets, 0);
+                }
+
+                // 5. Remove pending bet, also of players that unregistered
+                assert!(contract.pending_bets.get(&bet_id).is_none());
+                assert!(contract.bets.get(&bet_id).is_none());
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert panic button
+            (true, 10 + REVEAL_DEADLINE_BLOCKS, true, true, Some(ERR_007.to_string())),
+            // 2. Assert pending bet exists
+            (false, 10 + REVEAL_DEADLINE_BLOCKS, false, true, Some(ERR_408.to_string())),
+            // 3. Assert reveal deadline has passed
+            (true, 10 + REVEAL_DEADLINE_BLOCKS - 1, false, true, Some(ERR_420.to_string())),
+            // 4. Release reserved funds back to house, the bet is lost
+            (true, 10 + REVEAL_DEADLINE_BLOCKS, false, true, None),
+            // 5. Remove pending bet, also of players that unregistered
+            (true, 10 + REVEAL_DEADLINE_BLOCKS + 1, false, false, None),
         ];
 
         let mut counter = 0;
@@ -0,0 +1,408 @@
+use crate::*;
+use crate::events::*;
+use crate::random::random_value;
+
+#[near_bindgen]
+impl Contract {
+    //opens a player versus player coin flip on a game, staking credits of the game's token.
+    //the stake is held until another player matches it with accept_challenge or the creator
+    //cancels it. Returns the id of the challenge
+    pub fn open_challenge(&mut self, game_code: GameId, stake: U128) -> U128 {
+        self.assert_panic_button();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let game = self.internal_get_game(&game_code).expect(ERR_002);
+        let credits = account.balances.get(&game.partner_token).unwrap_or(0);
+        assert_stake(&game, credits, stake.0);
+
+        let challenge_id = self.next_id;
+        let challenge = Challenge {
+            creator: account_id.clone(),
+            game_code,
+            token_contract: game.partner_token.clone(),
+            stake: stake.0,
+            open_block: env::block_index(),
+        };
+        self.challenges.insert(&challenge_id, &challenge);
+        self.next_id += 1;
+        ContractEvent::ChallengeOpened(ChallengeData {
+            challenge_id: U128(challenge_id),
+            challenge: &challenge,
+        })
+        .emit();
+
+        // creator pays for the storage of the challenge until it is matched or cancelled
+        account
+            .balances
+            .insert(&game.partner_token, &(credits - stake.0));
+        account.open_challenges += 1;
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        U128(challenge_id)
+    }
+
+    //cancels a challenge that was not matched yet, refunding the stake to its creator
+    pub fn cancel_challenge(&mut self, challenge_id: U128) {
+        self.assert_panic_button();
+        let account_id = env::predecessor_account_id();
+        let challenge = self.challenges.get(&challenge_id.0).expect(ERR_601);
+        assert!(challenge.creator == account_id, "{}", ERR_602);
+
+        let initial_storage = env::storage_usage();
+        self.challenges.remove(&challenge_id.0);
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let credits = account.balances.get(&challenge.token_contract).unwrap_or(0);
+        account
+            .balances
+            .insert(&challenge.token_contract, &(credits + challenge.stake));
+        account.open_challenges -= 1;
+        ContractEvent::ChallengeCancelled(ChallengeData {
+            challenge_id,
+            challenge: &challenge,
+        })
+        .emit();
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+    }
+
+    //matches an open challenge with the same stake and flips the coin. The winner is credited
+    //both stakes minus the game's nft, owner and partner fees, house_funds are never touched
+    pub fn accept_challenge(&mut self, challenge_id: U128) -> ChallengeReceipt {
+        self.assert_panic_button();
+        let challenger = env::predecessor_account_id();
+        let challenge = self.challenges.get(&challenge_id.0).expect(ERR_601);
+        assert!(challenge.creator != challenger, "{}", ERR_603);
+
+        let mut challenger_account = self.internal_get_account(&challenger).expect(ERR_001);
+        let mut game = self.internal_get_game(&challenge.game_code).expect(ERR_002);
+        assert!(!game.is_blocked(), "{}", ERR_418);
+        let mut challenger_credits = challenger_account
+            .balances
+            .get(&challenge.token_contract)
+            .unwrap_or(0);
+        assert!(challenger_credits >= challenge.stake, "{}", ERR_402);
+        challenger_credits -= challenge.stake;
+
+        let pot = challenge.stake * 2;
+        let fees = FeeCuts::without_house(pot, &GameParameters::from(&game));
+        let prize = pot - fees.total();
+        let seed = env::random_seed();
+        let roll = random_value(&seed, &challenge.creator, challenge_id.0, &challenge.game_code, 2);
+        let winner = if roll == 0 {
+            challenge.creator.clone()
+        } else {
+            challenger.clone()
+        };
+
+        // storage freed by the challenge goes back to its creator
+        let initial_storage = env::storage_usage();
+        self.challenges.remove(&challenge_id.0);
+        let mut creator_account = self.internal_get_account(&challenge.creator).expect(ERR_001);
+        creator_account.open_challenges -= 1;
+        if winner == challenge.creator {
+            let creator_credits = creator_account
+                .balances
+                .get(&challenge.token_contract)
+                .unwrap_or(0);
+            creator_account
+                .balances
+                .insert(&challenge.token_contract, &(creator_credits + prize));
+        } else {
+            challenger_credits += prize;
+        }
+        self.internal_update_account_storage_check(&challenge.creator, creator_account, initial_storage);
+
+        let initial_storage = env::storage_usage();
+        self.internal_accrue_fees(&challenge.game_code, &mut game, &fees);
+        let receipt = ChallengeReceipt {
+            challenge_id: challenge_id.0,
+            creator: challenge.creator,
+            challenger: challenger.clone(),
+            game_code: challenge.game_code,
+            token_contract: challenge.token_contract,
+            stake: challenge.stake,
+            fees,
+            seed: Base64VecU8(seed),
+            roll,
+            winner,
+            prize,
+        };
+        ContractEvent::ChallengeSettled(&receipt).emit();
+
+        challenger_account
+            .balances
+            .insert(&receipt.token_contract, &challenger_credits);
+        self.internal_update_game(&receipt.game_code, &game);
+        self.internal_update_account_storage_check(&challenger, challenger_account, initial_storage);
+        receipt
+    }
+}
+
+// stakes follow the bet size limits of the game and are suspended with its bets
+fn assert_stake(game: &PartneredGame, credits: u128, stake: u128) {
+    assert!(!game.is_blocked(), "{}", ERR_418);
+    assert!(credits >= stake, "{}", ERR_402);
+    assert!(stake >= game.min_bet, "{}. Minimum is {}", ERR_403, game.min_bet);
+    assert!(stake <= game.max_bet, "{}. Maximum is {}", ERR_404, game.max_bet);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    const HOUSE_FUNDS: u128 = 1_000_000;
+    const CREDITS: u128 = 10_000;
+    const MIN_STAKE: u128 = 10;
+    const MAX_STAKE: u128 = 1_000;
+    const PARTNER_FEE: u128 = 1_000;
+    const NFT_FEE: u128 = 500;
+    const OWNER_FEE: u128 = 500;
+
+    // creates a game with fees, funds creator and challenger with credits of its token
+    // and leaves the creator as signer with the seed as random_seed
+    fn sample_pvp_contract(seed: u128, creator: &AccountId, challenger: &AccountId) -> (Contract, GameId, AccountId) {
+        let token = format!("{}-token.testnet", seed);
+        let game_id = "pvp".to_string();
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.game_count = seed;
+        contract.next_id = seed;
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &token);
+        insert_test_game(
+            &mut contract,
+            &game_id,
+            &PartneredGame {
+                partner_owner: "partner.testnet".to_string(),
+                partner_fee: PARTNER_FEE,
+                house_fee: 1_000,
+                max_bet: MAX_STAKE,
+                min_bet: MIN_STAKE,
+                nft_fee: NFT_FEE,
+                owner_fee: OWNER_FEE,
+                ..test_game(&token)
+            },
+        );
+        let mut game = contract.games.get(&game_id).unwrap();
+        game.house_funds = HOUSE_FUNDS;
+        contract.games.insert(&game_id, &game);
+
+        for account_id in [creator, challenger].iter() {
+            contract.internal_deposit_storage_account(account_id, 1000 * ONE_NEAR);
+            let mut account = contract.internal_get_account(account_id).unwrap();
+            account.balances.insert(&token, &CREDITS);
+            contract.internal_update_account(account_id, &account);
+        }
+        set_signer(creator, seed);
+        (contract, game_id, token)
+    }
+
+    fn set_signer(account_id: &AccountId, seed: u128) {
+        let mut context = get_context(vec![], false, 0, 1000, account_id.clone());
+        context.random_seed = seed.to_be_bytes().to_vec();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+    }
+
+    // blocks the game the way its owner would, bets and challenges are suspended
+    fn block_game(contract: &mut Contract, game_id: &GameId, blocked: bool) {
+        let mut game = contract.games.get(game_id).unwrap();
+        game.blocked = blocked;
+        contract.games.insert(game_id, &game);
+    }
+
+    fn credits(contract: &Contract, account_id: &AccountId, token: &AccountId) -> u128 {
+        contract.get_credits(token.clone(), account_id.clone()).0
+    }
+
+    /// open_challenge
+    /// method must:
+    /// 1. Assert the game exists and is not blocked
+    /// 2. Assert the creator has the stake and it respects the game bet limits
+    /// 3. Hold the stake and store the challenge under next_id, leaving game_count to bets
+    /// 4. Charge the storage of the challenge to the creator
+    #[test]
+    fn test_open_challenge() {
+        fn closure_generator(game_code: Option<GameId>, stake: u128, blocked: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let creator = format!("creator-{}.testnet", seed);
+                let challenger = format!("challenger-{}.testnet", seed);
+                let (mut contract, game_id, token) = sample_pvp_contract(seed, &creator, &challenger);
+                block_game(&mut contract, &game_id, blocked);
+                let storage_used = contract.internal_get_account(&creator).unwrap().storage_used;
+
+                let challenge_id = contract.open_challenge(game_code.unwrap_or(game_id.clone()), U128(stake));
+
+                assert_eq!(challenge_id, U128(seed));
+                assert_eq!(contract.next_id, seed + 1);
+                assert_eq!(contract.game_count, seed);
+                assert_eq!(
+                    contract.challenges.get(&seed).unwrap(),
+                    Challenge {
+                        creator: creator.clone(),
+                        game_code: game_id,
+                        token_contract: token.clone(),
+                        stake,
+                        open_block: 0,
+                    }
+                );
+                assert_eq!(credits(&contract, &creator, &token), CREDITS - stake);
+                assert!(contract.internal_get_account(&creator).unwrap().storage_used > storage_used);
+                assert_eq!(contract.internal_get_account(&creator).unwrap().open_challenges, 1);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the game exists and is not blocked
+            (Some("no-game".to_string()), 100, false, Some(ERR_002.to_string())),
+            (None, 100, true, Some(ERR_418.to_string())),
+            // 2. Assert the creator has the stake and it respects the game bet limits
+            (None, CREDITS + 1, false, Some(ERR_402.to_string())),
+            (None, MIN_STAKE - 1, false, Some(ERR_403.to_string())),
+            (None, MAX_STAKE + 1, false, Some(ERR_404.to_string())),
+            // 3. Hold the stake and store the challenge under next_id, leaving game_count to bets
+            // 4. Charge the storage of the challenge to the creator
+            (None, MIN_STAKE, false, None),
+            (None, MAX_STAKE, false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+        });
+    }
+
+    /// cancel_challenge
+    /// method must:
+    /// 1. Assert the challenge is open
+    /// 2. Only allow the creator to cancel
+    /// 3. Refund the stake and release the storage of the challenge
+    #[test]
+    fn test_cancel_challenge() {
+        fn closure_generator(challenge_id: Option<u128>, caller_is_creator: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let creator = format!("creator-{}.testnet", seed);
+                let challenger = format!("challenger-{}.testnet", seed);
+                let (mut contract, game_id, token) = sample_pvp_contract(seed, &creator, &challenger);
+                let storage_used = contract.internal_get_account(&creator).unwrap().storage_used;
+                let opened_id = contract.open_challenge(game_id, U128(100));
+
+                if !caller_is_creator {
+                    set_signer(&challenger, seed);
+                }
+                contract.cancel_challenge(U128(challenge_id.unwrap_or(opened_id.0)));
+
+                assert!(contract.challenges.get(&opened_id.0).is_none());
+                assert_eq!(credits(&contract, &creator, &token), CREDITS);
+                assert_eq!(contract.internal_get_account(&creator).unwrap().storage_used, storage_used);
+                assert_eq!(contract.internal_get_account(&creator).unwrap().open_challenges, 0);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the challenge is open
+            (Some(u128::MAX), true, Some(ERR_601.to_string())),
+            // 2. Only allow the creator to cancel
+            (None, false, Some(ERR_602.to_string())),
+            // 3. Refund the stake and release the storage of the challenge
+            (None, true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            counter += 1;
+        });
+    }
+
+    /// accept_challenge
+    /// method must:
+    /// 1. Assert the challenge is open, its game is not blocked and it is not accepted by its creator
+    /// 2. Assert the challenger has the stake
+    /// 3. Credit both stakes minus nft, owner and partner fees to the winner of the roll
+    /// 4. Leave house_funds untouched
+    /// 5. Close the challenge, releasing its storage to the creator
+    #[test]
+    fn test_accept_challenge() {
+        fn closure_generator(
+            challenge_id: Option<u128>,
+            caller_is_creator: bool,
+            challenger_credits: u128,
+            blocked: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let creator = format!("creator-{}.testnet", seed);
+                let challenger = format!("challenger-{}.testnet", seed);
+                let (mut contract, game_id, token) = sample_pvp_contract(seed, &creator, &challenger);
+                let storage_used = contract.internal_get_account(&creator).unwrap().storage_used;
+                let stake = 1_000;
+                let opened_id = contract.open_challenge(game_id.clone(), U128(stake));
+                block_game(&mut contract, &game_id, blocked);
+
+                if !caller_is_creator {
+                    let mut account = contract.internal_get_account(&challenger).unwrap();
+                    account.balances.insert(&token, &challenger_credits);
+                    contract.internal_update_account(&challenger, &account);
+                    set_signer(&challenger, seed);
+                }
+                let receipt = contract.accept_challenge(U128(challenge_id.unwrap_or(opened_id.0)));
+
+                let pot = 2 * stake;
+                let nft_cut = (pot * NFT_FEE) / FRACTIONAL_BASE;
+                let owner_cut = (pot * OWNER_FEE) / FRACTIONAL_BASE;
+                let partner_cut = (pot * PARTNER_FEE) / FRACTIONAL_BASE;
+                let prize = pot - nft_cut - owner_cut - partner_cut;
+                assert_eq!(receipt.prize, prize);
+                assert_eq!(receipt.fees.house_cut, 0);
+                assert!(receipt.roll < 2);
+                let (winner, loser, loser_credits) = if receipt.roll == 0 {
+                    (creator.clone(), challenger.clone(), challenger_credits)
+                } else {
+                    (challenger.clone(), creator.clone(), CREDITS)
+                };
+                assert_eq!(receipt.winner, winner);
+                let winner_credits = if winner == creator { CREDITS } else { challenger_credits };
+                assert_eq!(credits(&contract, &winner, &token), winner_credits - stake + prize);
+                assert_eq!(credits(&contract, &loser, &token), loser_credits - stake);
+
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(game.house_funds, HOUSE_FUNDS);
+                assert_eq!(game.partner_balance, partner_cut);
+                assert_eq!(contract.nft_balance.get(&token).unwrap(), nft_cut);
+                assert_eq!(contract.owner_balance.get(&token).unwrap(), owner_cut);
+
+                assert!(contract.challenges.get(&opened_id.0).is_none());
+                assert_eq!(contract.internal_get_account(&creator).unwrap().storage_used, storage_used);
+                assert_eq!(contract.internal_get_account(&creator).unwrap().open_challenges, 0);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the challenge is open, its game is not blocked and it is not accepted by its creator
+            (Some(u128::MAX), false, CREDITS, false, Some(ERR_601.to_string())),
+            (None, false, CREDITS, true, Some(ERR_418.to_string())),
+            (None, true, CREDITS, false, Some(ERR_603.to_string())),
+            // 2. Assert the challenger has the stake
+            (None, false, 999, false, Some(ERR_402.to_string())),
+            // 3. Credit both stakes minus nft, owner and partner fees to the winner of the roll
+            // 4. Leave house_funds untouched
+            // 5. Close the challenge, releasing its storage to the creator
+            (None, false, CREDITS, false, None),
+            (None, false, CREDITS, false, None),
+            (None, false, CREDITS, false, None),
+            (None, false, CREDITS, false, None),
+            (None, false, 1_000, false, None),
+            (None, false, 1_000, false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            counter += 1;
+        });
+    }
+}
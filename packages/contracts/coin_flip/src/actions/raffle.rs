@@ -0,0 +1,604 @@
+use crate::*;
+use crate::events::*;
+use crate::random::raffle_ticket;
+
+#[near_bindgen]
+impl Contract {
+    //opens a raffle round on a game, only callable by the partner. Tickets cost ticket_price
+    //credits of the game's token until closes_at (block timestamp in nanoseconds).
+    //winner_shares split the prize between winner places, defaults to a single winner
+    pub fn open_raffle(
+        &mut self,
+        game_code: GameId,
+        ticket_price: U128,
+        closes_at: U64,
+        winner_shares: Option<Vec<u32>>,
+    ) -> U128 {
+        self.assert_panic_button();
+        let game = self.internal_get_game(&game_code).expect(ERR_002);
+        assert!(game.partner_owner == env::predecessor_account_id(), "{}", ERR_004);
+        assert!(ticket_price.0 > 0 && closes_at.0 > env::block_timestamp(), "{}", ERR_701);
+        let winner_shares = winner_shares.unwrap_or_else(|| vec![FRACTIONAL_BASE as u32]);
+        if let Err(err) = RaffleRound::validate_shares(&winner_shares) {
+            panic!("{}", err);
+        }
+
+        // rounds are paid for by the contract like the games they belong to
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        let round_id = self.next_id;
+        let round = RaffleRound::new(
+            round_id,
+            game_code,
+            game.partner_token,
+            ticket_price.0,
+            closes_at.0,
+            winner_shares,
+        );
+        self.raffles.insert(&round_id, &round);
+        self.open_raffles.insert(&round_id);
+        self.next_id += 1;
+        ContractEvent::RaffleOpened(RaffleData {
+            round_id: U128(round_id),
+            round: &round,
+        })
+        .emit();
+
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+        U128(round_id)
+    }
+
+    //buys tickets of an open raffle round with credits, returns how many tickets
+    //of the round the caller holds
+    pub fn buy_raffle_tickets(&mut self, round_id: U128, tickets: U64) -> U64 {
+        self.assert_panic_button();
+        let account_id = env::predecessor_account_id();
+        let mut round = self.raffles.get(&round_id.0).expect(ERR_703);
+        assert!(!round.drawn && env::block_timestamp() < round.closes_at, "{}", ERR_704);
+        assert!(tickets.0 > 0, "{}", ERR_706);
+
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let credits = account.balances.get(&round.token_contract).unwrap_or(0);
+        let cost = tickets.0 as u128 * round.ticket_price;
+        assert!(credits >= cost, "{}", ERR_402);
+
+        let count = round.add_tickets(&account_id, tickets.0);
+        self.raffles.insert(&round_id.0, &round);
+        ContractEvent::RaffleTicketsBought(RaffleTicketsData {
+            round_id,
+            account_id: &account_id,
+            tickets,
+            cost: U128(cost),
+        })
+        .emit();
+
+        // buyers pay for the storage of their tickets
+        account.balances.insert(&round.token_contract, &(credits - cost));
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        U64(count)
+    }
+
+    //closes ticket sales of a round past closes_at, callable by anyone. Its winners are drawn
+    //with the seed of a block REVEAL_DELAY_BLOCKS later, unknown while the round is closed.
+    //Returns the block the round can be drawn from
+    pub fn close_raffle(&mut self, round_id: U128) -> U64 {
+        self.assert_panic_button();
+        let mut round = self.raffles.get(&round_id.0).expect(ERR_703);
+        assert!(round.draw_block.is_none(), "{}", ERR_708);
+        assert!(env::block_timestamp() >= round.closes_at, "{}", ERR_705);
+
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        let draw_block = U64(env::block_index() + REVEAL_DELAY_BLOCKS);
+        round.draw_block = Some(draw_block);
+        self.raffles.insert(&round_id.0, &round);
+        ContractEvent::RaffleClosed(RaffleData {
+            round_id,
+            round: &round,
+        })
+        .emit();
+
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+        draw_block
+    }
+
+    //draws the winners of a closed raffle round, callable by anyone from its draw_block. The pot
+    //is charged the game fees and the rest is split between winners by winner_shares
+    pub fn draw_raffle(&mut self, round_id: U128) -> Vec<RaffleWinner> {
+        self.assert_panic_button();
+        let mut round = self.raffles.get(&round_id.0).expect(ERR_703);
+        assert!(!round.drawn, "{}", ERR_707);
+        let draw_block = round.draw_block.expect(ERR_709).0;
+        assert!(
+            env::block_index() >= draw_block,
+            "{}. Round can be drawn from block {}",
+            ERR_709,
+            draw_block
+        );
+
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        let mut game = self.internal_get_game(&round.game_code).expect(ERR_002);
+        let fees = FeeCuts::new(round.pot, &GameParameters::from(&game));
+        self.internal_accrue_fees(&round.game_code, &mut game, &fees);
+
+        // every ticket wins at most once, places without a ticket left are not drawn
+        let places = round.winner_shares.len().min(round.tickets_sold as usize);
+        let prizes = round.split_prize(round.pot - fees.total(), places);
+        let seed = env::random_seed();
+        let mut tickets: Vec<u64> = Vec::with_capacity(places);
+        let mut attempt = 0;
+        while tickets.len() < places {
+            let ticket = raffle_ticket(&seed, round_id.0, &round.game_code, attempt, round.tickets_sold);
+            attempt += 1;
+            if !tickets.contains(&ticket) {
+                tickets.push(ticket);
+            }
+        }
+        for (ticket, prize) in tickets.into_iter().zip(prizes) {
+            round.winners.push(RaffleWinner {
+                account_id: round.ticket_owner(ticket),
+                ticket,
+                prize,
+            });
+        }
+
+        round.drawn = true;
+        self.raffles.insert(&round_id.0, &round);
+        self.open_raffles.remove(&round_id.0);
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+
+        // winners pay for the storage of their prize, prizes that cannot be credited go to the house
+        for winner in round.winners.iter() {
+            if !self.internal_credit_prize(&winner.account_id, &round.token_contract, winner.prize) {
+                game.house_funds += winner.prize;
+            }
+        }
+        self.internal_update_game(&round.game_code, &game);
+        ContractEvent::RaffleDrawn(RaffleData {
+            round_id,
+            round: &round,
+        })
+        .emit();
+        round.winners
+    }
+}
+
+impl Contract {
+    // credits a prize to a winner, charging the winner the storage of a new balance entry.
+    // Returns false, leaving storage untouched, for winners that unregistered since buying
+    // their tickets or that cannot pay for the entry, so that no winner can block a draw
+    fn internal_credit_prize(&mut self, account_id: &AccountId, token_contract: &AccountId, prize: u128) -> bool {
+        let mut account = match self.internal_get_account(account_id) {
+            Some(account) => account,
+            None => return false,
+        };
+        let initial_storage = env::storage_usage();
+        let credits = account.balances.get(token_contract).unwrap_or(0);
+        account.balances.insert(token_contract, &(credits + prize));
+        let storage_cost = (env::storage_usage() - initial_storage) as u128 * env::storage_byte_cost();
+        if storage_cost > account.storage_funds_available() {
+            account.balances.remove(token_contract);
+            return false;
+        }
+        self.internal_update_account_storage_check(account_id, account, initial_storage);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    const CREDITS: u128 = 10_000;
+    const TICKET_PRICE: u128 = 100;
+    const CLOSES_AT: u64 = 1_000;
+    const HOUSE_FEE: u128 = 1_000;
+    const PARTNER_FEE: u128 = 2_000;
+    const NFT_FEE: u128 = 500;
+    const OWNER_FEE: u128 = 500;
+
+    fn partner(seed: u128) -> AccountId {
+        format!("partner-{}.testnet", seed)
+    }
+
+    fn player(seed: u128, index: usize) -> AccountId {
+        format!("player-{}-{}.testnet", index, seed)
+    }
+
+    fn set_context(account_id: &AccountId, block_timestamp: u64, seed: u128) {
+        let mut context = get_context(vec![], false, 0, 1000 * ONE_NEAR, account_id.clone());
+        context.block_timestamp = block_timestamp;
+        context.random_seed = seed.to_be_bytes().to_vec();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+    }
+
+    fn set_block_context(block_index: u64, seed: u128) {
+        let mut context = get_context(vec![], false, 0, 1000 * ONE_NEAR, SIGNER_ACCOUNT.to_string());
+        context.block_timestamp = CLOSES_AT;
+        context.block_index = block_index;
+        context.random_seed = seed.to_be_bytes().to_vec();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+    }
+
+    // creates a game with fees and funds players with credits of its token
+    fn sample_raffle_contract(seed: u128, players: usize) -> (Contract, GameId, AccountId) {
+        let token = format!("{}-token.testnet", seed);
+        let game_id = "raffle".to_string();
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.game_count = seed;
+        contract.next_id = seed;
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &token);
+        insert_test_game(
+            &mut contract,
+            &game_id,
+            &PartneredGame {
+                partner_owner: partner(seed),
+                partner_fee: PARTNER_FEE,
+                house_fee: HOUSE_FEE,
+                max_bet: 1_000,
+                nft_fee: NFT_FEE,
+                owner_fee: OWNER_FEE,
+                ..test_game(&token)
+            },
+        );
+        for index in 0..players {
+            let account_id = player(seed, index);
+            contract.internal_deposit_storage_account(&account_id, 1000 * ONE_NEAR);
+            let mut account = contract.internal_get_account(&account_id).unwrap();
+            account.balances.insert(&token, &CREDITS);
+            contract.internal_update_account(&account_id, &account);
+        }
+        (contract, game_id, token)
+    }
+
+    /// open_raffle
+    /// method must:
+    /// 1. Assert the game exists and caller is its partner
+    /// 2. Assert the round has a ticket price and closes in the future
+    /// 3. Assert winner shares are valid, defaulting to a single winner
+    /// 4. Store the open round under next_id, leaving game_count to bets
+    #[test]
+    fn test_open_raffle() {
+        fn closure_generator(
+            game_code: Option<GameId>,
+            caller_is_partner: bool,
+            ticket_price: u128,
+            closes_at: u64,
+            winner_shares: Option<Vec<u32>>,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_raffle_contract(seed, 0);
+                let caller = if caller_is_partner { partner(seed) } else { player(seed, 0) };
+                set_context(&caller, 10, seed);
+
+                let round_id = contract.open_raffle(
+                    game_code.unwrap_or_else(|| game_id.clone()),
+                    U128(ticket_price),
+                    U64(closes_at),
+                    winner_shares.clone(),
+                );
+
+                assert_eq!(round_id, U128(seed));
+                assert_eq!(contract.next_id, seed + 1);
+                assert_eq!(contract.game_count, seed);
+                let round = contract.view_raffle(round_id);
+                assert_eq!(round.game_code, game_id);
+                assert_eq!(round.token_contract, token);
+                assert_eq!(round.ticket_price, ticket_price);
+                assert_eq!(round.closes_at, closes_at);
+                assert_eq!(round.winner_shares, winner_shares.unwrap_or_else(|| vec![FRACTIONAL_BASE as u32]));
+                assert!(!round.drawn);
+                let open = contract.open_raffles(None, None);
+                assert_eq!(open.len(), 1);
+                assert_eq!(open[0].0, round_id);
+            }
+        }
+
+        let base = FRACTIONAL_BASE as u32;
+        let test_cases = [
+            // 1. Assert the game exists and caller is its partner
+            (Some("no-game".to_string()), true, TICKET_PRICE, CLOSES_AT, None, Some(ERR_002.to_string())),
+            (None, false, TICKET_PRICE, CLOSES_AT, None, Some(ERR_004.to_string())),
+            // 2. Assert the round has a ticket price and closes in the future
+            (None, true, 0, CLOSES_AT, None, Some(ERR_701.to_string())),
+            (None, true, TICKET_PRICE, 10, None, Some(ERR_701.to_string())),
+            // 3. Assert winner shares are valid, defaulting to a single winner
+            (None, true, TICKET_PRICE, CLOSES_AT, Some(vec![base / 2]), Some(ERR_702.to_string())),
+            (None, true, TICKET_PRICE, CLOSES_AT, Some(vec![]), Some(ERR_702.to_string())),
+            // 4. Store the open round under next_id, leaving game_count to bets
+            (None, true, TICKET_PRICE, CLOSES_AT, None, None),
+            (None, true, TICKET_PRICE, CLOSES_AT, Some(vec![base / 2, base / 4, base / 4]), None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, counter), v.5);
+            counter += 1;
+        });
+    }
+
+    /// buy_raffle_tickets
+    /// method must:
+    /// 1. Assert the round exists and is still selling tickets
+    /// 2. Assert at least one ticket is bought with enough credits
+    /// 3. Charge the tickets and add them to the pot
+    /// 4. Count the tickets of each buyer
+    #[test]
+    fn test_buy_raffle_tickets() {
+        fn closure_generator(round_id: Option<u128>, block_timestamp: u64, tickets: u64, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_raffle_contract(seed, 1);
+                set_context(&partner(seed), 0, seed);
+                let opened_id = contract.open_raffle(game_id, U128(TICKET_PRICE), U64(CLOSES_AT), None);
+                let buyer = player(seed, 0);
+                set_context(&buyer, block_timestamp, seed);
+
+                let round_id = U128(round_id.unwrap_or(opened_id.0));
+                assert_eq!(contract.buy_raffle_tickets(round_id, U64(tickets)), U64(tickets));
+                assert_eq!(contract.buy_raffle_tickets(round_id, U64(1)), U64(tickets + 1));
+
+                let cost = (tickets as u128 + 1) * TICKET_PRICE;
+                assert_eq!(contract.get_credits(token, buyer.clone()).0, CREDITS - cost);
+                let round = contract.view_raffle(round_id);
+                assert_eq!(round.tickets_sold, tickets + 1);
+                assert_eq!(round.pot, cost);
+                assert_eq!(contract.raffle_tickets(round_id, buyer), U64(tickets + 1));
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the round exists and is still selling tickets
+            (Some(u128::MAX), 0, 1, Some(ERR_703.to_string())),
+            (None, CLOSES_AT, 1, Some(ERR_704.to_string())),
+            // 2. Assert at least one ticket is bought with enough credits
+            (None, 0, 0, Some(ERR_706.to_string())),
+            (None, 0, (CREDITS / TICKET_PRICE) as u64 + 1, Some(ERR_402.to_string())),
+            // 3. Charge the tickets and add them to the pot
+            // 4. Count the tickets of each buyer
+            (None, 0, 1, None),
+            (None, CLOSES_AT - 1, 10, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+        });
+    }
+
+    /// close_raffle
+    /// method must:
+    /// 1. Assert the round exists, was not closed and is past closes_at
+    /// 2. Set the draw block REVEAL_DELAY_BLOCKS ahead, leaving the round open until drawn
+    #[test]
+    fn test_close_raffle() {
+        fn closure_generator(round_id: Option<u128>, block_timestamp: u64, close_twice: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, _) = sample_raffle_contract(seed, 0);
+                set_context(&partner(seed), 0, seed);
+                let opened_id = contract.open_raffle(game_id, U128(TICKET_PRICE), U64(CLOSES_AT), None);
+
+                set_context(&SIGNER_ACCOUNT.to_string(), block_timestamp, seed);
+                let round_id = U128(round_id.unwrap_or(opened_id.0));
+                let draw_block = contract.close_raffle(round_id);
+                if close_twice {
+                    contract.close_raffle(round_id);
+                }
+
+                let round = contract.view_raffle(round_id);
+                assert_eq!(draw_block.0, env::block_index() + REVEAL_DELAY_BLOCKS);
+                assert_eq!(round.draw_block, Some(draw_block));
+                assert!(!round.drawn);
+                assert_eq!(contract.open_raffles(None, None).len(), 1);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert the round exists, was not closed and is past closes_at
+            (Some(u128::MAX), CLOSES_AT, false, Some(ERR_703.to_string())),
+            (None, CLOSES_AT - 1, false, Some(ERR_705.to_string())),
+            (None, CLOSES_AT, true, Some(ERR_708.to_string())),
+            // 2. Set the draw block REVEAL_DELAY_BLOCKS ahead, leaving the round open until drawn
+            (None, CLOSES_AT, false, None),
+            (None, CLOSES_AT + 1, false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+        });
+    }
+
+    /// draw_raffle
+    /// method must:
+    /// 1. Assert the round exists, was not drawn and its draw block was reached
+    /// 2. Charge the game fees on the pot
+    /// 3. Draw distinct winning tickets, at most one per place
+    /// 4. Credit each winner its share of the prize
+    /// 5. Move the round out of the open rounds, keeping its winners
+    #[test]
+    fn test_draw_raffle() {
+        fn closure_generator(
+            round_id: Option<u128>,
+            draw_delay: Option<u64>,
+            tickets: Vec<u64>,
+            winner_shares: Vec<u32>,
+            draw_twice: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_raffle_contract(seed, tickets.len());
+                set_context(&partner(seed), 0, seed);
+                let opened_id =
+                    contract.open_raffle(game_id.clone(), U128(TICKET_PRICE), U64(CLOSES_AT), Some(winner_shares.clone()));
+                for (index, count) in tickets.iter().enumerate() {
+                    set_context(&player(seed, index), 0, seed);
+                    contract.buy_raffle_tickets(opened_id, U64(*count));
+                }
+
+                set_block_context(10, seed);
+                if let Some(draw_delay) = draw_delay {
+                    contract.close_raffle(opened_id);
+                    set_block_context(10 + draw_delay, seed);
+                }
+                let round_id = U128(round_id.unwrap_or(opened_id.0));
+                let winners = contract.draw_raffle(round_id);
+                if draw_twice {
+                    contract.draw_raffle(round_id);
+                }
+
+                let tickets_sold: u64 = tickets.iter().sum();
+                let pot = tickets_sold as u128 * TICKET_PRICE;
+                let fee = |fee: u128| (pot * fee) / FRACTIONAL_BASE;
+                let prize = pot - fee(HOUSE_FEE) - fee(PARTNER_FEE) - fee(NFT_FEE) - fee(OWNER_FEE);
+                let game = contract.games.get(&game_id).unwrap();
+                assert_eq!(game.house_funds, fee(HOUSE_FEE));
+                assert_eq!(game.partner_balance, fee(PARTNER_FEE));
+                assert_eq!(contract.nft_balance.get(&token).unwrap(), fee(NFT_FEE));
+
+                assert_eq!(winners.len(), winner_shares.len().min(tickets_sold as usize));
+                assert_eq!(winners.iter().map(|winner| winner.prize).sum::<u128>(), prize);
+                let round = contract.view_raffle(round_id);
+                for (place, winner) in winners.iter().enumerate() {
+                    assert!(winner.ticket < tickets_sold);
+                    assert!(winners[..place].iter().all(|other| other.ticket != winner.ticket));
+                    assert_eq!(winner.account_id, round.ticket_owner(winner.ticket));
+                }
+                for (index, count) in tickets.iter().enumerate() {
+                    let account_id = player(seed, index);
+                    let won: u128 = winners
+                        .iter()
+                        .filter(|winner| winner.account_id == account_id)
+                        .map(|winner| winner.prize)
+                        .sum();
+                    assert_eq!(
+                        contract.get_credits(token.clone(), account_id).0,
+                        CREDITS - *count as u128 * TICKET_PRICE + won
+                    );
+                }
+
+                assert!(round.drawn);
+                assert_eq!(contract.raffle_winners(round_id), winners);
+                assert!(contract.open_raffles(None, None).is_empty());
+            }
+        }
+
+        let base = FRACTIONAL_BASE as u32;
+        let test_cases = [
+            // 1. Assert the round exists, was not drawn and its draw block was reached
+            (Some(u128::MAX), Some(REVEAL_DELAY_BLOCKS), vec![1], vec![base], false, Some(ERR_703.to_string())),
+            (None, None, vec![1], vec![base], false, Some(ERR_709.to_string())),
+            (None, Some(REVEAL_DELAY_BLOCKS - 1), vec![1], vec![base], false, Some(ERR_709.to_string())),
+            (None, Some(REVEAL_DELAY_BLOCKS), vec![1], vec![base], true, Some(ERR_707.to_string())),
+            // 2. Charge the game fees on the pot
+            // 3. Draw distinct winning tickets, at most one per place
+            // 4. Credit each winner its share of the prize
+            // 5. Move the round out of the open rounds, keeping its winners
+            (None, Some(REVEAL_DELAY_BLOCKS), vec![], vec![base], false, None),
+            (None, Some(REVEAL_DELAY_BLOCKS), vec![1], vec![base], false, None),
+            (None, Some(REVEAL_DELAY_BLOCKS), vec![3, 5, 2], vec![base], false, None),
+            (None, Some(REVEAL_DELAY_BLOCKS), vec![3, 5, 2], vec![base / 2, base * 3 / 10, base / 5], false, None),
+            (None, Some(REVEAL_DELAY_BLOCKS), vec![1, 1], vec![base / 2, base * 3 / 10, base / 5], false, None),
+            (None, Some(REVEAL_DELAY_BLOCKS + 100), vec![10, 10, 10, 10], vec![base / 4; 4], false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, counter), v.5);
+            counter += 1;
+        });
+    }
+
+    /// draw_raffle
+    /// method must:
+    /// 1. Charge winners the storage of a new balance entry
+    /// 2. Give the house the prize of winners that unregistered or cannot pay for the entry
+    #[test]
+    fn test_draw_raffle_winner_storage() {
+        fn closure_generator(holds_credits: bool, storage_funds: bool, registered: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_raffle_contract(seed, 1);
+                let winner = player(seed, 0);
+                set_context(&partner(seed), 0, seed);
+                let round_id = contract.open_raffle(game_id.clone(), U128(TICKET_PRICE), U64(CLOSES_AT), None);
+                set_context(&winner, 0, seed);
+                contract.buy_raffle_tickets(round_id, U64(1));
+
+                let mut account = contract.internal_get_account(&winner).unwrap();
+                if !holds_credits {
+                    account.balances.remove(&token);
+                }
+                if !storage_funds {
+                    account.storage_deposit = account.storage_used as u128 * env::storage_byte_cost();
+                }
+                contract.internal_update_account(&winner, &account);
+                if !registered {
+                    contract.accounts.remove(&winner);
+                }
+
+                set_block_context(10, seed);
+                contract.close_raffle(round_id);
+                set_block_context(10 + REVEAL_DELAY_BLOCKS, seed);
+                let contract_storage = contract.internal_get_account(&CONTRACT_ACCOUNT.to_string()).unwrap().storage_used;
+                let storage = env::storage_usage();
+                let house_funds = contract.games.get(&game_id).unwrap().house_funds;
+                let prize = contract.draw_raffle(round_id)[0].prize;
+
+                // 1. Charge winners the storage of a new balance entry
+                let credited = registered && (holds_credits || storage_funds);
+                let winner_storage = match contract.internal_get_account(&winner) {
+                    Some(updated) => {
+                        let held = if holds_credits { CREDITS - TICKET_PRICE } else { 0 };
+                        let won = if credited { prize } else { 0 };
+                        assert_eq!(contract.get_credits(token.clone(), winner.clone()).0, held + won);
+                        updated.storage_used - account.storage_used
+                    }
+                    None => 0,
+                };
+                assert_eq!(winner_storage > 0, credited && !holds_credits);
+                let contract_storage =
+                    contract.internal_get_account(&CONTRACT_ACCOUNT.to_string()).unwrap().storage_used - contract_storage;
+                assert_eq!(contract_storage + winner_storage, env::storage_usage() - storage);
+
+                // 2. Give the house the prize of winners that unregistered or cannot pay for the entry
+                let house_cut = (TICKET_PRICE * HOUSE_FEE) / FRACTIONAL_BASE;
+                let forfeited = if credited { 0 } else { prize };
+                assert_eq!(contract.games.get(&game_id).unwrap().house_funds, house_funds + house_cut + forfeited);
+            }
+        }
+
+        let test_cases = [
+            // 1. Charge winners the storage of a new balance entry
+            (true, false, true, None),
+            (false, true, true, None),
+            // 2. Give the house the prize of winners that unregistered or cannot pay for the entry
+            (false, false, true, None),
+            (true, true, false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+        });
+    }
+}
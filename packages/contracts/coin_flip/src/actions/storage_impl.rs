@@ -19,7 +19,7 @@ impl StorageManagement for Contract {
         let amount = env::attached_deposit();
         let account_id = account_id
             .map(|a| a.into())
-            .unwrap_or_else(|| env::predecessor_account_id());
+            .unwrap_or_else(env::predecessor_account_id);
         let registration_only = registration_only.unwrap_or(false);
         let min_balance = self.storage_balance_bounds().min.0;
         let already_registered = self.accounts.contains_key(&account_id);
@@ -69,7 +69,12 @@ impl StorageManagement for Contract {
 
             // TODO: figure out force option logic.
             assert!(
-                account_deposit.balances.is_empty(),
+                account_deposit.balances.is_empty()
+                    && account_deposit.streaks.is_empty()
+                    && account_deposit.house_shares.is_empty()
+                    && account_deposit.house_withdrawals.is_empty()
+                    && account_deposit.pending_bets == 0
+                    && account_deposit.open_challenges == 0,
                 "{}", ERR_103
             );
             self.accounts.remove(&account_id);
@@ -99,4 +104,75 @@ impl StorageManagement for Contract {
         }
         
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    /// storage_unregister
+    /// method must:
+    /// 1. Assert one yocto is attached
+    /// 2. Assert the account holds nothing the contract still owes it
+    ///    a. queued liquidity withdrawals
+    ///    b. pending commit-reveal bets
+    ///    c. open pvp challenges
+    /// 3. Remove the account
+    #[test]
+    fn test_storage_unregister() {
+        fn closure_generator(attached_deposit: u128, holding: &'static str, seed: u128) -> impl FnOnce() {
+            move || {
+                let account_id = format!("{}-account.testnet", seed);
+                let context = get_context(vec![], false, 0, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+                testing_env!(context);
+                let mut contract = sample_contract(seed);
+                contract.internal_deposit_storage_account(&account_id, 1000 * ONE_NEAR);
+                let mut account = contract.internal_get_account(&account_id).unwrap();
+                if holding == "house_withdrawal" {
+                    let withdrawal = HouseWithdrawal {
+                        withdrawal_id: 0,
+                        provider: Some(account_id.clone()),
+                        amount: 100,
+                        unlocks_at: 0,
+                    };
+                    account.house_withdrawals.insert(&"game".to_string(), &vec![withdrawal]);
+                }
+                if holding == "pending_bet" {
+                    account.pending_bets = 1;
+                }
+                if holding == "open_challenge" {
+                    account.open_challenges = 1;
+                }
+                contract.internal_update_account(&account_id, &account);
+
+                let mut context = get_context(vec![], false, attached_deposit, 1000 * ONE_NEAR, account_id.clone());
+                context.storage_usage = env::storage_usage();
+                testing_env!(context);
+                assert!(contract.storage_unregister(None));
+
+                // 3. Remove the account
+                assert!(contract.internal_get_account(&account_id).is_none());
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert one yocto is attached
+            (0, "nothing", Some("Requires attached deposit of exactly 1 yoctoNEAR".to_string())),
+            // 2. Assert the account holds nothing the contract still owes it
+            //    a. queued liquidity withdrawals
+            (1, "house_withdrawal", Some(ERR_103.to_string())),
+            //    b. pending commit-reveal bets
+            (1, "pending_bet", Some(ERR_103.to_string())),
+            //    c. open pvp challenges
+            (1, "open_challenge", Some(ERR_103.to_string())),
+            // 3. Remove the account
+            (1, "nothing", None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            counter += 1;
+        });
+    }
+}
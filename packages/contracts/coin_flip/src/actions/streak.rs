@@ -0,0 +1,349 @@
+use crate::*;
+use crate::events::*;
+
+#[near_bindgen]
+impl Contract {
+    //lets the payout held by the caller's streak on a game ride on another bet with the
+    //chosen odds, double or nothing at even odds. The house must be able to cover the win
+    //before rolling. A win grows the streak up to the game's max_streak, a loss ends it
+    pub fn press_streak(
+        &mut self,
+        game_code: GameId,
+        odds: u32,
+        _bet_type: String,
+        direction: Option<RollDirection>,
+        target_multiplier: Option<U128>,
+    ) -> BetReceipt {
+        self.assert_panic_button();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let mut streak = account.streaks.get(&game_code).expect(ERR_414);
+        let mut game = self.internal_get_game(&game_code).expect(ERR_002);
+        assert!(streak.length < game.max_streak, "{}", ERR_416);
+
+        // the held payout is bet on top of the player's credits, so that it alone pays the bet
+        let choice = BetChoice::new(odds, direction, target_multiplier);
+        let balance = account.balances.get(&streak.token_contract).unwrap_or(0);
+        let mut credits = balance + streak.amount;
+        // settling checks the pressed bet against the house cover before rolling it
+        let receipt = self.internal_settle_bet(&account_id, &game_code, &mut game, &mut credits, streak.amount, &choice);
+        self.internal_record_bets(&game_code, &game, std::slice::from_ref(&receipt));
+
+        if receipt.outcome {
+            streak.amount = receipt.payout;
+            streak.length += 1;
+            streak.last_bet_id = receipt.bet_id;
+            account.streaks.insert(&game_code, &streak);
+            ContractEvent::StreakPressed(StreakData {
+                account_id: &account_id,
+                game_code: &game_code,
+                streak: &streak,
+            })
+            .emit();
+        } else {
+            account.streaks.remove(&game_code);
+            ContractEvent::StreakLost(StreakData {
+                account_id: &account_id,
+                game_code: &game_code,
+                streak: &streak,
+            })
+            .emit();
+        }
+        // jackpots won pressing are credited right away, only the payout rides
+        let held = if receipt.outcome { streak.amount } else { 0 };
+        account.balances.insert(&streak.token_contract, &(credits - held));
+
+        self.internal_update_game(&game_code, &game);
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        receipt
+    }
+
+    //moves the payout held by the caller's streak on a game into their credits,
+    //returns the amount collected
+    pub fn collect_streak(&mut self, game_code: GameId) -> U128 {
+        self.assert_panic_button();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+
+        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let streak = account.streaks.remove(&game_code).expect(ERR_414);
+        let credits = account.balances.get(&streak.token_contract).unwrap_or(0);
+        account.balances.insert(&streak.token_contract, &(credits + streak.amount));
+        ContractEvent::StreakCollected(StreakData {
+            account_id: &account_id,
+            game_code: &game_code,
+            streak: &streak,
+        })
+        .emit();
+
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        U128(streak.amount)
+    }
+}
+
+impl Contract {
+    // plays a bet as in play and, if it wins, moves its payout from the player's credits
+    // into a new streak of the game. Jackpots won are kept in the credits
+    pub fn internal_play_streak(
+        &mut self,
+        account_id: AccountId,
+        game_code: GameId,
+        bet_size: u128,
+        choice: BetChoice,
+    ) -> BetReceipt {
+        let account = self.internal_get_account(&account_id).expect(ERR_001);
+        assert!(account.streaks.get(&game_code).is_none(), "{}", ERR_415);
+
+        let receipt = self.internal_play(account_id.clone(), game_code.clone(), bet_size, choice);
+        if !receipt.outcome {
+            return receipt;
+        }
+
+        let initial_storage = env::storage_usage();
+        let mut account = self.internal_get_account(&account_id).unwrap();
+        let credits = account.balances.get(&receipt.token_contract).unwrap_or(0);
+        account.balances.insert(&receipt.token_contract, &(credits - receipt.payout));
+        let streak = Streak {
+            token_contract: receipt.token_contract.clone(),
+            amount: receipt.payout,
+            length: 1,
+            last_bet_id: receipt.bet_id,
+        };
+        account.streaks.insert(&game_code, &streak);
+        ContractEvent::StreakOpened(StreakData {
+            account_id: &account_id,
+            game_code: &game_code,
+            streak: &streak,
+        })
+        .emit();
+
+        self.internal_update_account_storage_check(&account_id, account, initial_storage);
+        receipt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    const CREDITS: u128 = 10_000;
+    const HOUSE_FUNDS: u128 = 1_000_000;
+    const BET_SIZE: u128 = 100;
+    const MAX_BET: u128 = 1_000;
+    const MAX_STREAK: u32 = 3;
+
+    fn player(seed: u128) -> AccountId {
+        format!("player-{}.testnet", seed)
+    }
+
+    // creates a fee-less even odds dice game and funds a player for it, leaves the
+    // player as the signer with the seed as random_seed
+    fn sample_streak_contract(seed: u128, house_funds: u128) -> (Contract, GameId, AccountId) {
+        let token = format!("{}-token.testnet", seed);
+        let game_id = "streak".to_string();
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.game_count = seed;
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &token);
+        insert_test_game(
+            &mut contract,
+            &game_id,
+            &PartneredGame {
+                max_bet: MAX_BET,
+                max_streak: MAX_STREAK,
+                ..test_game(&token)
+            },
+        );
+        let mut game = contract.games.get(&game_id).unwrap();
+        game.house_funds = house_funds;
+        contract.games.insert(&game_id, &game);
+
+        let mut context = get_context(vec![], false, 0, 1000, player(seed));
+        context.random_seed = seed.to_be_bytes().to_vec();
+        testing_env!(context);
+        contract.internal_deposit_storage_account(&player(seed), 1000 * ONE_NEAR);
+        let mut account = contract.internal_get_account(&player(seed)).unwrap();
+        account.balances.insert(&token, &CREDITS);
+        contract.internal_update_account(&player(seed), &account);
+        (contract, game_id, token)
+    }
+
+    fn open_streak(contract: &mut Contract, game_id: &GameId, token: &AccountId, seed: u128, amount: u128, length: u32) {
+        let initial_storage = env::storage_usage();
+        let mut account = contract.internal_get_account(&player(seed)).unwrap();
+        account.streaks.insert(
+            game_id,
+            &Streak {
+                token_contract: token.clone(),
+                amount,
+                length,
+                last_bet_id: 0,
+            },
+        );
+        contract.internal_update_account_storage_check(&player(seed), account, initial_storage);
+    }
+
+    /// play with start_streak
+    /// method must:
+    /// 1. Assert no streak is open for the game
+    /// 2. Hold the payout of a win in a new streak instead of the credits
+    /// 3. Credit nothing and open no streak on a loss
+    #[test]
+    fn test_play_start_streak() {
+        fn closure_generator(streak_open: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_streak_contract(seed, HOUSE_FUNDS);
+                if streak_open {
+                    open_streak(&mut contract, &game_id, &token, seed, BET_SIZE, 1);
+                }
+
+                let receipt = contract.play(
+                    game_id.clone(),
+                    U128(BET_SIZE),
+                    128,
+                    "_bet_type".to_string(),
+                    None,
+                    None,
+                    Some(true),
+                );
+
+                let credits = contract.get_credits(token.clone(), player(seed)).0;
+                assert_eq!(credits, CREDITS - BET_SIZE + receipt.jackpot_payout);
+                let streak = contract.view_streak(player(seed), game_id);
+                if receipt.outcome {
+                    assert_eq!(
+                        streak,
+                        Some(Streak {
+                            token_contract: token,
+                            amount: receipt.payout,
+                            length: 1,
+                            last_bet_id: receipt.bet_id,
+                        })
+                    );
+                } else {
+                    assert_eq!(streak, None);
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert no streak is open for the game
+            (true, Some(ERR_415.to_string())),
+            // 2. Hold the payout of a win in a new streak instead of the credits
+            // 3. Credit nothing and open no streak on a loss
+            (false, None),
+            (false, None),
+            (false, None),
+            (false, None),
+            (false, None),
+            (false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+        });
+    }
+
+    /// press_streak
+    /// method must:
+    /// 1. Assert a streak is open for the game and below max_streak
+    /// 2. Assert the held payout respects the game limits
+    /// 3. Assert the house covers the win before rolling
+    /// 4. Hold the new payout and grow the streak on a win
+    /// 5. Close the streak on a loss, leaving the credits untouched
+    #[test]
+    fn test_press_streak() {
+        fn closure_generator(streak: Option<(u128, u32)>, house_funds: u128, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_streak_contract(seed, house_funds);
+                if let Some((amount, length)) = streak {
+                    open_streak(&mut contract, &game_id, &token, seed, amount, length);
+                }
+
+                let receipt = contract.press_streak(game_id.clone(), 128, "_bet_type".to_string(), None, None);
+
+                let (amount, length) = streak.unwrap();
+                assert_eq!(receipt.bet_size, amount);
+                let credits = contract.get_credits(token.clone(), player(seed)).0;
+                assert_eq!(credits, CREDITS + receipt.jackpot_payout);
+                let game = contract.view_partner_data(game_id.clone());
+                let held = contract.view_streak(player(seed), game_id);
+                if receipt.outcome {
+                    let held = held.unwrap();
+                    assert_eq!(held.amount, receipt.payout);
+                    assert_eq!(held.length, length + 1);
+                    assert_eq!(held.last_bet_id, receipt.bet_id);
+                    assert_eq!(game.house_funds, house_funds - receipt.payout);
+                } else {
+                    assert_eq!(held, None);
+                    assert_eq!(game.house_funds, house_funds);
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert a streak is open for the game and below max_streak
+            (None, HOUSE_FUNDS, Some(ERR_414.to_string())),
+            (Some((BET_SIZE, MAX_STREAK)), HOUSE_FUNDS, Some(ERR_416.to_string())),
+            // 2. Assert the held payout respects the game limits
+            (Some((MAX_BET + 1, 1)), HOUSE_FUNDS, Some(ERR_404.to_string())),
+            // 3. Assert the house covers the win before rolling
+            (Some((BET_SIZE, 1)), BET_SIZE, Some(ERR_407.to_string())),
+            // 4. Hold the new payout and grow the streak on a win
+            // 5. Close the streak on a loss, leaving the credits untouched
+            (Some((BET_SIZE, 1)), HOUSE_FUNDS, None),
+            (Some((BET_SIZE, 1)), HOUSE_FUNDS, None),
+            (Some((BET_SIZE * 2, 2)), HOUSE_FUNDS, None),
+            (Some((BET_SIZE * 2, 2)), HOUSE_FUNDS, None),
+            (Some((MAX_BET, MAX_STREAK - 1)), HOUSE_FUNDS, None),
+            (Some((MAX_BET, MAX_STREAK - 1)), HOUSE_FUNDS, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            counter += 1;
+        });
+    }
+
+    /// collect_streak
+    /// method must:
+    /// 1. Assert a streak is open for the game
+    /// 2. Move the held payout into the credits and close the streak
+    #[test]
+    fn test_collect_streak() {
+        fn closure_generator(streak_open: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_streak_contract(seed, HOUSE_FUNDS);
+                if streak_open {
+                    open_streak(&mut contract, &game_id, &token, seed, BET_SIZE * 4, MAX_STREAK);
+                }
+
+                assert_eq!(contract.collect_streak(game_id.clone()), U128(BET_SIZE * 4));
+                assert_eq!(contract.get_credits(token, player(seed)).0, CREDITS + BET_SIZE * 4);
+                assert_eq!(contract.view_streak(player(seed), game_id.clone()), None);
+                contract.collect_streak(game_id);
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert a streak is open for the game
+            (false, Some(ERR_414.to_string())),
+            // 2. Move the held payout into the credits and close the streak
+            (true, Some(ERR_414.to_string())),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+        });
+    }
+}
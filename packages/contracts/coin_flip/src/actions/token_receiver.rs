@@ -1,46 +1,204 @@
 use crate::*;
+use crate::events::*;
 
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde", tag = "type")]
 pub enum CallType {
-    FundGame { game_id: String },
+    FundGame { game_id: GameId },
+    ProvideLiquidity { game_id: GameId },
+    FundTournament { tournament_id: U128 },
     DepositBalance,
+    // bet_type is a dummy param for indexers, same as in play
+    Play {
+        game_code: GameId,
+        odds: u32,
+        bet_type: String,
+        direction: Option<RollDirection>,
+        target_multiplier: Option<U128>,
+        withdraw_winnings: Option<bool>,
+    },
 }
 
 #[near_bindgen]
 impl Contract {
-    #[allow(unreachable_patterns)]
+    // invalid transfers are never applied, their whole amount is returned
+    // as unused to the token contract and the reason is logged
     pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> U128 {
-        match serde_json::from_str::<CallType>(&msg).expect(ERR_005) {
+        let token_contract = env::predecessor_account_id();
+        let call_type = match serde_json::from_str::<CallType>(&msg) {
+            Ok(call_type) => call_type,
+            Err(_) => {
+                return self.internal_refund_transfer(&sender_id, &token_contract, amount, ERR_005)
+            }
+        };
+        if let Err(reason) =
+            self.internal_validate_transfer(&sender_id, &token_contract, amount.0, &call_type)
+        {
+            return self.internal_refund_transfer(&sender_id, &token_contract, amount, &reason);
+        }
+
+        match call_type {
             CallType::FundGame { game_id } => {
-                self.fund_game_house(env::predecessor_account_id(), amount.0, game_id);
+                self.fund_game_house(sender_id, token_contract, amount.0, game_id);
+                U128(0)
+            }
+            CallType::ProvideLiquidity { game_id } => {
+                self.provide_house_liquidity(sender_id, token_contract, amount.0, game_id);
+                U128(0)
+            }
+            CallType::FundTournament { tournament_id } => {
+                self.fund_tournament(sender_id, token_contract, amount.0, tournament_id.0);
                 U128(0)
             }
             CallType::DepositBalance => {
-                self.user_deposit_balance(sender_id, env::predecessor_account_id(), amount.0);
+                self.user_deposit_balance(sender_id, token_contract, amount.0);
+                U128(0)
+            }
+            CallType::Play {
+                game_code,
+                odds,
+                bet_type: _,
+                direction,
+                target_multiplier,
+                withdraw_winnings,
+            } => {
+                self.user_play_transfer(
+                    sender_id,
+                    token_contract,
+                    amount.0,
+                    game_code,
+                    BetChoice::new(odds, direction, target_multiplier),
+                    withdraw_winnings.unwrap_or(false),
+                );
                 U128(0)
             }
-            _ => unimplemented!(),
         }
     }
 }
 
+impl Contract {
+    fn internal_validate_transfer(
+        &self,
+        sender_id: &AccountId,
+        token_contract: &AccountId,
+        amount: u128,
+        call_type: &CallType,
+    ) -> Result<(), String> {
+        if self.panic_button {
+            return Err(ERR_007.to_string());
+        }
+        if self.tokens.get(token_contract).is_none() {
+            return Err(ERR_501.to_string());
+        }
+        let is_registered = self.accounts.contains_key(sender_id);
+        let game_code = match call_type {
+            CallType::FundGame { game_id } => game_id,
+            // the partner provides liquidity without registering, their shares are kept in the game
+            CallType::ProvideLiquidity { game_id } => game_id,
+            CallType::FundTournament { tournament_id } => {
+                let tournament = self
+                    .tournaments
+                    .get(&tournament_id.0)
+                    .ok_or_else(|| ERR_803.to_string())?;
+                if tournament.settled {
+                    return Err(ERR_806.to_string());
+                }
+                if &tournament.token_contract != token_contract {
+                    return Err(ERR_301.to_string());
+                }
+                return Ok(());
+            }
+            _ if !is_registered => {
+                return Err(format!(
+                    "{}. Call storage_deposit to register before sending tokens",
+                    ERR_001
+                ))
+            }
+            CallType::DepositBalance => return Ok(()),
+            CallType::Play { game_code, .. } => game_code,
+        };
+
+        let game = self
+            .internal_get_game(game_code)
+            .ok_or_else(|| ERR_002.to_string())?;
+        if &game.partner_token != token_contract {
+            return Err(ERR_301.to_string());
+        }
+
+        if let CallType::ProvideLiquidity { .. } = call_type {
+            if !is_registered && sender_id != &game.partner_owner {
+                return Err(format!(
+                    "{}. Call storage_deposit to register before sending tokens",
+                    ERR_001
+                ));
+            }
+            game.shares_for_deposit(amount)?;
+        }
+
+        if let CallType::Play {
+            odds,
+            direction,
+            target_multiplier,
+            ..
+        } = call_type
+        {
+            let choice = BetChoice::new(*odds, *direction, *target_multiplier);
+            self.internal_check_bet(&game, amount, amount, &choice)?;
+            self.internal_check_cover(&game, amount, &choice)?;
+        }
+        Ok(())
+    }
+
+    fn internal_refund_transfer(
+        &self,
+        sender_id: &AccountId,
+        token_contract: &AccountId,
+        amount: U128,
+        reason: &str,
+    ) -> U128 {
+        ContractEvent::TransferRefunded(TransferRefundedData {
+            sender_id,
+            token_contract,
+            amount,
+            reason,
+        })
+        .emit();
+        amount
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::*;
+    use near_sdk::test_utils::get_logs;
+
+    fn assert_refunded(result: U128, amount: u128, reason: &str) {
+        assert_eq!(result, U128(amount));
+        let logs = get_logs();
+        let log = logs.last().unwrap();
+        assert!(log.contains(r#""event":"transfer_refunded""#));
+        assert!(log.contains(reason));
+    }
 
     /// ft_on_transfer
     /// method must:
-    /// 1. Assert msg is in correct format
+    /// 1. Refund transfers with msg in wrong format
     /// 2. Delegate to correct internal method
+    ///
     /// fund_game_house
     /// method must:
-    /// 1. Assert transferred token is the game's token
-    /// 2. Increase house balance in the game
+    /// 1. Refund transfers to games that do not exist
+    /// 2. Refund transfers of tokens that are not the game's token
+    /// 3. Increase house balance in the game
     #[test]
     fn test_ft_on_transfer_fund_game_house() {
-        fn closure_generator(is_correct_token: bool, seed: u128) -> impl FnOnce() {
+        fn closure_generator(
+            msg_game_id: Option<&'static str>,
+            is_correct_token: bool,
+            refund_reason: Option<&'static str>,
+            seed: u128,
+        ) -> impl FnOnce() {
             move || {
                 let user = format!("{}.testnet", seed);
                 let base_token = format!("{}-token.testnet", seed);
@@ -56,62 +214,79 @@ mod tests {
                 let game_id = "the_game".to_string();
                 let amount = 1000;
                 let mut contract = sample_contract(seed);
+                register_sample_token(&mut contract, &base_token);
+                register_sample_token(&mut contract, &wrong_token);
 
                 let game_settings = PartneredGame {
-                    partner_owner: "anyone".to_string(),
-                    blocked: false,
                     house_funds: 347,
-                    partner_token: base_token.clone(),
-                    partner_fee: 0,
-                    partner_balance: 0,
                     bet_payment_adjustment: 0,
-                    house_fee: 0,
                     max_bet: 0,
                     min_bet: 0,
                     max_odds: 0,
                     min_odds: 0,
-                    nft_fee: 0,
-                    owner_fee: 0,
+                    ..test_game(&base_token)
                 };
                 contract.games.insert(&game_id, &game_settings);
 
-                let result = contract.ft_on_transfer(
-                    user.clone(),
-                    U128(amount),
-                    json!({"type": "FundGame", "game_id": game_id}).to_string(),
-                );
+                let msg = match msg_game_id {
+                    Some(id) => json!({"type": "FundGame", "game_id": id}).to_string(),
+                    None => "not a valid msg".to_string(),
+                };
+                let result = contract.ft_on_transfer(user.clone(), U128(amount), msg);
 
-                assert_eq!(
-                    contract.games.get(&game_id).unwrap().house_funds,
-                    amount + game_settings.house_funds
-                );
-                assert_eq!(result, U128(0));
+                match refund_reason {
+                    Some(reason) => {
+                        assert_refunded(result, amount, reason);
+                        assert_eq!(
+                            contract.games.get(&game_id).unwrap().house_funds,
+                            game_settings.house_funds
+                        );
+                    }
+                    None => {
+                        assert_eq!(
+                            contract.games.get(&game_id).unwrap().house_funds,
+                            amount + game_settings.house_funds
+                        );
+                        assert_eq!(result, U128(0));
+                    }
+                }
             }
         }
 
         let test_cases = [
-            // 1. Assert transferred token is the game's token
-            (false, Some(ERR_301.to_string())),
-            // 2. Assert enough balance
-            (true, None),
+            // ft_on_transfer 1. Refund transfers with msg in wrong format
+            (None, true, Some(ERR_005), None),
+            // 1. Refund transfers to games that do not exist
+            (Some("other_game"), true, Some(ERR_002), None),
+            // 2. Refund transfers of tokens that are not the game's token
+            (Some("the_game"), false, Some(ERR_301), None),
+            // 3. Increase house balance in the game
+            (Some("the_game"), true, None, None),
         ];
 
         let mut counter = 0;
         IntoIterator::into_iter(test_cases).for_each(|v| {
-            run_test_case(closure_generator(v.0, counter), v.1);
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
             counter += 1;
             println!("{}", counter);
         });
     }
 
-    /// fund_game_house
+    /// user_deposit_balance
     /// method must:
-    /// 1. Assert user is registered
-    /// 2. Panic Button is not on
-    /// 3. Increase user balance in the token
+    /// 1. Refund transfers from users that are not registered
+    /// 2. Refund transfers while panic button is on
+    /// 3. Refund transfers of tokens that are not whitelisted
+    /// 4. Increase user balance in the token
     #[test]
     fn test_ft_on_transfer_user_deposit_balance() {
-        fn closure_generator(is_registered_user: bool, panic_button: bool, seed: u128) -> impl FnOnce() {
+        fn closure_generator(
+            is_registered_user: bool,
+            is_registered_token: bool,
+            panic_button: bool,
+            refund_reason: Option<&'static str>,
+            seed: u128,
+        ) -> impl FnOnce() {
             move || {
                 let user = format!("{}.testnet", seed);
                 let base_token = format!("{}-token.testnet", seed);
@@ -124,6 +299,9 @@ mod tests {
                 let starting_balance = 267;
                 let amount = 1000;
                 let mut contract = sample_contract(seed);
+                if is_registered_token {
+                    register_sample_token(&mut contract, &base_token);
+                }
                 contract.panic_button = panic_button;
 
                 contract.internal_deposit_storage_account(&user, 1000 * ONE_NEAR);
@@ -139,26 +317,156 @@ mod tests {
 
                 let new_account = contract.internal_get_account(&user).unwrap();
 
-                assert_eq!(
-                    new_account.balances.get(&base_token).unwrap(),
-                    amount + starting_balance
+                match refund_reason {
+                    Some(reason) => {
+                        assert_refunded(result, amount, reason);
+                        assert_eq!(
+                            new_account.balances.get(&base_token).unwrap(),
+                            starting_balance
+                        );
+                    }
+                    None => {
+                        assert_eq!(
+                            new_account.balances.get(&base_token).unwrap(),
+                            amount + starting_balance
+                        );
+                        assert_eq!(result, U128(0));
+                    }
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Refund transfers from users that are not registered
+            (false, true, false, Some(ERR_001), None),
+            // 2. Refund transfers while panic button is on
+            (true, true, true, Some(ERR_007), None),
+            // 3. Refund transfers of tokens that are not whitelisted
+            (true, false, false, Some(ERR_501), None),
+            // 4. Increase user balance in the token
+            (true, true, false, None, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// user_play_transfer
+    /// method must:
+    /// 1. Refund transfers of tokens that are not the game's token
+    /// 2. Refund bets outside of game limits
+    /// 3. Refund bets the house cannot cover
+    /// 4. Bet the whole transferred amount
+    /// 5. Send winnings back to player if requested, jackpot included
+    #[test]
+    fn test_ft_on_transfer_play() {
+        fn closure_generator(
+            is_correct_token: bool,
+            amount: u128,
+            house_funds: u128,
+            jackpot_trigger: u32,
+            withdraw_winnings: bool,
+            refund_reason: Option<&'static str>,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let base_token = format!("{}-token.testnet", seed);
+                let wrong_token = format!("{}-token2.testnet", seed);
+                let signer = if is_correct_token {
+                    base_token.clone()
+                } else {
+                    wrong_token.clone()
+                };
+                let mut context = get_context(vec![], false, 0, 1000, signer);
+                context.random_seed = seed.to_be_bytes().to_vec();
+                testing_env!(context);
+
+                let game_id = "the_game".to_string();
+                let starting_balance = 267;
+                let mut contract = sample_contract(seed);
+                register_sample_token(&mut contract, &base_token);
+                register_sample_token(&mut contract, &wrong_token);
+                contract.game_count = seed;
+
+                contract.internal_deposit_storage_account(&user, 1000 * ONE_NEAR);
+                let mut account = contract.internal_get_account(&user).unwrap();
+                account.balances.insert(&base_token, &starting_balance);
+                contract.internal_update_account(&user, &account);
+
+                let game_settings = PartneredGame {
+                    house_funds,
+                    partner_fee: 1000,
+                    bet_payment_adjustment: 10000,
+                    house_fee: 100,
+                    nft_fee: 500,
+                    owner_fee: 300,
+                    jackpot_trigger,
+                    jackpot_balance: 1_000,
+                    ..test_game(&base_token)
+                };
+                contract.games.insert(&game_id, &game_settings);
+
+                let result = contract.ft_on_transfer(
+                    user.clone(),
+                    U128(amount),
+                    json!({
+                        "type": "Play",
+                        "game_code": game_id,
+                        "odds": 128,
+                        "bet_type": "heads",
+                        "withdraw_winnings": withdraw_winnings,
+                    })
+                    .to_string(),
                 );
+                if let Some(reason) = refund_reason {
+                    assert_refunded(result, amount, reason);
+                    assert_eq!(contract.get_credits(base_token, user).0, starting_balance);
+                    return;
+                }
                 assert_eq!(result, U128(0));
+
+                let receipt = contract.view_bet(U128(seed));
+                assert_eq!(receipt.bet_size, amount);
+                let expected_credits = if withdraw_winnings {
+                    starting_balance
+                } else {
+                    starting_balance + receipt.payout + receipt.jackpot_payout
+                };
+                assert_eq!(
+                    contract.get_credits(base_token, user).0,
+                    expected_credits
+                );
             }
         }
 
         let test_cases = [
-            // 1. Assert transferred token is the game's token
-            (false, false, Some(ERR_001.to_string())),
-            // 2. Panic Button
-            (true, true, Some(ERR_007.to_string())),
-            // 3. Increase user balance in the token
-            (true, false, None),
+            // 1. Refund transfers of tokens that are not the game's token
+            (false, 100, 1_000_000, DEFAULT_JACKPOT_TRIGGER, false, Some(ERR_301), None),
+            // 2. Refund bets outside of game limits
+            (true, 1000, 1_000_000, DEFAULT_JACKPOT_TRIGGER, false, Some(ERR_404), None),
+            (true, 1, 1_000_000, DEFAULT_JACKPOT_TRIGGER, false, Some(ERR_403), None),
+            // 3. Refund bets the house cannot cover
+            (true, 100, 10, DEFAULT_JACKPOT_TRIGGER, false, Some(ERR_407), None),
+            // 4. Bet the whole transferred amount
+            (true, 100, 1_000_000, DEFAULT_JACKPOT_TRIGGER, false, None, None),
+            (true, 100, 1_000_000, DEFAULT_JACKPOT_TRIGGER, false, None, None),
+            (true, 100, 1_000_000, 2, false, None, None),
+            // 5. Send winnings back to player if requested, jackpot included
+            (true, 100, 1_000_000, DEFAULT_JACKPOT_TRIGGER, true, None, None),
+            (true, 100, 1_000_000, DEFAULT_JACKPOT_TRIGGER, true, None, None),
+            (true, 100, 1_000_000, 2, true, None, None),
+            (true, 100, 1_000_000, 2, true, None, None),
+            (true, 100, 1_000_000, 2, true, None, None),
         ];
 
         let mut counter = 0;
         IntoIterator::into_iter(test_cases).for_each(|v| {
-            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, v.5, counter), v.6);
             counter += 1;
             println!("{}", counter);
         });
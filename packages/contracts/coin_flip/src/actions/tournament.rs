@@ -0,0 +1,526 @@
+use crate::*;
+use crate::events::*;
+use crate::tournament::MAX_GAME_TOURNAMENTS;
+
+#[near_bindgen]
+impl Contract {
+    //creates a tournament on a game, only callable by the partner. Bets placed on the game
+    //between starts_at and ends_at (block timestamps in nanoseconds) are scored by scoring.
+    //prize_shares split the prize pool between leaderboard places, defaults to a single winner.
+    //the prize pool is funded through ft_transfer_call with a FundTournament msg
+    pub fn create_tournament(
+        &mut self,
+        game_code: GameId,
+        starts_at: U64,
+        ends_at: U64,
+        scoring: TournamentScoring,
+        prize_shares: Option<Vec<u32>>,
+    ) -> U128 {
+        self.assert_panic_button();
+        let game = self.internal_get_game(&game_code).expect(ERR_002);
+        assert!(game.partner_owner == env::predecessor_account_id(), "{}", ERR_004);
+        assert!(ends_at.0 > starts_at.0 && ends_at.0 > env::block_timestamp(), "{}", ERR_801);
+        let prize_shares = prize_shares.unwrap_or_else(|| vec![FRACTIONAL_BASE as u32]);
+        if let Err(err) = Tournament::validate_shares(&prize_shares) {
+            panic!("{}", err);
+        }
+        let mut tournament_ids = self.game_tournaments.get(&game_code).unwrap_or_default();
+        assert!(tournament_ids.len() < MAX_GAME_TOURNAMENTS, "{}", ERR_804);
+
+        // tournaments are paid for by the contract like the games they belong to
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        let tournament_id = self.next_id;
+        let tournament = Tournament::new(
+            tournament_id,
+            game_code.clone(),
+            game.partner_token,
+            starts_at.0,
+            ends_at.0,
+            scoring,
+            prize_shares,
+        );
+        self.tournaments.insert(&tournament_id, &tournament);
+        tournament_ids.push(tournament_id);
+        self.game_tournaments.insert(&game_code, &tournament_ids);
+        self.next_id += 1;
+        ContractEvent::TournamentCreated(TournamentData {
+            tournament_id: U128(tournament_id),
+            tournament: &tournament,
+        })
+        .emit();
+
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+        U128(tournament_id)
+    }
+
+    //pays the prize pool of an ended tournament to its leaderboard, callable by anyone.
+    //the pool goes to the game's house_funds if nobody scored
+    pub fn settle_tournament(&mut self, tournament_id: U128) -> Vec<LeaderboardEntry> {
+        self.assert_panic_button();
+        let mut tournament = self.tournaments.get(&tournament_id.0).expect(ERR_803);
+        assert!(!tournament.settled, "{}", ERR_806);
+        assert!(env::block_timestamp() >= tournament.ends_at, "{}", ERR_805);
+
+        let contract_id = env::current_account_id();
+        let mut contract_account = self.internal_get_account(&contract_id).unwrap();
+        let initial_storage = env::storage_usage();
+
+        let mut game = self.internal_get_game(&tournament.game_code).expect(ERR_002);
+        if tournament.leaderboard.is_empty() {
+            game.house_funds += tournament.prize_pool;
+        }
+        let prizes = tournament.split_prize_pool();
+        for (entry, prize) in tournament.leaderboard.iter_mut().zip(prizes) {
+            entry.prize = prize;
+            match self.internal_get_account(&entry.account_id) {
+                Some(mut account) => {
+                    let credits = account.balances.get(&tournament.token_contract).unwrap_or(0);
+                    account.balances.insert(&tournament.token_contract, &(credits + prize));
+                    self.internal_update_account(&entry.account_id, &account);
+                }
+                // winners that unregistered during the tournament forfeit the prize to the house
+                None => game.house_funds += prize,
+            }
+        }
+
+        tournament.settled = true;
+        self.tournaments.insert(&tournament_id.0, &tournament);
+        let mut tournament_ids = self.game_tournaments.get(&tournament.game_code).unwrap_or_default();
+        tournament_ids.retain(|id| *id != tournament_id.0);
+        self.game_tournaments.insert(&tournament.game_code, &tournament_ids);
+        ContractEvent::TournamentSettled(TournamentData {
+            tournament_id,
+            tournament: &tournament,
+        })
+        .emit();
+
+        self.internal_update_game(&tournament.game_code, &game);
+        contract_account.track_storage_usage(initial_storage);
+        self.internal_update_account(&contract_id, &contract_account);
+        tournament.leaderboard
+    }
+}
+
+impl Contract {
+    // scores settled bets in every running tournament of their game. Called before the
+    // player's storage check so that players pay for their own tournament scores
+    pub fn internal_score_tournaments(&mut self, game_code: &GameId, receipts: &[BetReceipt]) {
+        let tournament_ids = match self.game_tournaments.get(game_code) {
+            Some(tournament_ids) => tournament_ids,
+            None => return,
+        };
+        let block_timestamp = env::block_timestamp();
+        for tournament_id in tournament_ids {
+            let mut tournament = self.tournaments.get(&tournament_id).unwrap();
+            if !tournament.is_running(block_timestamp) {
+                continue;
+            }
+            for receipt in receipts {
+                tournament.record_bet(&receipt.account_id, receipt.bet_size, receipt.payout, receipt.jackpot_payout);
+            }
+            self.tournaments.insert(&tournament_id, &tournament);
+        }
+    }
+}
+
+// methods to be called through token receiver
+impl Contract {
+    pub fn fund_tournament(
+        &mut self,
+        sender_id: AccountId,
+        token_contract: AccountId,
+        amount: u128,
+        tournament_id: u128,
+    ) {
+        let mut tournament = self.tournaments.get(&tournament_id).expect(ERR_803);
+        assert!(!tournament.settled, "{}", ERR_806);
+        assert_eq!(tournament.token_contract, token_contract, "{}", ERR_301);
+        tournament.prize_pool += amount;
+        self.tournaments.insert(&tournament_id, &tournament);
+        ContractEvent::TournamentFunded(TournamentFundedData {
+            tournament_id: U128(tournament_id),
+            sender_id: &sender_id,
+            token_contract: &token_contract,
+            amount: U128(amount),
+        })
+        .emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    const CREDITS: u128 = 10_000;
+    const HOUSE_FUNDS: u128 = 1_000_000;
+    const PRIZE_POOL: u128 = 1_000;
+    const STARTS_AT: u64 = 100;
+    const ENDS_AT: u64 = 1_000;
+
+    fn partner(seed: u128) -> AccountId {
+        format!("partner-{}.testnet", seed)
+    }
+
+    fn player(seed: u128, index: usize) -> AccountId {
+        format!("player-{}-{}.testnet", index, seed)
+    }
+
+    fn set_context(account_id: &AccountId, block_timestamp: u64, seed: u128) {
+        let mut context = get_context(vec![], false, 0, 1000 * ONE_NEAR, account_id.clone());
+        context.block_timestamp = block_timestamp;
+        context.random_seed = seed.to_be_bytes().to_vec();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+    }
+
+    // creates a funded fee-less game and gives players credits of its token
+    fn sample_tournament_contract(seed: u128, players: usize) -> (Contract, GameId, AccountId) {
+        let token = format!("{}-token.testnet", seed);
+        let game_id = "tournament".to_string();
+        let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut contract = sample_contract(seed);
+        contract.game_count = seed;
+        contract.next_id = seed;
+        contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+        register_sample_token(&mut contract, &token);
+        insert_test_game(
+            &mut contract,
+            &game_id,
+            &PartneredGame {
+                partner_owner: partner(seed),
+                max_bet: 1_000,
+                ..test_game(&token)
+            },
+        );
+        contract.fund_game_house(SIGNER_ACCOUNT.to_string(), token.clone(), HOUSE_FUNDS, game_id.clone());
+        for index in 0..players {
+            let account_id = player(seed, index);
+            contract.internal_deposit_storage_account(&account_id, 1000 * ONE_NEAR);
+            let mut account = contract.internal_get_account(&account_id).unwrap();
+            account.balances.insert(&token, &CREDITS);
+            contract.internal_update_account(&account_id, &account);
+        }
+        (contract, game_id, token)
+    }
+
+    fn fund(contract: &mut Contract, token: &AccountId, tournament_id: U128, amount: u128) -> U128 {
+        set_context(token, 0, 0);
+        contract.ft_on_transfer(
+            SIGNER_ACCOUNT.to_string(),
+            U128(amount),
+            json!({"type": "FundTournament", "tournament_id": tournament_id}).to_string(),
+        )
+    }
+
+    /// create_tournament
+    /// method must:
+    /// 1. Assert the game exists and caller is its partner
+    /// 2. Assert the tournament ends after it starts and in the future
+    /// 3. Assert prize shares are valid, defaulting to a single winner
+    /// 4. Assert the game runs less than MAX_GAME_TOURNAMENTS tournaments
+    /// 5. Store the tournament under next_id, leaving game_count to bets
+    #[test]
+    fn test_create_tournament() {
+        fn closure_generator(
+            game_code: Option<GameId>,
+            caller_is_partner: bool,
+            starts_at: u64,
+            ends_at: u64,
+            prize_shares: Option<Vec<u32>>,
+            running: usize,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_tournament_contract(seed, 0);
+                set_context(&partner(seed), 10, seed);
+                for _ in 0..running {
+                    contract.create_tournament(game_id.clone(), U64(0), U64(ENDS_AT), TournamentScoring::Volume, None);
+                }
+                let caller = if caller_is_partner { partner(seed) } else { player(seed, 0) };
+                set_context(&caller, 10, seed);
+
+                let tournament_id = contract.create_tournament(
+                    game_code.unwrap_or_else(|| game_id.clone()),
+                    U64(starts_at),
+                    U64(ends_at),
+                    TournamentScoring::NetProfit,
+                    prize_shares.clone(),
+                );
+
+                assert_eq!(tournament_id, U128(seed + running as u128));
+                assert_eq!(contract.next_id, tournament_id.0 + 1);
+                assert_eq!(contract.game_count, seed);
+                let tournament = contract.view_tournament(tournament_id);
+                assert_eq!(tournament.game_code, game_id);
+                assert_eq!(tournament.token_contract, token);
+                assert_eq!(tournament.starts_at, starts_at);
+                assert_eq!(tournament.ends_at, ends_at);
+                assert_eq!(tournament.scoring, TournamentScoring::NetProfit);
+                assert_eq!(tournament.prize_shares, prize_shares.unwrap_or_else(|| vec![FRACTIONAL_BASE as u32]));
+                assert_eq!(tournament.prize_pool, 0);
+                assert!(!tournament.settled);
+                let listed = contract.game_tournaments(game_id);
+                assert_eq!(listed.len(), running + 1);
+                assert_eq!(listed.last().unwrap().0, tournament_id);
+            }
+        }
+
+        let base = FRACTIONAL_BASE as u32;
+        let test_cases = [
+            // 1. Assert the game exists and caller is its partner
+            (Some("no-game".to_string()), true, STARTS_AT, ENDS_AT, None, 0, Some(ERR_002.to_string())),
+            (None, false, STARTS_AT, ENDS_AT, None, 0, Some(ERR_004.to_string())),
+            // 2. Assert the tournament ends after it starts and in the future
+            (None, true, ENDS_AT, ENDS_AT, None, 0, Some(ERR_801.to_string())),
+            (None, true, 0, 10, None, 0, Some(ERR_801.to_string())),
+            // 3. Assert prize shares are valid, defaulting to a single winner
+            (None, true, STARTS_AT, ENDS_AT, Some(vec![base / 2]), 0, Some(ERR_802.to_string())),
+            (None, true, STARTS_AT, ENDS_AT, Some(vec![base / 11; 11]), 0, Some(ERR_802.to_string())),
+            // 4. Assert the game runs less than MAX_GAME_TOURNAMENTS tournaments
+            (None, true, STARTS_AT, ENDS_AT, None, MAX_GAME_TOURNAMENTS, Some(ERR_804.to_string())),
+            // 5. Store the tournament under next_id, leaving game_count to bets
+            (None, true, STARTS_AT, ENDS_AT, None, 0, None),
+            (None, true, 0, ENDS_AT, Some(vec![base / 2, base / 4, base / 4]), MAX_GAME_TOURNAMENTS - 1, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, v.5, counter), v.6);
+            counter += 1;
+        });
+    }
+
+    /// fund_tournament
+    /// method must:
+    /// 1. Refund transfers to tournaments that do not exist or were settled
+    /// 2. Refund transfers of tokens that are not the game's token
+    /// 3. Increase the prize pool of the tournament
+    #[test]
+    fn test_fund_tournament() {
+        fn closure_generator(
+            tournament_id: Option<u128>,
+            is_correct_token: bool,
+            settled: bool,
+            refund_reason: Option<&'static str>,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_tournament_contract(seed, 0);
+                let wrong_token = format!("{}-token2.testnet", seed);
+                register_sample_token(&mut contract, &wrong_token);
+                set_context(&partner(seed), 0, seed);
+                let created_id =
+                    contract.create_tournament(game_id, U64(STARTS_AT), U64(ENDS_AT), TournamentScoring::Volume, None);
+                if settled {
+                    set_context(&partner(seed), ENDS_AT, seed);
+                    contract.settle_tournament(created_id);
+                }
+
+                let tournament_id = U128(tournament_id.unwrap_or(created_id.0));
+                let sender = if is_correct_token { &token } else { &wrong_token };
+                let result = fund(&mut contract, sender, tournament_id, PRIZE_POOL);
+                fund(&mut contract, sender, tournament_id, PRIZE_POOL);
+
+                match refund_reason {
+                    Some(reason) => {
+                        assert_eq!(result, U128(PRIZE_POOL));
+                        assert!(near_sdk::test_utils::get_logs().last().unwrap().contains(reason));
+                        if let Some(tournament) = contract.tournaments.get(&tournament_id.0) {
+                            assert_eq!(tournament.prize_pool, 0);
+                        }
+                    }
+                    None => {
+                        assert_eq!(result, U128(0));
+                        assert_eq!(contract.view_tournament(tournament_id).prize_pool, 2 * PRIZE_POOL);
+                    }
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Refund transfers to tournaments that do not exist or were settled
+            (Some(u128::MAX), true, false, Some(ERR_803), None),
+            (None, true, true, Some(ERR_806), None),
+            // 2. Refund transfers of tokens that are not the game's token
+            (None, false, false, Some(ERR_301), None),
+            // 3. Increase the prize pool of the tournament
+            (None, true, false, None, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, counter), v.4);
+            counter += 1;
+        });
+    }
+
+    /// internal_score_tournaments
+    /// method must:
+    /// 1. Score bets placed while the tournament runs
+    /// 2. Ignore bets placed before it starts or after it ends
+    /// 3. Score every bet of play_batch
+    #[test]
+    fn test_score_tournaments() {
+        fn closure_generator(block_timestamp: u64, bets: Vec<u128>, scored: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, _) = sample_tournament_contract(seed, 1);
+                set_context(&partner(seed), 0, seed);
+                let tournament_id = contract.create_tournament(
+                    game_id.clone(),
+                    U64(STARTS_AT),
+                    U64(ENDS_AT),
+                    TournamentScoring::Volume,
+                    None,
+                );
+
+                let account_id = player(seed, 0);
+                set_context(&account_id, block_timestamp, seed);
+                let receipts = if bets.len() == 1 {
+                    vec![contract.play(game_id, U128(bets[0]), 128, "heads".to_string(), None, None, None)]
+                } else {
+                    let batch = bets
+                        .iter()
+                        .map(|bet_size| BatchBet {
+                            bet_size: U128(*bet_size),
+                            odds: 128,
+                            direction: None,
+                            target_multiplier: None,
+                        })
+                        .collect();
+                    contract.play_batch(game_id, batch)
+                };
+                assert_eq!(receipts.len(), bets.len());
+
+                let volume: u128 = bets.iter().sum();
+                let expected = if scored { volume as i128 } else { 0 };
+                assert_eq!(contract.tournament_score(tournament_id, account_id.clone()), I128(expected));
+                let leaderboard = contract.view_tournament(tournament_id).leaderboard;
+                if scored {
+                    assert_eq!(leaderboard.len(), 1);
+                    assert_eq!(leaderboard[0].account_id, account_id);
+                    assert_eq!(leaderboard[0].score, expected);
+                } else {
+                    assert!(leaderboard.is_empty());
+                }
+            }
+        }
+
+        let test_cases = [
+            // 1. Score bets placed while the tournament runs
+            (STARTS_AT, vec![100], true, None),
+            (ENDS_AT - 1, vec![100], true, None),
+            // 2. Ignore bets placed before it starts or after it ends
+            (STARTS_AT - 1, vec![100], false, None),
+            (ENDS_AT, vec![100], false, None),
+            // 3. Score every bet of play_batch
+            (STARTS_AT, vec![100, 50, 200], true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, counter), v.3);
+            counter += 1;
+        });
+    }
+
+    /// settle_tournament
+    /// method must:
+    /// 1. Assert the tournament exists, ended and was not settled
+    /// 2. Pay the prize pool to the leaderboard by prize shares
+    /// 3. Give the pool to the house if nobody scored
+    /// 4. Stop scoring the game for the tournament
+    #[test]
+    fn test_settle_tournament() {
+        fn closure_generator(
+            tournament_id: Option<u128>,
+            block_timestamp: u64,
+            bets: Vec<u128>,
+            prize_shares: Vec<u32>,
+            settle_twice: bool,
+            seed: u128,
+        ) -> impl FnOnce() {
+            move || {
+                let (mut contract, game_id, token) = sample_tournament_contract(seed, bets.len());
+                set_context(&partner(seed), 0, seed);
+                let created_id = contract.create_tournament(
+                    game_id.clone(),
+                    U64(STARTS_AT),
+                    U64(ENDS_AT),
+                    TournamentScoring::Volume,
+                    Some(prize_shares.clone()),
+                );
+                fund(&mut contract, &token, created_id, PRIZE_POOL);
+                let mut credits_before = vec![];
+                for (index, bet_size) in bets.iter().enumerate() {
+                    set_context(&player(seed, index), STARTS_AT, seed);
+                    contract.play(game_id.clone(), U128(*bet_size), 128, "heads".to_string(), None, None, None);
+                    credits_before.push(contract.get_credits(token.clone(), player(seed, index)).0);
+                }
+                let house_funds = contract.view_partner_data(game_id.clone()).house_funds;
+
+                set_context(&SIGNER_ACCOUNT.to_string(), block_timestamp, seed);
+                let tournament_id = U128(tournament_id.unwrap_or(created_id.0));
+                let winners = contract.settle_tournament(tournament_id);
+                if settle_twice {
+                    contract.settle_tournament(tournament_id);
+                }
+
+                let places = prize_shares.len().min(bets.len());
+                assert_eq!(winners.len(), places);
+                for (place, winner) in winners.iter().enumerate() {
+                    if place > 0 {
+                        assert!(winners[place - 1].score >= winner.score);
+                    }
+                }
+                for (index, bet_size) in bets.iter().enumerate() {
+                    let account_id = player(seed, index);
+                    let prize = match winners.iter().find(|winner| winner.account_id == account_id) {
+                        Some(winner) => {
+                            assert_eq!(winner.score, *bet_size as i128);
+                            winner.prize
+                        }
+                        None => 0,
+                    };
+                    assert_eq!(contract.get_credits(token.clone(), account_id).0, credits_before[index] + prize);
+                }
+                let paid: u128 = winners.iter().map(|winner| winner.prize).sum();
+                let expected_house = if winners.is_empty() { house_funds + PRIZE_POOL } else { house_funds };
+                assert_eq!(paid, if winners.is_empty() { 0 } else { PRIZE_POOL });
+                assert_eq!(contract.view_partner_data(game_id.clone()).house_funds, expected_house);
+
+                let tournament = contract.view_tournament(tournament_id);
+                assert!(tournament.settled);
+                assert_eq!(tournament.leaderboard, winners);
+                assert!(contract.game_tournaments(game_id).is_empty());
+            }
+        }
+
+        let base = FRACTIONAL_BASE as u32;
+        let test_cases = [
+            // 1. Assert the tournament exists, ended and was not settled
+            (Some(u128::MAX), ENDS_AT, vec![100], vec![base], false, Some(ERR_803.to_string())),
+            (None, ENDS_AT - 1, vec![100], vec![base], false, Some(ERR_805.to_string())),
+            (None, ENDS_AT, vec![100], vec![base], true, Some(ERR_806.to_string())),
+            // 2. Pay the prize pool to the leaderboard by prize shares
+            (None, ENDS_AT, vec![100], vec![base], false, None),
+            (None, ENDS_AT, vec![100, 300, 200], vec![base], false, None),
+            (None, ENDS_AT, vec![100, 300, 200, 50], vec![base / 2, base * 3 / 10, base / 5], false, None),
+            (None, ENDS_AT + 1, vec![100, 300], vec![base / 2, base * 3 / 10, base / 5], false, None),
+            // 3. Give the pool to the house if nobody scored
+            // 4. Stop scoring the game for the tournament
+            (None, ENDS_AT, vec![], vec![base], false, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, v.2, v.3, v.4, counter), v.5);
+            counter += 1;
+        });
+    }
+}
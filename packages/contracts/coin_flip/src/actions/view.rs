@@ -6,8 +6,70 @@ impl Contract {
         json!(&self).to_string()
     }
 
-    pub fn view_partner_data(&self, nft_contract: AccountId) -> PartneredGame {
-        self.games.get(&nft_contract).expect(ERR_002)
+    pub fn view_partner_data(&self, game_id: GameId) -> PartneredGame {
+        self.games.get(&game_id).expect(ERR_002)
+    }
+
+    // pool paid to the next bet that hits the jackpot trigger of a game
+    pub fn view_jackpot(&self, game_id: GameId) -> U128 {
+        U128(self.games.get(&game_id).expect(ERR_002).jackpot_balance)
+    }
+
+    // house funds withdrawals of a game queued by its partner, oldest first
+    pub fn house_withdrawals(&self, game_id: GameId) -> Vec<HouseWithdrawal> {
+        self.house_withdrawals.get(&game_id).unwrap_or_default()
+    }
+
+    // liquidity withdrawals of a game queued by a provider, oldest first
+    pub fn liquidity_withdrawals(&self, game_id: GameId, account_id: AccountId) -> Vec<HouseWithdrawal> {
+        self.internal_get_account(&account_id)
+            .and_then(|account| account.house_withdrawals.get(&game_id))
+            .unwrap_or_default()
+    }
+
+    // current value of an amount of house shares of a game in the game's token
+    pub fn house_share_value(&self, game_id: GameId, shares: U128) -> U128 {
+        U128(self.games.get(&game_id).expect(ERR_002).share_value(shares.0))
+    }
+
+    // largest bet a game accepts right now for the given odds, below max_bet whenever
+    // house_funds can only cover a smaller prize within the game's max_exposure
+    pub fn effective_max_bet(
+        &self,
+        game_id: GameId,
+        odds: u32,
+        direction: Option<RollDirection>,
+        target_multiplier: Option<U128>,
+    ) -> U128 {
+        let game = self.games.get(&game_id).expect(ERR_002);
+        U128(game.effective_max_bet(&BetChoice::new(odds, direction, target_multiplier)))
+    }
+
+    // lists registered games, paginated over their insertion order
+    pub fn list_games(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<(GameId, PartneredGame)> {
+        let keys = self.games.keys_as_vector();
+        let values = self.games.values_as_vector();
+        paginate(keys.len(), from_index, limit)
+            .map(|index| (keys.get(index).unwrap(), values.get(index).unwrap()))
+            .collect()
+    }
+
+    pub fn games_by_partner(
+        &self,
+        partner: AccountId,
+        from_index: Option<U64>,
+        limit: Option<U64>,
+    ) -> Vec<(GameId, PartneredGame)> {
+        self.internal_list_indexed_games(self.games_by_partner.get(&partner), from_index, limit)
+    }
+
+    pub fn games_by_token(
+        &self,
+        token: AccountId,
+        from_index: Option<U64>,
+        limit: Option<U64>,
+    ) -> Vec<(GameId, PartneredGame)> {
+        self.internal_list_indexed_games(self.games_by_token.get(&token), from_index, limit)
     }
 
     pub fn get_credits(&self, token_type: AccountId, account_id: AccountId) -> U128 {
@@ -19,4 +81,399 @@ impl Contract {
                 .unwrap_or(0),
         )
     }
-}
\ No newline at end of file
+
+    pub fn view_bet(&self, bet_id: U128) -> BetReceipt {
+        self.bets.get(&bet_id.0).expect(ERR_410)
+    }
+
+    // recomputes the outcome of a settled bet from its stored inputs so that
+    // players can audit it without trusting any off chain service
+    pub fn verify_bet(&self, bet_id: U128) -> BetVerification {
+        self.bets.get(&bet_id.0).expect(ERR_410).verify()
+    }
+
+    pub fn get_formatted_credits(&self, token_type: AccountId, account_id: AccountId) -> String {
+        let credits = self.get_credits(token_type.clone(), account_id);
+        self.view_token(token_type).format_amount(credits.0)
+    }
+
+    pub fn view_token(&self, token_contract: AccountId) -> TokenInfo {
+        self.tokens.get(&token_contract).expect(ERR_501)
+    }
+
+    pub fn view_tokens(&self) -> Vec<(AccountId, TokenInfo)> {
+        self.tokens.to_vec()
+    }
+
+    pub fn view_pending_bet(&self, bet_id: U128) -> PendingBet {
+        self.pending_bets.get(&bet_id.0).expect(ERR_408)
+    }
+
+    pub fn view_raffle(&self, round_id: U128) -> RaffleRound {
+        self.raffles.get(&round_id.0).expect(ERR_703)
+    }
+
+    // lists raffle rounds still selling tickets or waiting to be drawn, paginated
+    pub fn open_raffles(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<(U128, RaffleRound)> {
+        let round_ids = self.open_raffles.as_vector();
+        paginate(round_ids.len(), from_index, limit)
+            .map(|index| {
+                let round_id = round_ids.get(index).unwrap();
+                (U128(round_id), self.raffles.get(&round_id).unwrap())
+            })
+            .collect()
+    }
+
+    pub fn raffle_tickets(&self, round_id: U128, account_id: AccountId) -> U64 {
+        let round = self.raffles.get(&round_id.0).expect(ERR_703);
+        U64(round.ticket_counts.get(&account_id).unwrap_or(0))
+    }
+
+    // winners of a drawn round with the ticket and prize of each, empty until it is drawn
+    pub fn raffle_winners(&self, round_id: U128) -> Vec<RaffleWinner> {
+        self.raffles.get(&round_id.0).expect(ERR_703).winners
+    }
+
+    // payout held by the streak of a player on a game, if one is open
+    pub fn view_streak(&self, account_id: AccountId, game_code: GameId) -> Option<Streak> {
+        self.internal_get_account(&account_id).expect(ERR_001).streaks.get(&game_code)
+    }
+
+    pub fn view_tournament(&self, tournament_id: U128) -> Tournament {
+        self.tournaments.get(&tournament_id.0).expect(ERR_803)
+    }
+
+    // unsettled tournaments of a game, including the ones not started or already ended
+    pub fn game_tournaments(&self, game_code: GameId) -> Vec<(U128, Tournament)> {
+        self.game_tournaments
+            .get(&game_code)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tournament_id| (U128(tournament_id), self.tournaments.get(&tournament_id).unwrap()))
+            .collect()
+    }
+
+    // score of a player in a tournament, also for players that are not on the leaderboard
+    pub fn tournament_score(&self, tournament_id: U128, account_id: AccountId) -> I128 {
+        let tournament = self.tournaments.get(&tournament_id.0).expect(ERR_803);
+        I128(tournament.scores.get(&account_id).unwrap_or(0))
+    }
+
+    pub fn view_challenge(&self, challenge_id: U128) -> Challenge {
+        self.challenges.get(&challenge_id.0).expect(ERR_601)
+    }
+
+    // lists open pvp challenges, paginated
+    pub fn list_challenges(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<(U128, Challenge)> {
+        let keys = self.challenges.keys_as_vector();
+        let values = self.challenges.values_as_vector();
+        paginate(keys.len(), from_index, limit)
+            .map(|index| (U128(keys.get(index).unwrap()), values.get(index).unwrap()))
+            .collect()
+    }
+}
+impl Contract {
+    fn internal_list_indexed_games(
+        &self,
+        index: Option<UnorderedSet<GameId>>,
+        from_index: Option<U64>,
+        limit: Option<U64>,
+    ) -> Vec<(GameId, PartneredGame)> {
+        match index {
+            Some(index) => {
+                let game_ids = index.as_vector();
+                paginate(game_ids.len(), from_index, limit)
+                    .map(|i| {
+                        let game_id = game_ids.get(i).unwrap();
+                        let game = self.games.get(&game_id).unwrap();
+                        (game_id, game)
+                    })
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+}
+
+// range of indexes to return for a page of a collection of given length
+fn paginate(length: u64, from_index: Option<U64>, limit: Option<U64>) -> std::ops::Range<u64> {
+    let start = from_index.map(|v| v.0).unwrap_or(0).min(length);
+    let end = start.saturating_add(limit.map(|v| v.0).unwrap_or(length)).min(length);
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    /// verify_bet
+    /// method must:
+    /// 1. Assert bet exists
+    /// 2. Recompute the same outcome the bet had when played
+    /// 3. Recompute the value credited to the player
+    #[test]
+    fn test_verify_bet() {
+        fn closure_generator(bet_exists: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let mut context = get_context(vec![], false, 0, 1000, user.clone());
+                context.random_seed = seed.to_be_bytes().to_vec();
+                testing_env!(context);
+                let game_id = "teste.near".to_string();
+                let base_token = format!("{}-token.testnet", seed);
+                let user_balance = 10000;
+
+                let mut contract = sample_contract(seed);
+                contract.game_count = seed;
+
+                contract.internal_deposit_storage_account(&user, 1000 * ONE_NEAR);
+                let mut account = contract.internal_get_account(&user).unwrap();
+                account.balances.insert(&base_token, &user_balance);
+                contract.internal_update_account(&user, &account);
+
+                let game_settings = PartneredGame {
+                    house_funds: 1_000_000,
+                    partner_fee: 1000,
+                    bet_payment_adjustment: 10000,
+                    house_fee: 100,
+                    nft_fee: 500,
+                    owner_fee: 300,
+                    ..test_game(&base_token)
+                };
+                contract.games.insert(&game_id, &game_settings);
+
+                let bet_size = 100;
+                let bet_id = contract.game_count;
+                let result = contract
+                    .play(game_id, U128(bet_size), 128, "_bet_type".to_string(), None, None, None)
+                    .outcome;
+                let verified_id = if bet_exists { bet_id } else { bet_id + 1 };
+
+                let verification = contract.verify_bet(U128(verified_id));
+
+                assert_eq!(verification.outcome, result);
+                assert!(verification.matches_record);
+                assert_eq!(
+                    user_balance - bet_size + verification.won_value,
+                    contract.get_credits(base_token, user).0
+                );
+            }
+        }
+
+        let test_cases = [
+            // 1. Assert bet exists
+            (false, Some(ERR_410.to_string())),
+            // 2. Recompute the same outcome the bet had when played
+            // 3. Recompute the value credited to the player
+            (true, None),
+            (true, None),
+            (true, None),
+            (true, None),
+            (true, None),
+            (true, None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, counter), v.1);
+            counter += 1;
+            println!("{}", counter);
+        });
+    }
+
+    /// effective_max_bet
+    /// method must:
+    /// 1. Return max_bet while house_funds cover it within max_exposure
+    /// 2. Return a lower limit when max_exposure of house_funds cannot cover max_bet
+    /// 3. Match the limit play enforces before rolling, ERR_417 above it
+    #[test]
+    fn test_effective_max_bet() {
+        fn closure_generator(max_exposure: u128, over_limit: bool, seed: u128) -> impl FnOnce() {
+            move || {
+                let user = format!("{}.testnet", seed);
+                let context = get_context(vec![], false, 0, 1000, user.clone());
+                testing_env!(context);
+                let game_id = "teste.near".to_string();
+                let base_token = format!("{}-token.testnet", seed);
+                let house_funds = 1_000_000;
+                let max_bet = 100_000;
+                let odds = 128;
+
+                let mut contract = sample_contract(seed);
+                contract.internal_deposit_storage_account(&user, 1000 * ONE_NEAR);
+                let mut account = contract.internal_get_account(&user).unwrap();
+                account.balances.insert(&base_token, &house_funds);
+                contract.internal_update_account(&user, &account);
+
+                let game_settings = PartneredGame {
+                    house_funds,
+                    partner_fee: 1000,
+                    house_fee: 100,
+                    max_bet,
+                    nft_fee: 500,
+                    owner_fee: 300,
+                    max_exposure,
+                    ..test_game(&base_token)
+                };
+                contract.games.insert(&game_id, &game_settings);
+
+                let effective_max_bet = contract.effective_max_bet(game_id.clone(), odds, None, None).0;
+                // 1. Return max_bet while house_funds cover it within max_exposure
+                // 2. Return a lower limit when max_exposure of house_funds cannot cover max_bet
+                if max_exposure == FRACTIONAL_BASE {
+                    assert_eq!(effective_max_bet, max_bet);
+                } else {
+                    assert!(effective_max_bet < max_bet);
+                    let choice = BetChoice::new(odds, None, None);
+                    let fees = FeeCuts::new(effective_max_bet, &GameParameters::from(&game_settings));
+                    assert!(
+                        game_settings.max_payout(effective_max_bet - fees.total(), &choice)
+                            <= game_settings.exposure_limit()
+                    );
+                }
+
+                // 3. Match the limit play enforces before rolling, ERR_417 above it
+                let bet_size = if over_limit { effective_max_bet + 1 } else { effective_max_bet };
+                let receipt = contract.play(game_id, U128(bet_size), odds, "_bet_type".to_string(), None, None, None);
+                assert_eq!(receipt.bet_size, bet_size);
+            }
+        }
+
+        let test_cases = [
+            // 1. Return max_bet while house_funds cover it within max_exposure
+            (FRACTIONAL_BASE, false, None),
+            (FRACTIONAL_BASE, true, Some(ERR_404.to_string())),
+            // 2. Return a lower limit when max_exposure of house_funds cannot cover max_bet
+            // 3. Match the limit play enforces before rolling, ERR_417 above it
+            (1_000, false, None),
+            (1_000, true, Some(ERR_417.to_string())),
+            (5_000, false, None),
+            (5_000, true, Some(ERR_417.to_string())),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            counter += 1;
+        });
+    }
+
+    /// list_games, games_by_partner, games_by_token
+    /// methods must:
+    /// 1. List every registered game, paginated
+    /// 2. List games of a partner, following partner changes
+    /// 3. List games of a token
+    #[test]
+    fn test_list_games() {
+        fn closure_generator(from_index: u64, limit: Option<u64>, seed: u128) -> impl FnOnce() {
+            move || {
+                let context = get_context(vec![], false, 1, 1000 * ONE_NEAR, OWNER_ACCOUNT.to_string());
+                testing_env!(context);
+                let partner_a = format!("{}-partner-a.testnet", seed);
+                let partner_b = format!("{}-partner-b.testnet", seed);
+                let token_a = format!("{}-token-a.testnet", seed);
+                let token_b = format!("{}-token-b.testnet", seed);
+
+                let mut contract = sample_contract(seed);
+                contract.internal_deposit_storage_account(&CONTRACT_ACCOUNT.to_string(), 1000 * ONE_NEAR);
+                register_sample_token(&mut contract, &token_a);
+                register_sample_token(&mut contract, &token_b);
+
+                let games = [
+                    (partner_a.clone(), token_a.clone()),
+                    (partner_a.clone(), token_b.clone()),
+                    (partner_b.clone(), token_a.clone()),
+                    (partner_b.clone(), token_a.clone()),
+                ];
+                for (partner, token) in games.iter() {
+                    contract.create_new_partner(
+                        None,
+                        partner.clone(),
+                        "nft.testnet".to_string(),
+                        token.clone(),
+                        U128(0),
+                        U128(FRACTIONAL_BASE),
+                        U128(0),
+                        U128(100),
+                        U128(10),
+                        200,
+                        10,
+                        None,
+                        None,
+                        U128(0),
+                        U128(0),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                    );
+                }
+
+                let page = contract.list_games(Some(U64(from_index)), limit.map(U64));
+                let expected_len = (games.len() as u64)
+                    .saturating_sub(from_index)
+                    .min(limit.unwrap_or(u64::MAX));
+                assert_eq!(page.len() as u64, expected_len);
+                for (i, (game_id, game)) in page.iter().enumerate() {
+                    let index = from_index as usize + i;
+                    assert_eq!(game_id, &index.to_string());
+                    assert_eq!(game.partner_owner, games[index].0);
+                }
+
+                let ids = |list: Vec<(GameId, PartneredGame)>| -> Vec<GameId> {
+                    list.into_iter().map(|(game_id, _)| game_id).collect()
+                };
+                assert_eq!(ids(contract.games_by_partner(partner_a.clone(), None, None)), vec!["0", "1"]);
+                assert_eq!(ids(contract.games_by_token(token_a.clone(), None, None)), vec!["0", "2", "3"]);
+                assert_eq!(ids(contract.games_by_token(token_a.clone(), Some(U64(1)), Some(U64(1)))), vec!["2"]);
+                assert!(contract.games_by_partner("nobody.testnet".to_string(), None, None).is_empty());
+
+                contract.alter_partner(
+                    "1".to_string(),
+                    partner_b.clone(),
+                    U128(0),
+                    false,
+                    U128(FRACTIONAL_BASE),
+                    U128(0),
+                    U128(100),
+                    U128(10),
+                    200,
+                    10,
+                    None,
+                    None,
+                    U128(0),
+                    U128(0),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+                assert_eq!(ids(contract.games_by_partner(partner_a.clone(), None, None)), vec!["0"]);
+                let mut partner_b_games = ids(contract.games_by_partner(partner_b, None, None));
+                partner_b_games.sort();
+                assert_eq!(partner_b_games, vec!["1", "2", "3"]);
+            }
+        }
+
+        let test_cases = [
+            // 1. List every registered game, paginated
+            // 2. List games of a partner, following partner changes
+            // 3. List games of a token
+            (0, None, None),
+            (1, Some(2), None),
+            (3, Some(5), None),
+            (7, Some(1), None),
+        ];
+
+        let mut counter = 0;
+        IntoIterator::into_iter(test_cases).for_each(|v| {
+            run_test_case(closure_generator(v.0, v.1, counter), v.2);
+            counter += 1;
+        });
+    }
+}
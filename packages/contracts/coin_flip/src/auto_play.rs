@@ -0,0 +1,187 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Serialize, Deserialize};
+
+use crate::{BetChoice, BetReceipt, RollDirection, ERR_413, MAX_BATCH_BETS};
+
+// gas left required to place one more auto_play bet and still save account and game
+pub const AUTO_BET_GAS: u64 = 15_000_000_000_000;
+
+/// How the size of each auto_play bet follows from the previous one
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum StakingPlan {
+    // every bet is base_bet
+    Fixed,
+    // doubles the bet after a loss, back to base_bet after a win
+    Martingale,
+    // doubles the bet after a win, back to base_bet after a loss
+    AntiMartingale,
+}
+
+/// Betting strategy run by auto_play. Odds, direction and target_multiplier are used
+/// for every bet as in play, stop_loss and take_profit are net amounts over the run
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AutoPlayStrategy {
+    pub plan: StakingPlan,
+    pub base_bet: U128,
+    pub odds: u32,
+    #[serde(default)]
+    pub direction: Option<RollDirection>,
+    #[serde(default)]
+    pub target_multiplier: Option<U128>,
+    // the run never places a bet that could take its losses past stop_loss
+    #[serde(default)]
+    pub stop_loss: Option<U128>,
+    // the run stops once its profit reaches take_profit
+    #[serde(default)]
+    pub take_profit: Option<U128>,
+    pub max_bets: u32,
+}
+
+/// Why an auto_play run stopped
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", tag = "kind", rename_all = "snake_case")]
+pub enum AutoPlayStop {
+    MaxBets,
+    StopLoss,
+    TakeProfit,
+    // not enough prepaid gas left for another bet
+    OutOfGas,
+    // next bet would have panicked with error, e.g. ERR_402 or ERR_407
+    BetRejected { error: String },
+}
+
+/// Result of an auto_play run
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AutoPlayReport {
+    pub receipts: Vec<BetReceipt>,
+    pub stop: AutoPlayStop,
+    #[serde(with = "crate::string")]
+    pub total_bet: u128,
+    // prizes and jackpots won over the run
+    #[serde(with = "crate::string")]
+    pub total_payout: u128,
+    #[serde(with = "crate::string")]
+    pub credits: u128,
+}
+
+impl AutoPlayStrategy {
+    pub fn choice(&self) -> BetChoice {
+        BetChoice::new(self.odds, self.direction, self.target_multiplier)
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.base_bet.0 == 0 || self.max_bets == 0 || self.max_bets as usize > MAX_BATCH_BETS {
+            return Err(ERR_413);
+        }
+        Ok(())
+    }
+
+    /// Size of the next bet given the size and outcome of the last one
+    pub fn next_bet_size(&self, last: Option<(u128, bool)>) -> u128 {
+        match (self.plan, last) {
+            (StakingPlan::Martingale, Some((bet_size, false)))
+            | (StakingPlan::AntiMartingale, Some((bet_size, true))) => bet_size.saturating_mul(2),
+            _ => self.base_bet.0,
+        }
+    }
+
+    /// Limit of the strategy reached before placing next_bet, if any
+    pub fn limit_reached(
+        &self,
+        bets: usize,
+        total_bet: u128,
+        total_payout: u128,
+        next_bet: u128,
+    ) -> Option<AutoPlayStop> {
+        if bets >= self.max_bets as usize {
+            return Some(AutoPlayStop::MaxBets);
+        }
+        if let Some(take_profit) = self.take_profit {
+            if total_payout >= total_bet + take_profit.0 {
+                return Some(AutoPlayStop::TakeProfit);
+            }
+        }
+        if let Some(stop_loss) = self.stop_loss {
+            let loss = total_bet.saturating_sub(total_payout);
+            if loss + next_bet > stop_loss.0 {
+                return Some(AutoPlayStop::StopLoss);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy(plan: StakingPlan, stop_loss: Option<u128>, take_profit: Option<u128>) -> AutoPlayStrategy {
+        AutoPlayStrategy {
+            plan,
+            base_bet: U128(10),
+            odds: 128,
+            direction: None,
+            target_multiplier: None,
+            stop_loss: stop_loss.map(U128),
+            take_profit: take_profit.map(U128),
+            max_bets: 5,
+        }
+    }
+
+    /// next_bet_size
+    /// method must:
+    /// 1. Start every plan at base_bet
+    /// 2. Keep fixed bets at base_bet
+    /// 3. Double martingale bets after losses and anti martingale bets after wins
+    #[test]
+    fn test_next_bet_size() {
+        let fixed = strategy(StakingPlan::Fixed, None, None);
+        let martingale = strategy(StakingPlan::Martingale, None, None);
+        let anti_martingale = strategy(StakingPlan::AntiMartingale, None, None);
+
+        // 1. Start every plan at base_bet
+        for plan in [&fixed, &martingale, &anti_martingale].iter() {
+            assert_eq!(plan.next_bet_size(None), 10);
+        }
+
+        // 2. Keep fixed bets at base_bet
+        assert_eq!(fixed.next_bet_size(Some((10, false))), 10);
+        assert_eq!(fixed.next_bet_size(Some((10, true))), 10);
+
+        // 3. Double martingale bets after losses and anti martingale bets after wins
+        assert_eq!(martingale.next_bet_size(Some((40, false))), 80);
+        assert_eq!(martingale.next_bet_size(Some((40, true))), 10);
+        assert_eq!(anti_martingale.next_bet_size(Some((40, true))), 80);
+        assert_eq!(anti_martingale.next_bet_size(Some((40, false))), 10);
+        assert_eq!(martingale.next_bet_size(Some((u128::MAX, false))), u128::MAX);
+    }
+
+    /// limit_reached
+    /// method must:
+    /// 1. Stop after max_bets bets
+    /// 2. Stop once profit reaches take_profit
+    /// 3. Stop before a bet that could take losses past stop_loss
+    #[test]
+    fn test_limit_reached() {
+        let limits = strategy(StakingPlan::Fixed, Some(50), Some(30));
+        let no_limits = strategy(StakingPlan::Fixed, None, None);
+
+        assert_eq!(limits.limit_reached(0, 0, 0, 10), None);
+        assert_eq!(no_limits.limit_reached(4, 1_000, 0, 1_000), None);
+
+        // 1. Stop after max_bets bets
+        assert_eq!(no_limits.limit_reached(5, 0, 0, 10), Some(AutoPlayStop::MaxBets));
+
+        // 2. Stop once profit reaches take_profit
+        assert_eq!(limits.limit_reached(2, 20, 49, 10), None);
+        assert_eq!(limits.limit_reached(2, 20, 50, 10), Some(AutoPlayStop::TakeProfit));
+
+        // 3. Stop before a bet that could take losses past stop_loss
+        assert_eq!(limits.limit_reached(4, 40, 0, 10), None);
+        assert_eq!(limits.limit_reached(4, 40, 0, 11), Some(AutoPlayStop::StopLoss));
+        assert_eq!(limits.limit_reached(4, 60, 20, 10), None);
+    }
+}
@@ -0,0 +1,385 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::AccountId;
+
+use crate::random::{bet_hash, draw, jackpot_hash};
+use crate::{GameType, PartneredGame, CRASH_ROLL_RANGE, FRACTIONAL_BASE};
+
+/// Side of the roll a bet wins on. Under wins when roll < odds and Over wins when
+/// roll >= odds_precision - odds, so both win odds out of odds_precision rolls
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum RollDirection {
+    #[default]
+    Under,
+    Over,
+}
+
+impl RollDirection {
+    pub fn wins(&self, roll: u128, odds: u32, odds_precision: u32) -> bool {
+        match self {
+            RollDirection::Under => roll < odds as u128,
+            RollDirection::Over => roll >= (odds_precision - odds) as u128,
+        }
+    }
+}
+
+/// What a player bets on. Dice games use odds and direction, crash games use
+/// target_multiplier and wheel games ignore all of them
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BetChoice {
+    pub odds: u32,
+    pub direction: RollDirection,
+    #[serde(with = "crate::string")]
+    pub target_multiplier: u128, // base 10e-5
+}
+
+impl BetChoice {
+    pub fn new(odds: u32, direction: Option<RollDirection>, target_multiplier: Option<U128>) -> Self {
+        Self {
+            odds,
+            direction: direction.unwrap_or_default(),
+            target_multiplier: target_multiplier.map(|v| v.0).unwrap_or(0),
+        }
+    }
+}
+
+/// One bet of a play_batch call, direction and target_multiplier default as in play
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchBet {
+    pub bet_size: U128,
+    pub odds: u32,
+    #[serde(default)]
+    pub direction: Option<RollDirection>,
+    #[serde(default)]
+    pub target_multiplier: Option<U128>,
+}
+
+impl BatchBet {
+    pub fn choice(&self) -> BetChoice {
+        BetChoice::new(self.odds, self.direction, self.target_multiplier)
+    }
+}
+
+/// Partnered game parameters a bet was placed under
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameParameters {
+    #[serde(with = "crate::string")]
+    pub nft_fee: u128,
+    #[serde(with = "crate::string")]
+    pub owner_fee: u128,
+    #[serde(with = "crate::string")]
+    pub house_fee: u128,
+    #[serde(with = "crate::string")]
+    pub partner_fee: u128,
+    #[serde(with = "crate::string")]
+    pub bet_payment_adjustment: u128,
+    pub odds_precision: u32,
+    pub game_type: GameType,
+    #[serde(with = "crate::string")]
+    pub jackpot_fee: u128,
+    pub jackpot_trigger: u32,
+}
+
+impl From<&PartneredGame> for GameParameters {
+    fn from(game: &PartneredGame) -> Self {
+        Self {
+            nft_fee: game.nft_fee,
+            owner_fee: game.owner_fee,
+            house_fee: game.house_fee,
+            partner_fee: game.partner_fee,
+            bet_payment_adjustment: game.bet_payment_adjustment,
+            odds_precision: game.odds_precision,
+            game_type: game.game_type.clone(),
+            jackpot_fee: game.jackpot_fee,
+            jackpot_trigger: game.jackpot_trigger,
+        }
+    }
+}
+
+impl GameParameters {
+    /// Prize paid out for a winning dice bet of net_bet with odds winning rolls out of odds_precision
+    pub fn won_value(&self, net_bet: u128, odds: u32) -> u128 {
+        (((net_bet * self.odds_precision as u128) / (odds as u128)) * self.bet_payment_adjustment)
+            / FRACTIONAL_BASE
+    }
+
+    /// Rolls are drawn in [0, roll_range)
+    pub fn roll_range(&self) -> u128 {
+        match &self.game_type {
+            GameType::Dice => self.odds_precision as u128,
+            GameType::Wheel { segments } => GameType::table_weight(segments),
+            GameType::Crash { .. } => CRASH_ROLL_RANGE,
+        }
+    }
+
+    /// Outcome and prize of a bet given its roll. Dice bets win on their roll,
+    /// wheel bets win when their segment pays anything and crash bets win when
+    /// the crash point reaches their target
+    pub fn settle(&self, net_bet: u128, choice: &BetChoice, roll: u128) -> (bool, u128) {
+        match &self.game_type {
+            GameType::Dice => {
+                if choice.direction.wins(roll, choice.odds, self.odds_precision) {
+                    (true, self.won_value(net_bet, choice.odds))
+                } else {
+                    (false, 0)
+                }
+            }
+            GameType::Wheel { segments } => {
+                let segment = &segments[GameType::segment_index(segments, roll)];
+                let payout = (net_bet * segment.multiplier) / FRACTIONAL_BASE;
+                (payout > 0, payout)
+            }
+            GameType::Crash { .. } => {
+                if GameType::crash_point(self.bet_payment_adjustment, roll) >= choice.target_multiplier {
+                    (true, (net_bet * choice.target_multiplier) / FRACTIONAL_BASE)
+                } else {
+                    (false, 0)
+                }
+            }
+        }
+    }
+
+    /// Largest prize a bet can be paid, house funds must cover it
+    pub fn max_payout(&self, net_bet: u128, choice: &BetChoice) -> u128 {
+        match &self.game_type {
+            GameType::Dice => self.won_value(net_bet, choice.odds),
+            GameType::Wheel { segments } => {
+                let multiplier = segments.iter().map(|segment| segment.multiplier).max().unwrap_or(0);
+                (net_bet * multiplier) / FRACTIONAL_BASE
+            }
+            GameType::Crash { .. } => (net_bet * choice.target_multiplier) / FRACTIONAL_BASE,
+        }
+    }
+}
+
+/// Cuts taken from a bet before it is played
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeCuts {
+    #[serde(with = "crate::string")]
+    pub nft_cut: u128,
+    #[serde(with = "crate::string")]
+    pub owner_cut: u128,
+    #[serde(with = "crate::string")]
+    pub house_cut: u128,
+    #[serde(with = "crate::string")]
+    pub partner_cut: u128,
+    #[serde(with = "crate::string")]
+    pub jackpot_cut: u128,
+}
+
+impl FeeCuts {
+    pub fn new(bet_size: u128, params: &GameParameters) -> Self {
+        Self {
+            nft_cut: (bet_size * params.nft_fee) / FRACTIONAL_BASE,
+            owner_cut: (bet_size * params.owner_fee) / FRACTIONAL_BASE,
+            house_cut: (bet_size * params.house_fee) / FRACTIONAL_BASE,
+            partner_cut: (bet_size * params.partner_fee) / FRACTIONAL_BASE,
+            jackpot_cut: (bet_size * params.jackpot_fee) / FRACTIONAL_BASE,
+        }
+    }
+
+    /// Cuts taken from the pot of a player versus player match. The house
+    /// takes no risk on these matches, so it takes no cut either, and as
+    /// matches never roll for the jackpot they do not feed it
+    pub fn without_house(pot: u128, params: &GameParameters) -> Self {
+        Self {
+            house_cut: 0,
+            jackpot_cut: 0,
+            ..Self::new(pot, params)
+        }
+    }
+
+    pub fn total(&self) -> u128 {
+        self.nft_cut + self.owner_cut + self.house_cut + self.partner_cut + self.jackpot_cut
+    }
+
+    /// Adds the cuts of another bet, so that a batch is credited at once
+    pub fn add(&mut self, other: &FeeCuts) {
+        self.nft_cut += other.nft_cut;
+        self.owner_cut += other.owner_cut;
+        self.house_cut += other.house_cut;
+        self.partner_cut += other.partner_cut;
+        self.jackpot_cut += other.jackpot_cut;
+    }
+}
+
+/// Result of a settled bet. Returned to the player and stored with every input
+/// needed to recompute its outcome
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BetReceipt {
+    // bet_id is also the nonce used to derive the roll
+    #[serde(with = "crate::string")]
+    pub bet_id: u128,
+    pub account_id: AccountId,
+    pub game_code: String,
+    pub token_contract: AccountId,
+    #[serde(with = "crate::string")]
+    pub bet_size: u128,
+    #[serde(with = "crate::string")]
+    pub net_bet: u128,
+    pub fees: FeeCuts,
+    // random seed of the block the bet was settled in
+    pub seed: Base64VecU8,
+    #[serde(with = "crate::string")]
+    pub roll: u128,
+    pub odds: u32,
+    pub direction: RollDirection,
+    #[serde(with = "crate::string")]
+    pub target_multiplier: u128,
+    pub game_parameters: GameParameters,
+    pub outcome: bool,
+    #[serde(with = "crate::string")]
+    pub payout: u128,
+    // whether the jackpot trigger hit, paying the whole pool on top of payout
+    pub jackpot_won: bool,
+    #[serde(with = "crate::string")]
+    pub jackpot_payout: u128,
+    // player's credits in token_contract after the bet
+    #[serde(with = "crate::string")]
+    pub credits: u128,
+    #[serde(with = "crate::string")]
+    pub block_index: u64,
+}
+
+/// Outcome of a bet recomputed from its record, with every intermediate step
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BetVerification {
+    #[serde(with = "crate::string")]
+    pub bet_id: u128,
+    pub outcome: bool,
+    pub matches_record: bool,
+    #[serde(with = "crate::string")]
+    pub roll: u128,
+    #[serde(with = "crate::string")]
+    pub won_value: u128,
+    pub jackpot_won: bool,
+    pub derivation: Vec<String>,
+}
+
+impl BetReceipt {
+    pub fn choice(&self) -> BetChoice {
+        BetChoice {
+            odds: self.odds,
+            direction: self.direction,
+            target_multiplier: self.target_multiplier,
+        }
+    }
+
+    pub fn verify(&self) -> BetVerification {
+        let mut derivation = Vec::new();
+        let params = &self.game_parameters;
+        let seed = &self.seed.0;
+
+        let hash = bet_hash(seed, &self.account_id, self.bet_id, &self.game_code);
+        derivation.push(format!(
+            "bet_hash = keccak256(u32_be(len(seed)) | seed | u32_be(len(account_id)) | account_id | u128_be(bet_id) | u32_be(len(game_code)) | game_code) = keccak256({} | 0x{} | {} | {} | {} | {} | {}) = 0x{}",
+            seed.len(),
+            to_hex(seed),
+            self.account_id.len(),
+            self.account_id,
+            self.bet_id,
+            self.game_code.len(),
+            self.game_code,
+            to_hex(&hash)
+        ));
+
+        let range = params.roll_range();
+        let (roll, rehashes) = draw(&hash, range);
+        derivation.push(format!(
+            "roll = u128_be(bet_hash[0..16]) % roll_range = 0x{} % {} = {}, rehashed {} times to remove modulo bias",
+            to_hex(&hash[0..16]), range, roll, rehashes
+        ));
+
+        let fees = FeeCuts::new(self.bet_size, params);
+        let net_bet = self.bet_size - fees.total();
+        derivation.push(format!(
+            "net_bet = bet_size - nft_cut - owner_cut - house_cut - partner_cut - jackpot_cut = {} - {} - {} - {} - {} - {} = {}",
+            self.bet_size, fees.nft_cut, fees.owner_cut, fees.house_cut, fees.partner_cut, fees.jackpot_cut, net_bet
+        ));
+
+        let (outcome, won_value) = params.settle(net_bet, &self.choice(), roll);
+        match &params.game_type {
+            GameType::Dice => {
+                let precision = params.odds_precision;
+                derivation.push(match self.direction {
+                    RollDirection::Under => format!(
+                        "outcome = roll < odds = {} < {} = {}",
+                        roll, self.odds, outcome
+                    ),
+                    RollDirection::Over => format!(
+                        "outcome = roll >= odds_precision - odds = {} >= {} - {} = {}",
+                        roll, precision, self.odds, outcome
+                    ),
+                });
+                if outcome {
+                    derivation.push(format!(
+                        "won_value = ((net_bet * odds_precision) / odds) * bet_payment_adjustment / {} = (({} * {}) / {}) * {} / {} = {}",
+                        FRACTIONAL_BASE, net_bet, precision, self.odds, params.bet_payment_adjustment, FRACTIONAL_BASE, won_value
+                    ));
+                } else {
+                    derivation.push("won_value = 0, bet was lost".to_string());
+                }
+            }
+            GameType::Wheel { segments } => {
+                let index = GameType::segment_index(segments, roll);
+                derivation.push(format!(
+                    "segment = first segment whose cumulative weight exceeds roll = {} (weight {})",
+                    index, segments[index].weight
+                ));
+                derivation.push(format!(
+                    "won_value = net_bet * multiplier / {} = {} * {} / {} = {}",
+                    FRACTIONAL_BASE, net_bet, segments[index].multiplier, FRACTIONAL_BASE, won_value
+                ));
+            }
+            GameType::Crash { .. } => {
+                let crash_point = GameType::crash_point(params.bet_payment_adjustment, roll);
+                derivation.push(format!(
+                    "crash_point = bet_payment_adjustment * {} / ({} - roll) = {} * {} / ({} - {}) = {}",
+                    CRASH_ROLL_RANGE, CRASH_ROLL_RANGE, params.bet_payment_adjustment, CRASH_ROLL_RANGE,
+                    CRASH_ROLL_RANGE, roll, crash_point
+                ));
+                derivation.push(format!(
+                    "outcome = crash_point >= target_multiplier = {} >= {} = {}",
+                    crash_point, self.target_multiplier, outcome
+                ));
+                derivation.push(format!(
+                    "won_value = net_bet * target_multiplier / {} = {} * {} / {} = {}",
+                    FRACTIONAL_BASE, net_bet, self.target_multiplier, FRACTIONAL_BASE, won_value
+                ));
+            }
+        }
+
+        let jackpot_hash = jackpot_hash(&hash);
+        let (jackpot_roll, _) = draw(&jackpot_hash, params.jackpot_trigger as u128);
+        let jackpot_won = jackpot_roll == 0;
+        derivation.push(format!(
+            "jackpot_won = u128_be(keccak256(\"jackpot\" | bet_hash)[0..16]) % jackpot_trigger == 0 = 0x{} % {} == 0 = {}",
+            to_hex(&jackpot_hash[0..16]), params.jackpot_trigger, jackpot_won
+        ));
+
+        BetVerification {
+            bet_id: self.bet_id,
+            outcome,
+            matches_record: outcome == self.outcome
+                && roll == self.roll
+                && won_value == self.payout
+                && jackpot_won == self.jackpot_won,
+            roll,
+            won_value,
+            jackpot_won,
+            derivation,
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,43 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::AccountId;
+
+use crate::FeeCuts;
+
+/// Player versus player coin flip opened with open_challenge. The creator's stake
+/// is held by the contract until another player matches it or the creator cancels
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Challenge {
+    pub creator: AccountId,
+    pub game_code: String,
+    pub token_contract: AccountId,
+    #[serde(with = "crate::string")]
+    pub stake: u128,
+    #[serde(with = "crate::string")]
+    pub open_block: u64,
+}
+
+/// Result of a matched challenge. The winner is credited both stakes minus fees
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChallengeReceipt {
+    // challenge_id is also the nonce used to derive the roll
+    #[serde(with = "crate::string")]
+    pub challenge_id: u128,
+    pub creator: AccountId,
+    pub challenger: AccountId,
+    pub game_code: String,
+    pub token_contract: AccountId,
+    #[serde(with = "crate::string")]
+    pub stake: u128,
+    pub fees: FeeCuts,
+    pub seed: Base64VecU8,
+    // creator wins on roll 0, challenger on roll 1
+    #[serde(with = "crate::string")]
+    pub roll: u128,
+    pub winner: AccountId,
+    #[serde(with = "crate::string")]
+    pub prize: u128,
+}
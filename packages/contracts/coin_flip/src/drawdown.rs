@@ -0,0 +1,187 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+
+use crate::ERR_216;
+
+/// House losses a game accepts before its circuit breaker blocks it, a zero limit is disabled
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DrawdownLimits {
+    // largest house loss within loss_window
+    #[serde(with = "crate::string")]
+    pub max_window_loss: u128,
+    // nanoseconds
+    #[serde(with = "crate::string")]
+    pub loss_window: u64,
+    // largest house loss within a single block
+    #[serde(with = "crate::string")]
+    pub max_block_loss: u128,
+}
+
+impl DrawdownLimits {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.max_window_loss > 0 && self.loss_window == 0 {
+            return Err(ERR_216);
+        }
+        Ok(())
+    }
+}
+
+/// Limit breached by the bet that tripped a circuit breaker
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum DrawdownBreach {
+    Window,
+    Block,
+}
+
+// slices a loss window is split in, the window rolls forward one slice at a time
+pub const DRAWDOWN_BUCKETS: usize = 12;
+
+/// House result of the bets settled during one slice of a loss window
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LossBucket {
+    // block timestamp the slice starts at, in nanoseconds
+    #[serde(with = "crate::string")]
+    pub start: u64,
+    #[serde(with = "crate::string")]
+    pub loss: u128,
+    #[serde(with = "crate::string")]
+    pub gain: u128,
+}
+
+/// House losses counted against the DrawdownLimits of a game. Window losses are summed over
+/// the slices of the last loss_window, gains only offset losses of the same window or block
+/// so the count never goes below zero
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Drawdown {
+    pub buckets: [LossBucket; DRAWDOWN_BUCKETS],
+    // net house loss of the last loss_window as of the last bet counted
+    #[serde(with = "crate::string")]
+    pub window_loss: u128,
+    #[serde(with = "crate::string")]
+    pub block_index: u64,
+    #[serde(with = "crate::string")]
+    pub block_loss: u128,
+}
+
+impl Drawdown {
+    /// Counts what the house gained and paid on a bet, returns the limit it breached if any
+    pub fn record(
+        &mut self,
+        limits: &DrawdownLimits,
+        gain: u128,
+        loss: u128,
+        timestamp: u64,
+        block_index: u64,
+    ) -> Option<DrawdownBreach> {
+        if limits.max_window_loss > 0 {
+            self.window_loss = self.record_window(limits.loss_window, gain, loss, timestamp);
+        }
+        if block_index != self.block_index {
+            self.block_index = block_index;
+            self.block_loss = 0;
+        }
+        self.block_loss = (self.block_loss + loss).saturating_sub(gain);
+
+        if limits.max_window_loss > 0 && self.window_loss > limits.max_window_loss {
+            Some(DrawdownBreach::Window)
+        } else if limits.max_block_loss > 0 && self.block_loss > limits.max_block_loss {
+            Some(DrawdownBreach::Block)
+        } else {
+            None
+        }
+    }
+
+    // adds the bet to the slice of timestamp and returns the net loss of the slices that end
+    // inside the window. Slices are sized so that the ring always holds a whole window, the
+    // oldest slice can overcount the window by less than its length but never undercount it
+    fn record_window(&mut self, loss_window: u64, gain: u128, loss: u128, timestamp: u64) -> u128 {
+        let width = loss_window.div_ceil(DRAWDOWN_BUCKETS as u64 - 1);
+        let start = timestamp - timestamp % width;
+        let bucket = &mut self.buckets[((start / width) % DRAWDOWN_BUCKETS as u64) as usize];
+        if bucket.start != start {
+            *bucket = LossBucket {
+                start,
+                ..LossBucket::default()
+            };
+        }
+        bucket.loss += loss;
+        bucket.gain += gain;
+
+        let window_start = timestamp.saturating_sub(loss_window);
+        let (loss, gain) = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.start.saturating_add(width) > window_start)
+            .fold((0u128, 0u128), |(loss, gain), bucket| (loss + bucket.loss, gain + bucket.gain));
+        loss.saturating_sub(gain)
+    }
+
+    /// Forgets the losses counted so far, so that an unblocked game does not trip again at once
+    pub fn reset(&mut self) {
+        self.buckets = Default::default();
+        self.window_loss = 0;
+        self.block_loss = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// record
+    /// method must:
+    /// 1. Trip when losses of a window or a block go over their limit
+    /// 2. Offset losses with gains of the same window or block only
+    /// 3. Restart counting on a new block
+    /// 4. Forget losses once they are older than loss_window
+    /// 5. Count losses of the last loss_window whenever they happened
+    /// 6. Ignore disabled limits
+    #[test]
+    fn test_record() {
+        // slices of 10ns
+        let limits = DrawdownLimits {
+            max_window_loss: 1_000,
+            loss_window: 110,
+            max_block_loss: 500,
+        };
+        let mut drawdown = Drawdown::default();
+
+        // 1. Trip when losses of a window or a block go over their limit
+        assert_eq!(drawdown.record(&limits, 0, 500, 10, 1), None);
+        assert_eq!(drawdown.record(&limits, 0, 1, 10, 1), Some(DrawdownBreach::Block));
+        assert_eq!(drawdown.record(&limits, 0, 499, 20, 2), None);
+        assert_eq!(drawdown.record(&limits, 0, 1, 20, 2), Some(DrawdownBreach::Window));
+        assert_eq!((drawdown.window_loss, drawdown.block_loss), (1_001, 500));
+
+        // 2. Offset losses with gains of the same window or block only
+        // 3. Restart counting on a new block
+        assert_eq!(drawdown.record(&limits, 800, 0, 30, 3), None);
+        assert_eq!((drawdown.window_loss, drawdown.block_loss), (201, 0));
+
+        // 4. Forget losses once they are older than loss_window
+        let limits = DrawdownLimits {
+            max_block_loss: 0,
+            ..limits
+        };
+        let mut drawdown = Drawdown::default();
+        assert_eq!(drawdown.record(&limits, 0, 900, 10, 1), None);
+        assert_eq!(drawdown.record(&limits, 0, 400, 130, 2), None);
+        assert_eq!(drawdown.window_loss, 400);
+
+        // 5. Count losses of the last loss_window whenever they happened
+        let mut drawdown = Drawdown::default();
+        assert_eq!(drawdown.record(&limits, 0, 600, 100, 1), None);
+        assert_eq!(drawdown.record(&limits, 0, 300, 150, 2), None);
+        assert_eq!(drawdown.record(&limits, 0, 101, 205, 3), Some(DrawdownBreach::Window));
+        assert_eq!(drawdown.window_loss, 1_001);
+
+        // 6. Ignore disabled limits
+        let mut drawdown = Drawdown::default();
+        assert_eq!(drawdown.record(&DrawdownLimits::default(), 0, u128::MAX / 2, 10, 1), None);
+        assert_eq!(drawdown, Drawdown { block_index: 1, block_loss: u128::MAX / 2, ..Drawdown::default() });
+    }
+}
@@ -1,7 +1,7 @@
 // contract errors
 pub const ERR_001: &str = "ERR_001: Account is not registered";
-pub const ERR_002: &str = "ERR_002: No partner registered for this address";
-pub const ERR_003: &str = "ERR_003: Partner already registered for this address";
+pub const ERR_002: &str = "ERR_002: No partner game registered for this id";
+pub const ERR_003: &str = "ERR_003: Partner game already registered for this id";
 pub const ERR_004: &str = "ERR_004: Only partner game owner can call this method";
 pub const ERR_005: &str = "ERR_005: ft_on_transfer msg parameter could not be parsed";
 pub const ERR_006: &str = "ERR_006: Only owner can call this method";
@@ -11,7 +11,7 @@ pub const ERR_007: &str = "ERR_007: Panic mode is on, all non owner tasks are su
 // storage errors
 pub const ERR_101: &str = "ERR_101: Insufficient storage deposit";
 pub const ERR_102: &str = "ERR_102: Must attach at least the minimum deposit value";
-pub const ERR_103: &str = "ERR_103: Cannot unregister storage while user still has token balances to withdraw";
+pub const ERR_103: &str = "ERR_103: Cannot unregister storage while user still has token balances, streaks, house shares, queued liquidity withdrawals, pending bets or open challenges";
 
 // owner actions errors
 pub const ERR_201: &str = "ERR_201: No owner funds to withdraw";
@@ -20,10 +20,28 @@ pub const ERR_203: &str = "ERR_203: Balance for this token is 0";
 pub const ERR_204: &str = "ERR_204: Token index out of bouds";
 pub const ERR_205: &str = "ERR_205: Fee parameters must be <= FRACTION_BASE";
 pub const ERR_206: &str = "ERR_206: max parameter must be greater than min parameter";
+pub const ERR_207: &str = "ERR_207: odds_precision must be between 2 and MAX_ODDS_PRECISION";
+pub const ERR_208: &str = "ERR_208: Odds limits must be between 1 and odds_precision - 1";
+pub const ERR_209: &str = "ERR_209: Payout table expected value cannot exceed bet_payment_adjustment";
+pub const ERR_210: &str = "ERR_210: Payout table must have 1 to MAX_WHEEL_SEGMENTS segments, all with weight";
+pub const ERR_211: &str = "ERR_211: Crash max_multiplier must be greater than 1x";
+pub const ERR_212: &str = "ERR_212: jackpot_trigger must be greater than 1";
+pub const ERR_213: &str = "ERR_213: max_streak must be at least 1";
+pub const ERR_214: &str = "ERR_214: withdrawal_delay cannot exceed MAX_WITHDRAWAL_DELAY";
+pub const ERR_215: &str = "ERR_215: max_exposure must be between 1 and FRACTIONAL_BASE";
+pub const ERR_216: &str = "ERR_216: Drawdown limits with a max_window_loss need a loss_window";
 
 
 // partnered game errors
 pub const ERR_301: &str = "ERR_301: Token sent is not the registered token type for game";
+pub const ERR_302: &str = "ERR_302: Game id must have 1 to 64 lowercase letters, digits, '-' or '_'";
+pub const ERR_303: &str = "ERR_303: Numeric game ids are reserved for auto incremented games";
+pub const ERR_304: &str = "ERR_304: No queued house withdrawal registered for this id";
+pub const ERR_305: &str = "ERR_305: House withdrawal cannot be executed before its withdrawal_delay has passed";
+pub const ERR_306: &str = "ERR_306: Account already has MAX_QUEUED_WITHDRAWALS queued house withdrawals in this game";
+pub const ERR_307: &str = "ERR_307: Only the partner or the owner of a game can unblock it";
+pub const ERR_308: &str = "ERR_308: Game is not blocked";
+pub const ERR_309: &str = "ERR_309: Only the owner can unblock a game it blocked";
 
 // player actions errors
 pub const ERR_401: &str = "ERR_401: Not enough balance for this withdraw";
@@ -32,4 +50,54 @@ pub const ERR_403: &str = "ERR_403: Minimum bet size not respected";
 pub const ERR_404: &str = "ERR_404: Maximum bet size not respected";
 pub const ERR_405: &str = "ERR_405: Minimum odds not respected";
 pub const ERR_406: &str = "ERR_406: Maximum odds not respected";
-pub const ERR_407: &str = "ERR_407: Bet denied, house_funds are not enough to cover your possible win value";
\ No newline at end of file
+pub const ERR_407: &str = "ERR_407: Bet denied, house_funds are not enough to cover your possible win value";
+pub const ERR_408: &str = "ERR_408: No pending bet registered for this id";
+pub const ERR_409: &str = "ERR_409: Bet cannot be revealed before the reveal delay has passed";
+pub const ERR_410: &str = "ERR_410: No bet registered for this id";
+pub const ERR_411: &str = "ERR_411: Target multiplier must be greater than 1x and at most the game max_multiplier";
+pub const ERR_412: &str = "ERR_412: Batch must have between 1 and MAX_BATCH_BETS bets";
+pub const ERR_413: &str = "ERR_413: Auto play needs a base_bet and between 1 and MAX_BATCH_BETS max_bets";
+pub const ERR_414: &str = "ERR_414: No streak open for this game";
+pub const ERR_415: &str = "ERR_415: A streak is already open for this game, press or collect it first";
+pub const ERR_416: &str = "ERR_416: Streak reached the game max_streak and can only be collected";
+pub const ERR_417: &str = "ERR_417: Bet denied, its possible win exceeds the share of house_funds the game risks per bet";
+pub const ERR_418: &str = "ERR_418: Game is blocked, bets are suspended until its partner or owner unblocks it";
+pub const ERR_419: &str = "ERR_419: Bet was not revealed before its reveal deadline and can only be expired";
+pub const ERR_420: &str = "ERR_420: Bet cannot be expired before its reveal deadline has passed";
+
+
+// token registry errors
+pub const ERR_501: &str = "ERR_501: Token is not registered in the token whitelist";
+pub const ERR_502: &str = "ERR_502: Token is already registered in the token whitelist";
+pub const ERR_503: &str = "ERR_503: Could not fetch token metadata";
+
+// pvp challenge errors
+pub const ERR_601: &str = "ERR_601: No open challenge registered for this id";
+pub const ERR_602: &str = "ERR_602: Only the creator of a challenge can cancel it";
+pub const ERR_603: &str = "ERR_603: Challenge cannot be accepted by its creator";
+
+// raffle errors
+pub const ERR_701: &str = "ERR_701: Raffle round needs a ticket price and to close in the future";
+pub const ERR_702: &str = "ERR_702: Winner shares must be 1 to MAX_RAFFLE_WINNERS positive shares summing to FRACTIONAL_BASE";
+pub const ERR_703: &str = "ERR_703: No raffle round registered for this id";
+pub const ERR_704: &str = "ERR_704: Raffle round is closed for ticket sales";
+pub const ERR_705: &str = "ERR_705: Raffle round cannot be closed before closes_at";
+pub const ERR_706: &str = "ERR_706: Must buy at least one ticket";
+pub const ERR_707: &str = "ERR_707: Raffle round was already drawn";
+pub const ERR_708: &str = "ERR_708: Raffle round was already closed";
+pub const ERR_709: &str = "ERR_709: Raffle round can only be drawn REVEAL_DELAY_BLOCKS after close_raffle";
+
+// tournament errors
+pub const ERR_801: &str = "ERR_801: Tournament must end after it starts and in the future";
+pub const ERR_802: &str = "ERR_802: Prize shares must be 1 to MAX_TOURNAMENT_WINNERS positive shares summing to FRACTIONAL_BASE";
+pub const ERR_803: &str = "ERR_803: No tournament registered for this id";
+pub const ERR_804: &str = "ERR_804: Game already runs MAX_GAME_TOURNAMENTS unsettled tournaments";
+pub const ERR_805: &str = "ERR_805: Tournament cannot be settled before it ends";
+pub const ERR_806: &str = "ERR_806: Tournament was already settled";
+
+// house liquidity errors
+pub const ERR_901: &str = "ERR_901: Not enough house shares";
+pub const ERR_902: &str = "ERR_902: House funds not reserved for pending bets are not enough to pay the withdrawal";
+pub const ERR_903: &str = "ERR_903: Bankroll is empty, house shares cannot be priced";
+pub const ERR_904: &str = "ERR_904: Amount is too small to be worth a house share";
+pub const ERR_905: &str = "ERR_905: House share transfers need a positive amount and a receiver other than the sender";
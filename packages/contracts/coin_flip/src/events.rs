@@ -0,0 +1,364 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json;
+use near_sdk::{env, AccountId};
+
+use crate::{BetReceipt, Challenge, ChallengeReceipt, Drawdown, DrawdownBreach, FeeCuts, HouseWithdrawal, PartneredGame, RaffleRound, RollDirection, Streak, TokenInfo, Tournament};
+
+/// NEP-297 standard name and version of all events emitted by the contract.
+/// Bump EVENT_VERSION whenever the data of any event below changes
+pub const EVENT_STANDARD: &str = "coin_flip";
+pub const EVENT_VERSION: &str = "1.21.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum WithdrawalKind {
+    Credits,
+    Owner,
+    Nft,
+    Partner,
+    HouseFunds,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositData<'a> {
+    pub account_id: &'a AccountId,
+    pub token_contract: &'a AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferRefundedData<'a> {
+    pub sender_id: &'a AccountId,
+    pub token_contract: &'a AccountId,
+    pub amount: U128,
+    pub reason: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BetCommittedData<'a> {
+    pub bet_id: U128,
+    pub account_id: &'a AccountId,
+    pub game_code: &'a str,
+    pub bet_size: U128,
+    pub odds: u32,
+    pub direction: RollDirection,
+    pub target_multiplier: U128,
+    pub potential_win: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BetExpiredData<'a> {
+    pub bet_id: U128,
+    pub account_id: &'a AccountId,
+    pub game_code: &'a str,
+    pub bet_size: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BetData<'a> {
+    pub bet_id: U128,
+    pub account_id: &'a AccountId,
+    pub game_code: &'a str,
+    pub token_contract: &'a AccountId,
+    pub bet_size: U128,
+    pub net_bet: U128,
+    pub odds: u32,
+    pub odds_precision: u32,
+    pub direction: RollDirection,
+    pub target_multiplier: U128,
+    pub roll: U128,
+    pub outcome: bool,
+    pub payout: U128,
+    pub jackpot_won: bool,
+    pub jackpot_payout: U128,
+}
+
+impl<'a> From<&'a BetReceipt> for BetData<'a> {
+    fn from(receipt: &'a BetReceipt) -> Self {
+        Self {
+            bet_id: U128(receipt.bet_id),
+            account_id: &receipt.account_id,
+            game_code: &receipt.game_code,
+            token_contract: &receipt.token_contract,
+            bet_size: U128(receipt.bet_size),
+            net_bet: U128(receipt.net_bet),
+            odds: receipt.odds,
+            odds_precision: receipt.game_parameters.odds_precision,
+            direction: receipt.direction,
+            target_multiplier: U128(receipt.target_multiplier),
+            roll: U128(receipt.roll),
+            outcome: receipt.outcome,
+            payout: U128(receipt.payout),
+            jackpot_won: receipt.jackpot_won,
+            jackpot_payout: U128(receipt.jackpot_payout),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChallengeData<'a> {
+    pub challenge_id: U128,
+    pub challenge: &'a Challenge,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreakData<'a> {
+    pub account_id: &'a AccountId,
+    pub game_code: &'a str,
+    pub streak: &'a Streak,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaffleData<'a> {
+    pub round_id: U128,
+    pub round: &'a RaffleRound,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaffleTicketsData<'a> {
+    pub round_id: U128,
+    pub account_id: &'a AccountId,
+    pub tickets: U64,
+    pub cost: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TournamentData<'a> {
+    pub tournament_id: U128,
+    pub tournament: &'a Tournament,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TournamentFundedData<'a> {
+    pub tournament_id: U128,
+    pub sender_id: &'a AccountId,
+    pub token_contract: &'a AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WithdrawalData<'a> {
+    pub kind: WithdrawalKind,
+    pub receiver_id: &'a AccountId,
+    pub token_contract: &'a AccountId,
+    pub amount: U128,
+    pub game_code: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameData<'a> {
+    pub game_code: &'a str,
+    pub game: &'a PartneredGame,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameBlockedData<'a> {
+    pub game_code: &'a str,
+    pub breach: DrawdownBreach,
+    pub drawdown: &'a Drawdown,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameUnblockedData<'a> {
+    pub game_code: &'a str,
+    pub account_id: &'a AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HouseFundedData<'a> {
+    pub game_code: &'a str,
+    pub sender_id: &'a AccountId,
+    pub token_contract: &'a AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HouseWithdrawalData<'a> {
+    pub game_code: &'a str,
+    pub withdrawal: &'a HouseWithdrawal,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityData<'a> {
+    pub game_code: &'a str,
+    pub account_id: &'a AccountId,
+    pub token_contract: &'a AccountId,
+    pub amount: U128,
+    pub shares: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HouseSharesTransferData<'a> {
+    pub game_code: &'a str,
+    pub sender_id: &'a AccountId,
+    pub receiver_id: &'a AccountId,
+    pub shares: U128,
+    pub memo: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HouseSharesBurnData<'a> {
+    pub game_code: &'a str,
+    pub account_id: &'a AccountId,
+    pub shares: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeesAccruedData<'a> {
+    pub game_code: &'a str,
+    pub token_contract: &'a AccountId,
+    pub fees: &'a FeeCuts,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenData<'a> {
+    pub token_contract: &'a AccountId,
+    pub token: Option<&'a TokenInfo>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PanicToggleData<'a> {
+    pub account_id: &'a AccountId,
+    pub panic_button: bool,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OwnerUpdatedData<'a> {
+    pub old_owner: &'a AccountId,
+    pub new_owner: &'a AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data", rename_all = "snake_case")]
+pub enum ContractEvent<'a> {
+    Deposit(DepositData<'a>),
+    TransferRefunded(TransferRefundedData<'a>),
+    BetCommitted(BetCommittedData<'a>),
+    BetExpired(BetExpiredData<'a>),
+    Bet(BetData<'a>),
+    StreakOpened(StreakData<'a>),
+    StreakPressed(StreakData<'a>),
+    StreakLost(StreakData<'a>),
+    StreakCollected(StreakData<'a>),
+    ChallengeOpened(ChallengeData<'a>),
+    ChallengeCancelled(ChallengeData<'a>),
+    ChallengeSettled(&'a ChallengeReceipt),
+    RaffleOpened(RaffleData<'a>),
+    RaffleTicketsBought(RaffleTicketsData<'a>),
+    RaffleClosed(RaffleData<'a>),
+    RaffleDrawn(RaffleData<'a>),
+    TournamentCreated(TournamentData<'a>),
+    TournamentFunded(TournamentFundedData<'a>),
+    TournamentSettled(TournamentData<'a>),
+    HouseWithdrawalQueued(HouseWithdrawalData<'a>),
+    HouseWithdrawalCancelled(HouseWithdrawalData<'a>),
+    HouseWithdrawalExecuted(HouseWithdrawalData<'a>),
+    Withdrawal(WithdrawalData<'a>),
+    WithdrawalFailed(WithdrawalData<'a>),
+    GameCreated(GameData<'a>),
+    GameAltered(GameData<'a>),
+    GameBlocked(GameBlockedData<'a>),
+    GameUnblocked(GameUnblockedData<'a>),
+    HouseFunded(HouseFundedData<'a>),
+    LiquidityProvided(LiquidityData<'a>),
+    LiquidityWithdrawn(LiquidityData<'a>),
+    HouseSharesTransfer(HouseSharesTransferData<'a>),
+    HouseSharesBurn(HouseSharesBurnData<'a>),
+    FeesAccrued(FeesAccruedData<'a>),
+    TokenRegistered(TokenData<'a>),
+    TokenRemoved(TokenData<'a>),
+    PanicToggle(PanicToggleData<'a>),
+    OwnerUpdated(OwnerUpdatedData<'a>),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: ContractEvent<'a>,
+}
+
+impl ContractEvent<'_> {
+    pub fn emit(self) {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_VERSION,
+            event: self,
+        };
+        env::log(format!("EVENT_JSON:{}", serde_json::to_string(&log).unwrap()).as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use near_sdk::serde_json::{json, Value};
+    use near_sdk::test_utils::get_logs;
+
+    /// emit
+    /// method must:
+    /// 1. Prefix log with EVENT_JSON:
+    /// 2. Log standard, version, event name and data
+    #[test]
+    fn test_emit() {
+        let context = get_context(vec![], false, 0, 0, SIGNER_ACCOUNT.to_string());
+        testing_env!(context);
+
+        let account_id = SIGNER_ACCOUNT.to_string();
+        let token_contract = "token.testnet".to_string();
+        ContractEvent::Deposit(DepositData {
+            account_id: &account_id,
+            token_contract: &token_contract,
+            amount: U128(100),
+        })
+        .emit();
+
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        // 1. Prefix log with EVENT_JSON:
+        assert!(logs[0].starts_with("EVENT_JSON:"));
+        // 2. Log standard, version, event name and data
+        let log: Value = serde_json::from_str(&logs[0]["EVENT_JSON:".len()..]).unwrap();
+        assert_eq!(
+            log,
+            json!({
+                "standard": EVENT_STANDARD,
+                "version": EVENT_VERSION,
+                "event": "deposit",
+                "data": {
+                    "account_id": account_id,
+                    "token_contract": token_contract,
+                    "amount": "100",
+                },
+            })
+        );
+    }
+}
@@ -2,10 +2,16 @@ use crate::*;
 
 pub const FT_TRANSFER_GAS: u64 = 50_000_000_000_000;
 pub const TRANSFER_CALLBACK_GAS: u64 = 50_000_000_000_000;
+pub const FT_METADATA_GAS: u64 = 10_000_000_000_000;
+pub const METADATA_CALLBACK_GAS: u64 = 20_000_000_000_000;
+pub const HOUSE_FT_RESOLVE_GAS: u64 = 20_000_000_000_000;
+// gas kept back from ft_on_transfer for house_ft_transfer_call itself and its resolve callback
+pub const HOUSE_FT_TRANSFER_CALL_GAS: u64 = 25_000_000_000_000 + HOUSE_FT_RESOLVE_GAS;
 
 #[ext_contract(ext_ft)]
 pub trait FunglibleToken {
     fn ft_transfer(receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn ft_metadata();
 }
 
 #[ext_contract(ext_self)]
@@ -15,6 +21,8 @@ pub trait Callbacks {
     fn project_transfer_callback(amount: U128, project_id: String);
     fn house_funds_transfer_callback(amount: U128, project_id: String);
     fn user_transfer_callback(token_contract: AccountId, amount: U128, user_account_id: AccountId);
+    fn token_metadata_callback(token_contract: AccountId);
+    fn house_ft_resolve_transfer(game_id: GameId, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128;
 }
 
 pub fn transfer_token(token_contract: AccountId, receiver: AccountId, amount: u128) -> Promise {
@@ -29,6 +37,17 @@ pub fn transfer_token(token_contract: AccountId, receiver: AccountId, amount: u1
 }
 
 impl Contract {
+    pub fn fetch_token_metadata(&self, token_contract: AccountId) -> Promise {
+        ext_ft::ft_metadata(&token_contract, 0, FT_METADATA_GAS).then(
+            ext_self::token_metadata_callback(
+                token_contract,
+                &env::current_account_id(),
+                0,
+                METADATA_CALLBACK_GAS,
+            ),
+        )
+    }
+
     pub fn safe_transfer_owner(&self, token_contract: AccountId, amount: u128) -> Promise {
         transfer_token(token_contract.clone(), self.owner_id.clone(), amount).then(
             ext_self::owner_transfer_callback(
@@ -43,7 +62,7 @@ impl Contract {
 
     pub fn safe_transfer_nft(&self, token_contract: AccountId, amount: u128) -> Promise {
         transfer_token(token_contract.clone(), self.nft_account.clone(), amount).then(
-            ext_self::owner_transfer_callback(
+            ext_self::nft_transfer_callback(
                 token_contract,
                 U128(amount),
                 &env::current_account_id(),
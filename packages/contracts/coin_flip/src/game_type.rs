@@ -0,0 +1,179 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+
+use crate::{ERR_209, ERR_210, ERR_211, FRACTIONAL_BASE};
+
+pub const MAX_WHEEL_SEGMENTS: usize = 64;
+// number of rolls crash points are drawn from
+pub const CRASH_ROLL_RANGE: u128 = 1_000_000_000;
+
+/// Slice of a wheel payout table. It is drawn with probability weight / total weight
+/// and pays net_bet * multiplier / FRACTIONAL_BASE
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WheelSegment {
+    pub weight: u32,
+    #[serde(with = "crate::string")]
+    pub multiplier: u128, // base 10e-5
+}
+
+/// How the bets of a game are settled
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde", tag = "kind", rename_all = "snake_case")]
+pub enum GameType {
+    // bet wins odds out of odds_precision rolls and is paid the inverse of its odds,
+    // the original coin flip is a dice game with odds_precision 256
+    #[default]
+    Dice,
+    // roll picks a weighted segment of the payout table, odds are ignored
+    Wheel { segments: Vec<WheelSegment> },
+    // roll draws a crash point, bets win target_multiplier if the crash point reaches it.
+    // max_multiplier caps the targets players can choose, base 10e-5
+    Crash {
+        #[serde(with = "crate::string")]
+        max_multiplier: u128,
+    },
+}
+
+impl GameType {
+    /// Checks the payout table is drawable and that its expected value does not
+    /// exceed bet_payment_adjustment, the same edge dice games are bound to
+    pub fn validate(&self, bet_payment_adjustment: u128) -> Result<(), &'static str> {
+        match self {
+            GameType::Dice => Ok(()),
+            GameType::Wheel { segments } => {
+                if segments.is_empty()
+                    || segments.len() > MAX_WHEEL_SEGMENTS
+                    || segments.iter().any(|segment| segment.weight == 0)
+                {
+                    return Err(ERR_210);
+                }
+                let weighted_payout: u128 = segments
+                    .iter()
+                    .map(|segment| segment.weight as u128 * segment.multiplier)
+                    .sum();
+                if weighted_payout > bet_payment_adjustment * GameType::table_weight(segments) {
+                    return Err(ERR_209);
+                }
+                Ok(())
+            }
+            // crash points are drawn so that every target returns bet_payment_adjustment on average
+            GameType::Crash { max_multiplier } => {
+                if *max_multiplier <= FRACTIONAL_BASE {
+                    return Err(ERR_211);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Multiplier a crash roll stops at, base 10e-5. The chance of reaching a target t
+    /// is bet_payment_adjustment / t, so paying t on reaching it has the same edge as dice
+    pub fn crash_point(bet_payment_adjustment: u128, roll: u128) -> u128 {
+        (bet_payment_adjustment * CRASH_ROLL_RANGE) / (CRASH_ROLL_RANGE - roll)
+    }
+
+    /// Sum of the weights of a payout table, rolls are drawn in [0, table_weight)
+    pub fn table_weight(segments: &[WheelSegment]) -> u128 {
+        segments.iter().map(|segment| segment.weight as u128).sum()
+    }
+
+    /// Index of the wheel segment a roll lands on
+    pub fn segment_index(segments: &[WheelSegment], roll: u128) -> usize {
+        let mut upper_bound = 0;
+        for (index, segment) in segments.iter().enumerate() {
+            upper_bound += segment.weight as u128;
+            if roll < upper_bound {
+                return index;
+            }
+        }
+        panic!("roll out of the payout table range");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(weight: u32, multiplier: u128) -> WheelSegment {
+        WheelSegment { weight, multiplier }
+    }
+
+    /// validate
+    /// method must:
+    /// 1. Accept every dice game
+    /// 2. Reject empty tables, tables with too many segments or zero weights
+    /// 3. Reject tables paying more than bet_payment_adjustment on average
+    #[test]
+    fn test_validate() {
+        let adjustment = FRACTIONAL_BASE * 95 / 100;
+        // 0x, 0.5x, 2x and 10x segments with expected value 0.95x
+        let wheel = GameType::Wheel {
+            segments: vec![
+                segment(50, 0),
+                segment(30, FRACTIONAL_BASE / 2),
+                segment(15, 2 * FRACTIONAL_BASE),
+                segment(5, 10 * FRACTIONAL_BASE),
+            ],
+        };
+
+        // 1. Accept every dice game
+        assert_eq!(GameType::Dice.validate(0), Ok(()));
+
+        // 2. Reject empty tables, tables with too many segments or zero weights
+        assert_eq!(GameType::Wheel { segments: vec![] }.validate(adjustment), Err(ERR_210));
+        assert_eq!(
+            GameType::Wheel { segments: vec![segment(1, 0); MAX_WHEEL_SEGMENTS + 1] }.validate(adjustment),
+            Err(ERR_210)
+        );
+        assert_eq!(
+            GameType::Wheel { segments: vec![segment(1, 0), segment(0, FRACTIONAL_BASE)] }.validate(adjustment),
+            Err(ERR_210)
+        );
+
+        // 3. Reject tables paying more than bet_payment_adjustment on average
+        assert_eq!(wheel.validate(adjustment), Ok(()));
+        assert_eq!(wheel.validate(FRACTIONAL_BASE), Ok(()));
+        assert_eq!(wheel.validate(FRACTIONAL_BASE * 94 / 100), Err(ERR_209));
+    }
+
+    /// segment_index
+    /// method must:
+    /// 1. Map every roll in [0, total_weight) to the segment covering it
+    #[test]
+    fn test_segment_index() {
+        let segments = vec![segment(2, 0), segment(1, 0), segment(3, 0)];
+        let expected = [0, 0, 1, 2, 2, 2];
+        for (roll, index) in expected.iter().enumerate() {
+            assert_eq!(GameType::segment_index(&segments, roll as u128), *index);
+        }
+        assert_eq!(GameType::table_weight(&segments), 6);
+    }
+
+    /// crash_point
+    /// method must:
+    /// 1. Start at bet_payment_adjustment and grow with the roll
+    /// 2. Reach a target with bet_payment_adjustment / target probability
+    #[test]
+    fn test_crash_point() {
+        let adjustment = FRACTIONAL_BASE * 99 / 100;
+
+        // 1. Start at bet_payment_adjustment and grow with the roll
+        assert_eq!(GameType::crash_point(adjustment, 0), adjustment);
+        assert!(GameType::crash_point(adjustment, CRASH_ROLL_RANGE / 2) > GameType::crash_point(adjustment, 10));
+        assert_eq!(GameType::crash_point(adjustment, CRASH_ROLL_RANGE - 1), adjustment * CRASH_ROLL_RANGE);
+
+        // 2. Reach a target with bet_payment_adjustment / target probability
+        for target in [2 * FRACTIONAL_BASE, 10 * FRACTIONAL_BASE, 1000 * FRACTIONAL_BASE].iter() {
+            // first roll whose crash point reaches the target
+            let threshold = CRASH_ROLL_RANGE - (adjustment * CRASH_ROLL_RANGE) / target;
+            assert!(GameType::crash_point(adjustment, threshold) >= *target);
+            assert!(GameType::crash_point(adjustment, threshold - 1) < *target);
+            let winning_rolls = CRASH_ROLL_RANGE - threshold;
+            assert_eq!(winning_rolls, (adjustment * CRASH_ROLL_RANGE) / target);
+        }
+
+        assert_eq!(GameType::Crash { max_multiplier: FRACTIONAL_BASE }.validate(adjustment), Err(ERR_211));
+        assert_eq!(GameType::Crash { max_multiplier: 2 * FRACTIONAL_BASE }.validate(adjustment), Ok(()));
+    }
+}
@@ -0,0 +1,22 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::AccountId;
+
+// queued withdrawals a liquidity provider, or the partner, can have at once in a game
+pub const MAX_QUEUED_WITHDRAWALS: usize = 5;
+
+/// House funds a partner or a liquidity provider asked to withdraw. They leave house_funds
+/// as soon as they are queued and are paid once unlocks_at has passed
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HouseWithdrawal {
+    #[serde(with = "crate::string")]
+    pub withdrawal_id: u128,
+    // liquidity provider whose shares were burned for it, None when the partner withdrew house funds
+    pub provider: Option<AccountId>,
+    #[serde(with = "crate::string")]
+    pub amount: u128,
+    // block timestamp in nanoseconds
+    #[serde(with = "crate::string")]
+    pub unlocks_at: u64,
+}
@@ -1,10 +1,12 @@
-use std::convert::{TryFrom, TryInto};
+#![allow(clippy::too_many_arguments)]
+
+use std::convert::TryFrom;
 
 pub use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::{LookupMap, UnorderedMap},
+    collections::{LookupMap, UnorderedMap, UnorderedSet},
     env, ext_contract,
-    json_types::{ValidAccountId, U128, U64},
+    json_types::{Base64VecU8, ValidAccountId, I128, U128, U64},
     near_bindgen,
     serde::{Serialize, Deserialize},
     serde_json::{self, json},
@@ -13,26 +15,91 @@ pub use near_sdk::{
 };
 
 pub use crate::account::Account;
+pub use crate::auto_play::{AutoPlayReport, AutoPlayStop, AutoPlayStrategy, StakingPlan};
+pub use crate::challenge::{Challenge, ChallengeReceipt};
+pub use crate::bet::{BatchBet, BetChoice, BetReceipt, BetVerification, FeeCuts, GameParameters, RollDirection};
+pub use crate::drawdown::{Drawdown, DrawdownBreach, DrawdownLimits, LossBucket};
 pub use crate::errors::*;
-pub use crate::partnered_game::PartneredGame;
+pub use crate::game_type::{GameType, WheelSegment, CRASH_ROLL_RANGE};
+pub use crate::house_withdrawal::HouseWithdrawal;
+pub use crate::partnered_game::{GameId, PartneredGame};
+pub use crate::pending_bet::PendingBet;
+pub use crate::raffle::{RaffleRound, RaffleWinner};
+pub use crate::streak::Streak;
+pub use crate::token::TokenInfo;
+pub use crate::tournament::{LeaderboardEntry, Tournament, TournamentScoring};
 
 mod account;
 mod actions;
+mod auto_play;
+mod bet;
+mod challenge;
+mod drawdown;
 mod errors;
+mod events;
 mod ext_interface;
+mod game_type;
+mod house_withdrawal;
+mod math;
 mod partnered_game;
+mod pending_bet;
+mod raffle;
+mod random;
+mod streak;
+mod token;
+mod tournament;
 
 pub const FRACTIONAL_BASE: u128 = 100_000;
+// number of roll outcomes of the original coin flip, used when a game sets no precision
+pub const DEFAULT_ODDS_PRECISION: u32 = 256;
+// finest odds precision a game can use, 1/10,000
+pub const MAX_ODDS_PRECISION: u32 = 10_000;
+// minimum number of blocks between commit_bet and reveal_bet
+pub const REVEAL_DELAY_BLOCKS: u64 = 3;
+// blocks after commit_bet from which a bet can no longer be revealed, only expired as a loss
+pub const REVEAL_DEADLINE_BLOCKS: u64 = 1_000;
+// one in DEFAULT_JACKPOT_TRIGGER bets wins the jackpot of a game created without a trigger
+pub const DEFAULT_JACKPOT_TRIGGER: u32 = 100_000;
+// wins in a row a streak can reach on a game created without a max_streak
+pub const DEFAULT_MAX_STREAK: u32 = 5;
+// nanoseconds house funds withdrawals stay queued on a game created without a withdrawal_delay, 1 day
+pub const DEFAULT_WITHDRAWAL_DELAY: u64 = 86_400_000_000_000;
+// longest withdrawal_delay a game can be given, 30 days
+pub const MAX_WITHDRAWAL_DELAY: u64 = 30 * DEFAULT_WITHDRAWAL_DELAY;
+// maximum number of bets settled by a single play_batch call, every bet emits a bet event
+// and all of them, with the fees event of the batch, must fit the 16kb log limit of a receipt
+pub const MAX_BATCH_BETS: usize = 20;
 
 #[derive(BorshSerialize, BorshStorageKey)]
 pub enum StorageKey {
     Accounts,
     PartneredGames,
     AccountBalances { account_id: AccountId },
+    AccountStreaks { account_id: AccountId },
+    AccountHouseShares { account_id: AccountId },
+    AccountHouseWithdrawals { account_id: AccountId },
     OwnerFunds,
     NftFunds,
+    PendingBets,
+    Bets,
+    Tokens,
+    GamesByPartner,
+    PartnerGames { account_id: AccountId },
+    GamesByToken,
+    TokenGames { account_id: AccountId },
+    Challenges,
+    Raffles,
+    OpenRaffles,
+    RaffleTickets { round_id: u128 },
+    RaffleTicketCounts { round_id: u128 },
+    Tournaments,
+    GameTournaments,
+    TournamentScores { tournament_id: u128 },
+    HouseWithdrawals,
 }
 
+// the layout of games and PartneredGame changed without a migrate method, state of earlier
+// deployments does not deserialize, so upgrades must redeploy to a fresh account and call new
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault, Serialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -41,16 +108,49 @@ pub struct Contract {
     pub nft_account: AccountId,
     pub panic_button: bool,
 
+    // counts every bet placed, also used as the bet nonce
     pub game_count: u128,
+    // id given to the next game created without a custom slug
+    pub next_game_id: u64,
+    // id given to the next challenge, raffle round, tournament or house withdrawal
+    pub next_id: u128,
 
     #[serde(skip)]
     pub accounts: LookupMap<AccountId, Account>,
     #[serde(skip)]
-    pub games: LookupMap<String, PartneredGame>,
+    pub games: UnorderedMap<GameId, PartneredGame>,
+    #[serde(skip)]
+    pub games_by_partner: LookupMap<AccountId, UnorderedSet<GameId>>,
+    #[serde(skip)]
+    pub games_by_token: LookupMap<AccountId, UnorderedSet<GameId>>,
     #[serde(skip)]
     pub nft_balance: UnorderedMap<AccountId, u128>,
     #[serde(skip)]
     pub owner_balance: UnorderedMap<AccountId, u128>,
+    #[serde(skip)]
+    pub pending_bets: LookupMap<u128, PendingBet>,
+    #[serde(skip)]
+    pub bets: LookupMap<u128, BetReceipt>,
+    #[serde(skip)]
+    pub tokens: UnorderedMap<AccountId, TokenInfo>,
+    // open pvp challenges, keyed by an id taken from next_id
+    #[serde(skip)]
+    pub challenges: UnorderedMap<u128, Challenge>,
+    // raffle rounds, keyed by an id taken from next_id
+    #[serde(skip)]
+    pub raffles: LookupMap<u128, RaffleRound>,
+    #[serde(skip)]
+    pub open_raffles: UnorderedSet<u128>,
+    // tournaments, keyed by an id taken from next_id
+    #[serde(skip)]
+    pub tournaments: LookupMap<u128, Tournament>,
+    // ids of the unsettled tournaments of each game, scored on every play
+    #[serde(skip)]
+    pub game_tournaments: LookupMap<GameId, Vec<u128>>,
+    // house funds withdrawals of each game's partner waiting for the game's withdrawal_delay,
+    // liquidity withdrawals are kept in the account of their provider
+    #[serde(skip)]
+    pub house_withdrawals: LookupMap<GameId, Vec<HouseWithdrawal>>,
 }
 
 #[near_bindgen]
@@ -74,9 +174,22 @@ impl Contract {
             owner_balance: UnorderedMap::new(StorageKey::OwnerFunds),
 
             game_count: 0,
+            next_game_id: 0,
+            next_id: 0,
 
             accounts: LookupMap::new(StorageKey::Accounts),
-            games: LookupMap::new(StorageKey::PartneredGames),
+            games: UnorderedMap::new(StorageKey::PartneredGames),
+            games_by_partner: LookupMap::new(StorageKey::GamesByPartner),
+            games_by_token: LookupMap::new(StorageKey::GamesByToken),
+            pending_bets: LookupMap::new(StorageKey::PendingBets),
+            bets: LookupMap::new(StorageKey::Bets),
+            tokens: UnorderedMap::new(StorageKey::Tokens),
+            challenges: UnorderedMap::new(StorageKey::Challenges),
+            raffles: LookupMap::new(StorageKey::Raffles),
+            open_raffles: UnorderedSet::new(StorageKey::OpenRaffles),
+            tournaments: LookupMap::new(StorageKey::Tournaments),
+            game_tournaments: LookupMap::new(StorageKey::GameTournaments),
+            house_withdrawals: LookupMap::new(StorageKey::HouseWithdrawals),
         };
         let contract_address = env::current_account_id();
         let mut contract_account_entry = Account::new(&contract_address, env::account_balance());
@@ -121,7 +234,7 @@ impl Contract {
         account_id: &AccountId,
         amount: u128,
     ) -> u128 {
-        let mut account = self.internal_get_account(&account_id).expect(ERR_001);
+        let mut account = self.internal_get_account(account_id).expect(ERR_001);
         let available = account.storage_funds_available();
         assert!(
             available > 0,
@@ -146,13 +259,52 @@ impl Contract {
 
 // partnered_game related methods
 impl Contract {
-    pub fn internal_get_game(&self, code: &String) -> Option<PartneredGame> {
+    pub fn internal_get_game(&self, code: &GameId) -> Option<PartneredGame> {
         self.games.get(code)
     }
 
-    pub fn internal_update_game(&mut self, code: &String, game: &PartneredGame) {
+    pub fn internal_update_game(&mut self, code: &GameId, game: &PartneredGame) {
         self.games.insert(code, game);
     }
+
+    // registers a new game and adds it to the partner and token indexes
+    pub fn internal_insert_game(&mut self, code: &GameId, game: &PartneredGame) {
+        self.games.insert(code, game);
+        let mut partner_games = self.games_by_partner.get(&game.partner_owner).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::PartnerGames {
+                account_id: game.partner_owner.clone(),
+            })
+        });
+        partner_games.insert(code);
+        self.games_by_partner.insert(&game.partner_owner, &partner_games);
+
+        let mut token_games = self.games_by_token.get(&game.partner_token).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::TokenGames {
+                account_id: game.partner_token.clone(),
+            })
+        });
+        token_games.insert(code);
+        self.games_by_token.insert(&game.partner_token, &token_games);
+    }
+
+    // moves a game between partners in the partner index
+    pub fn internal_transfer_game(&mut self, code: &GameId, old_partner: &AccountId, new_partner: &AccountId) {
+        if let Some(mut partner_games) = self.games_by_partner.get(old_partner) {
+            partner_games.remove(code);
+            if partner_games.is_empty() {
+                self.games_by_partner.remove(old_partner);
+            } else {
+                self.games_by_partner.insert(old_partner, &partner_games);
+            }
+        }
+        let mut partner_games = self.games_by_partner.get(new_partner).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::PartnerGames {
+                account_id: new_partner.clone(),
+            })
+        });
+        partner_games.insert(code);
+        self.games_by_partner.insert(new_partner, &partner_games);
+    }
 }
 
 // helper methods
@@ -200,7 +352,7 @@ mod string {
 mod tests {
     use super::*;
     pub use near_sdk::MockedBlockchain;
-    pub use near_sdk::{testing_env, VMContext, VMConfig, RuntimeFeesConfig};
+    pub use near_sdk::{testing_env, VMContext};
     pub use std::panic::{UnwindSafe, catch_unwind};
 
     pub const CONTRACT_ACCOUNT: &str = "contract.testnet";
@@ -263,6 +415,17 @@ mod tests {
 		let hash2 = env::keccak256(&hash1[..]);
 		let hash3 = env::keccak256(&hash2[..]);
         let hash4 = env::keccak256(&hash3[..]);
+        let hash5 = env::keccak256(&hash4[..]);
+        let hash6 = env::keccak256(&hash5[..]);
+        let hash7 = env::keccak256(&hash6[..]);
+        let hash8 = env::keccak256(&hash7[..]);
+        let hash9 = env::keccak256(&hash8[..]);
+        let hash10 = env::keccak256(&hash9[..]);
+        let hash11 = env::keccak256(&hash10[..]);
+        let hash12 = env::keccak256(&hash11[..]);
+        let hash13 = env::keccak256(&hash12[..]);
+        let hash14 = env::keccak256(&hash13[..]);
+        let hash15 = env::keccak256(&hash14[..]);
         Contract {
             owner_id: OWNER_ACCOUNT.to_string(),
             nft_account: NFT_ACCOUNT.to_string(),
@@ -270,12 +433,77 @@ mod tests {
             nft_balance: UnorderedMap::new(hash1),
             owner_balance: UnorderedMap::new(hash2),
             game_count: 0,
+            next_game_id: 0,
+            next_id: 0,
 
             accounts: LookupMap::new(hash3),
-            games: LookupMap::new(hash4),
+            games: UnorderedMap::new(hash4),
+            games_by_partner: LookupMap::new(hash8),
+            games_by_token: LookupMap::new(hash9),
+            pending_bets: LookupMap::new(hash5),
+            bets: LookupMap::new(hash6),
+            tokens: UnorderedMap::new(hash7),
+            challenges: UnorderedMap::new(hash10),
+            raffles: LookupMap::new(hash11),
+            open_raffles: UnorderedSet::new(hash12),
+            tournaments: LookupMap::new(hash13),
+            game_tournaments: LookupMap::new(hash14),
+            house_withdrawals: LookupMap::new(hash15),
         }
     }
 
+    pub fn register_sample_token(contract: &mut Contract, token_contract: &AccountId) {
+        contract.tokens.insert(
+            token_contract,
+            &TokenInfo {
+                symbol: "TKN".to_string(),
+                decimals: 24,
+                icon: None,
+            },
+        );
+    }
+
+    /// Fee-less dice game of token without house funds. Tests override only the fields
+    /// they care about, e.g. `PartneredGame { house_funds, ..test_game(&token) }`
+    pub fn test_game(token: &str) -> PartneredGame {
+        PartneredGame {
+            partner_owner: "anyone".to_string(),
+            nft_contract: NFT_ACCOUNT.to_string(),
+            blocked: false,
+            breaker: None,
+            house_funds: 0,
+            reserved_funds: 0,
+            partner_token: token.to_string(),
+            partner_fee: 0,
+            partner_balance: 0,
+            bet_payment_adjustment: FRACTIONAL_BASE,
+            house_fee: 0,
+            max_bet: 100,
+            min_bet: 10,
+            game_type: GameType::Dice,
+            odds_precision: DEFAULT_ODDS_PRECISION,
+            max_odds: 200,
+            min_odds: 100,
+            nft_fee: 0,
+            owner_fee: 0,
+            jackpot_fee: 0,
+            jackpot_trigger: DEFAULT_JACKPOT_TRIGGER,
+            jackpot_balance: 0,
+            max_streak: DEFAULT_MAX_STREAK,
+            withdrawal_delay: DEFAULT_WITHDRAWAL_DELAY,
+            total_shares: 0,
+            partner_shares: 0,
+            max_exposure: FRACTIONAL_BASE,
+            drawdown_limits: DrawdownLimits::default(),
+            drawdown: Drawdown::default(),
+        }
+    }
+
+    /// Registers a game in the game indexes the way create_new_partner does, skipping its validations
+    pub fn insert_test_game(contract: &mut Contract, game_id: &str, game: &PartneredGame) {
+        contract.internal_insert_game(&game_id.to_string(), game);
+    }
+
     #[test]
     fn test_constructor() {
         // set up the mock context into the testing environment
@@ -0,0 +1,83 @@
+/// a * b / c rounded down. The product is kept in 256 bits so that share prices
+/// of large bankrolls don't overflow, panics if the result doesn't fit a u128
+pub fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    let (hi, lo) = full_mul(a, b);
+    div_rem(hi, lo, c).0
+}
+
+/// a * b / c rounded up
+pub fn mul_div_ceil(a: u128, b: u128, c: u128) -> u128 {
+    let (hi, lo) = full_mul(a, b);
+    let (quotient, remainder) = div_rem(hi, lo, c);
+    if remainder > 0 {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+/// 256 bit product of a and b as (high, low) halves, built from 64 bit limbs
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    let mask = u64::MAX as u128;
+    let (a_lo, a_hi) = (a & mask, a >> 64);
+    let (b_lo, b_hi) = (b & mask, b >> 64);
+
+    let lo_lo = a_lo * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_lo = a_hi * b_lo;
+    let hi_hi = a_hi * b_hi;
+
+    // cross products overlap the upper limb of lo_lo, carries spill into the high half
+    let middle = (lo_lo >> 64) + (lo_hi & mask) + (hi_lo & mask);
+    let lo = (lo_lo & mask) | ((middle & mask) << 64);
+    let hi = hi_hi + (lo_hi >> 64) + (hi_lo >> 64) + (middle >> 64);
+    (hi, lo)
+}
+
+/// Long division of a 256 bit number by divisor, returns (quotient, remainder)
+fn div_rem(hi: u128, lo: u128, divisor: u128) -> (u128, u128) {
+    assert!(divisor > 0, "mul_div division by zero");
+    assert!(hi < divisor, "mul_div result overflows u128");
+    let mut remainder = hi;
+    let mut quotient = 0;
+    for bit in (0..128).rev() {
+        // the remainder can take a 129th bit before subtracting
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// mul_div
+    /// method must:
+    /// 1. Match plain arithmetic when the product fits a u128
+    /// 2. Handle products wider than a u128
+    /// 3. Round down, or up for mul_div_ceil
+    #[test]
+    fn test_mul_div() {
+        // 1. Match plain arithmetic when the product fits a u128
+        assert_eq!(mul_div(1_000, 300, 1_200), 250);
+        assert_eq!(mul_div(0, 300, 7), 0);
+        assert_eq!(mul_div(u64::MAX as u128, u64::MAX as u128, 3), (u64::MAX as u128 * u64::MAX as u128) / 3);
+
+        // 2. Handle products wider than a u128
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), u128::MAX);
+        assert_eq!(mul_div(u128::MAX, 10u128.pow(24), 10u128.pow(25)), u128::MAX / 10);
+        assert_eq!(mul_div(10u128.pow(30), 10u128.pow(30), 10u128.pow(32)), 10u128.pow(28));
+
+        // 3. Round down, or up for mul_div_ceil
+        assert_eq!(mul_div(10, 10, 3), 33);
+        assert_eq!(mul_div_ceil(10, 10, 3), 34);
+        assert_eq!(mul_div_ceil(10, 9, 3), 30);
+        assert_eq!(mul_div_ceil(u128::MAX, 2, 4), u128::MAX / 2 + 1);
+    }
+}
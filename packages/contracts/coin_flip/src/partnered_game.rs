@@ -2,13 +2,34 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Serialize, Deserialize};
 use near_sdk::AccountId;
 
+use crate::math::{mul_div, mul_div_ceil};
+use crate::random::jackpot_triggered;
+use crate::{
+    BetChoice, Drawdown, DrawdownBreach, DrawdownLimits, FeeCuts, GameParameters, GameType, ERR_407, ERR_417, ERR_902, ERR_903, ERR_904, FRACTIONAL_BASE,
+    MAX_ODDS_PRECISION,
+};
+
+// games are either given a custom slug by the owner or an auto incremented numeric id
+pub type GameId = String;
+
+pub const MAX_GAME_ID_LENGTH: usize = 64;
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 #[serde( crate = "near_sdk::serde")]
 pub struct PartneredGame {
     pub partner_owner: AccountId,
+    // NFT collection the game belongs to, one collection can run several games. Informational
+    // only, the nft_fee of every game is credited to the contract wide nft_account
+    pub nft_contract: AccountId,
+    // set by the owner through alter_partner, only the owner can lift it
     pub blocked: bool,
+    // limit that tripped the circuit breaker, lifted by the partner or the owner
+    pub breaker: Option<DrawdownBreach>,
     #[serde(with = "crate::string")]
     pub house_funds: u128,
+    // house funds locked to cover pending commit-reveal bets
+    #[serde(with = "crate::string")]
+    pub reserved_funds: u128,
     pub partner_token: AccountId,
     #[serde(with = "crate::string")]
     pub partner_fee: u128, // base 10e-5
@@ -22,10 +43,312 @@ pub struct PartneredGame {
     pub max_bet: u128,
     #[serde(with = "crate::string")]
     pub min_bet: u128,
-    pub max_odds: u8,
-    pub min_odds: u8,
+    pub game_type: GameType,
+    // odds are counted in winning roll outcomes out of odds_precision
+    pub odds_precision: u32,
+    pub max_odds: u32,
+    pub min_odds: u32,
     #[serde(with = "crate::string")]
     pub nft_fee: u128,   // base 10e-5
     #[serde(with = "crate::string")]
     pub owner_fee: u128, // base 10e-5
+    #[serde(with = "crate::string")]
+    pub jackpot_fee: u128, // base 10e-5
+    // one in jackpot_trigger bets wins the whole jackpot
+    pub jackpot_trigger: u32,
+    // pool grown by jackpot fees, kept apart from house_funds and never used to cover bets
+    #[serde(with = "crate::string")]
+    pub jackpot_balance: u128,
+    // wins in a row a streak can reach before it must be collected
+    pub max_streak: u32,
+    // nanoseconds a house funds withdrawal stays queued before it can be executed
+    #[serde(with = "crate::string")]
+    pub withdrawal_delay: u64,
+    // house shares issued over the bankroll, including the ones held by the partner
+    #[serde(with = "crate::string")]
+    pub total_shares: u128,
+    #[serde(with = "crate::string")]
+    pub partner_shares: u128,
+    // largest fraction of house_funds the possible prize of a single bet may put at risk
+    #[serde(with = "crate::string")]
+    pub max_exposure: u128, // base 10e-5
+    // house losses that trip the circuit breaker, blocking the game
+    pub drawdown_limits: DrawdownLimits,
+    pub drawdown: Drawdown,
+}
+
+impl PartneredGame {
+    /// Custom game ids must be short lowercase slugs and cannot be purely numeric,
+    /// numeric ids are reserved for auto incremented games
+    pub fn validate_game_id(game_id: &str) -> Result<(), &'static str> {
+        let valid_chars = game_id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if game_id.is_empty() || game_id.len() > MAX_GAME_ID_LENGTH || !valid_chars {
+            return Err(crate::ERR_302);
+        }
+        if game_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(crate::ERR_303);
+        }
+        Ok(())
+    }
+
+    /// Checks that odds_precision and the odds limits allow every bet inside them
+    pub fn validate_odds(odds_precision: u32, min_odds: u32, max_odds: u32) -> Result<(), &'static str> {
+        if !(2..=MAX_ODDS_PRECISION).contains(&odds_precision) {
+            return Err(crate::ERR_207);
+        }
+        if min_odds == 0 || max_odds >= odds_precision {
+            return Err(crate::ERR_208);
+        }
+        Ok(())
+    }
+
+    /// Returns the largest prize a bet of net_bet with the given choice can be paid
+    pub fn max_payout(&self, net_bet: u128, choice: &BetChoice) -> u128 {
+        GameParameters::from(self).max_payout(net_bet, choice)
+    }
+
+    /// Largest prize a single bet may put at risk, max_exposure of the current house_funds
+    pub fn exposure_limit(&self) -> u128 {
+        mul_div(self.house_funds, self.max_exposure, FRACTIONAL_BASE)
+    }
+
+    /// Checks a bet before it is rolled. Returns ERR_407 if the house could not pay its
+    /// largest prize, counting the house fee the bet itself adds to house_funds, and
+    /// ERR_417 if that prize goes over the game's exposure_limit
+    pub fn check_cover(&self, bet_size: u128, choice: &BetChoice) -> Result<(), &'static str> {
+        let fees = FeeCuts::new(bet_size, &GameParameters::from(self));
+        let max_payout = self.max_payout(bet_size - fees.total(), choice);
+        if self.house_funds + fees.house_cut < max_payout {
+            return Err(ERR_407);
+        }
+        if max_payout > self.exposure_limit() {
+            return Err(ERR_417);
+        }
+        Ok(())
+    }
+
+    /// Largest bet up to max_bet that passes check_cover for the given choice, 0 if
+    /// even the smallest one does not. Prizes grow with the bet size, so the limit is
+    /// found by bisection over the fee and payout rounding of the real settlement
+    pub fn effective_max_bet(&self, choice: &BetChoice) -> u128 {
+        let (mut low, mut high) = (0, self.max_bet);
+        while low < high {
+            let middle = low + (high - low).div_ceil(2);
+            if self.check_cover(middle, choice).is_ok() {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+        low
+    }
+
+    /// Counts what the house gained and paid on a settled bet against drawdown_limits,
+    /// blocking the game when one is breached. Returns the breach only when it blocks the game
+    pub fn record_house_result(&mut self, gain: u128, loss: u128, timestamp: u64, block_index: u64) -> Option<DrawdownBreach> {
+        let breach = self.drawdown.record(&self.drawdown_limits, gain, loss, timestamp, block_index);
+        if breach.is_none() || self.is_blocked() {
+            return None;
+        }
+        self.breaker = breach;
+        breach
+    }
+
+    /// Whether bets are suspended, by the owner or by the circuit breaker
+    pub fn is_blocked(&self) -> bool {
+        self.blocked || self.breaker.is_some()
+    }
+
+    /// Rolls the jackpot trigger of a bet, emptying the whole pool into the prize
+    /// when it hits. Returns whether it hit and the prize
+    pub fn roll_jackpot(
+        &mut self,
+        seed: &[u8],
+        account_id: &AccountId,
+        bet_id: u128,
+        game_code: &str,
+        jackpot_trigger: u32,
+    ) -> (bool, u128) {
+        if jackpot_triggered(seed, account_id, bet_id, game_code, jackpot_trigger) {
+            let prize = self.jackpot_balance;
+            self.jackpot_balance = 0;
+            (true, prize)
+        } else {
+            (false, 0)
+        }
+    }
+
+    /// Funds backing the house shares, including the ones reserved for pending bets
+    pub fn bankroll(&self) -> u128 {
+        self.house_funds + self.reserved_funds
+    }
+
+    /// House shares minted for a liquidity deposit. Before the first deposit
+    /// shares are minted 1:1 with the bankroll
+    pub fn shares_for_deposit(&self, amount: u128) -> Result<u128, &'static str> {
+        let shares = if self.total_shares == 0 {
+            amount
+        } else if self.bankroll() == 0 {
+            return Err(ERR_903);
+        } else {
+            mul_div(amount, self.total_shares, self.bankroll())
+        };
+        if shares == 0 {
+            return Err(ERR_904);
+        }
+        Ok(shares)
+    }
+
+    /// Adds a liquidity deposit to house_funds and returns the shares minted for it.
+    /// On the first deposit the bankroll funded so far is issued to the partner
+    pub fn provide_liquidity(&mut self, amount: u128) -> Result<u128, &'static str> {
+        let shares = self.shares_for_deposit(amount)?;
+        if self.total_shares == 0 {
+            self.partner_shares = self.bankroll();
+            self.total_shares = self.bankroll();
+        }
+        self.house_funds += amount;
+        self.total_shares += shares;
+        Ok(shares)
+    }
+
+    /// Current value of an amount of house shares, rounded down
+    pub fn share_value(&self, shares: u128) -> u128 {
+        if self.total_shares == 0 {
+            return 0;
+        }
+        mul_div(shares, self.bankroll(), self.total_shares)
+    }
+
+    /// Burns house shares and takes their value out of house_funds, returns the amount withdrawn
+    pub fn withdraw_liquidity(&mut self, shares: u128) -> Result<u128, &'static str> {
+        let amount = self.share_value(shares);
+        if amount == 0 {
+            return Err(ERR_904);
+        }
+        if amount > self.house_funds {
+            return Err(ERR_902);
+        }
+        self.house_funds -= amount;
+        self.total_shares -= shares;
+        Ok(amount)
+    }
+
+    /// Credits the cuts kept by the game. Nft and owner cuts are credited by the contract
+    pub fn accrue_fees(&mut self, fees: &FeeCuts) {
+        self.house_funds += fees.house_cut;
+        self.partner_balance += fees.partner_cut;
+        self.jackpot_balance += fees.jackpot_cut;
+    }
+
+    /// Puts funds the partner took out back into house_funds. On games with liquidity providers
+    /// they buy the partner shares at the current value, unless the bankroll was emptied meanwhile
+    pub fn return_partner_funds(&mut self, amount: u128) {
+        let shares = if self.total_shares > 0 {
+            self.provide_liquidity(amount).ok()
+        } else {
+            None
+        };
+        match shares {
+            Some(shares) => self.partner_shares += shares,
+            None => self.house_funds += amount,
+        }
+    }
+
+    /// House shares burned to withdraw an amount of the bankroll, rounded up so
+    /// that withdrawals never take value from the other holders
+    pub fn shares_for_withdrawal(&self, amount: u128) -> u128 {
+        if self.total_shares == 0 {
+            return 0;
+        }
+        mul_div_ceil(amount, self.total_shares, self.bankroll())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_game;
+
+    fn sample_game(house_funds: u128) -> PartneredGame {
+        PartneredGame {
+            house_funds,
+            ..test_game("token.testnet")
+        }
+    }
+
+    /// house shares
+    /// methods must:
+    /// 1. Issue the bankroll funded before the first deposit to the partner 1:1
+    /// 2. Mint shares at the current share value
+    /// 3. Move share value with house wins and losses
+    /// 4. Burn shares for their value out of the free house_funds
+    /// 5. Round withdrawal burns up and refuse deposits worth no share
+    #[test]
+    fn test_house_shares() {
+        // 1. Issue the bankroll funded before the first deposit to the partner 1:1
+        let mut game = sample_game(1_000);
+        assert_eq!(game.provide_liquidity(500), Ok(500));
+        assert_eq!((game.partner_shares, game.total_shares, game.house_funds), (1_000, 1_500, 1_500));
+
+        // 2. Mint shares at the current share value
+        game.house_funds = 3_000;
+        assert_eq!(game.share_value(500), 1_000);
+        assert_eq!(game.provide_liquidity(600), Ok(300));
+        assert_eq!((game.total_shares, game.house_funds), (1_800, 3_600));
+
+        // 3. Move share value with house wins and losses, including funds reserved for pending bets
+        game.house_funds = 900;
+        game.reserved_funds = 900;
+        assert_eq!(game.share_value(300), 300);
+
+        // 4. Burn shares for their value out of the free house_funds
+        assert_eq!(game.withdraw_liquidity(1_000), Err(ERR_902));
+        assert_eq!(game.withdraw_liquidity(500), Ok(500));
+        assert_eq!((game.total_shares, game.house_funds), (1_300, 400));
+
+        // 5. Round withdrawal burns up and refuse deposits worth no share
+        game.house_funds = 401;
+        game.reserved_funds = 0;
+        assert_eq!(game.shares_for_withdrawal(100), 325);
+        assert_eq!(game.withdraw_liquidity(0), Err(ERR_904));
+        game.house_funds = 1_300_000;
+        assert_eq!(game.shares_for_deposit(999), Err(ERR_904));
+        game.house_funds = 0;
+        assert_eq!(game.shares_for_deposit(1_000), Err(ERR_903));
+    }
+
+    /// check_cover, effective_max_bet
+    /// methods must:
+    /// 1. Refuse bets whose largest prize house_funds cannot pay with ERR_407
+    /// 2. Refuse bets whose largest prize goes over max_exposure of house_funds with ERR_417
+    /// 3. Return the largest bet passing both checks, capped at max_bet
+    #[test]
+    fn test_exposure_limit() {
+        let mut game = sample_game(10_000);
+        game.max_bet = 10_000;
+        let even = BetChoice::new(128, None, None);
+        let quarter = BetChoice::new(64, None, None);
+
+        // 1. Refuse bets whose largest prize house_funds cannot pay with ERR_407
+        assert_eq!(game.check_cover(5_000, &even), Ok(()));
+        assert_eq!(game.check_cover(5_001, &even), Err(ERR_407));
+        assert_eq!(game.effective_max_bet(&even), 5_000);
+
+        // 2. Refuse bets whose largest prize goes over max_exposure of house_funds with ERR_417
+        game.max_exposure = FRACTIONAL_BASE / 10;
+        assert_eq!(game.exposure_limit(), 1_000);
+        assert_eq!(game.check_cover(500, &even), Ok(()));
+        assert_eq!(game.check_cover(501, &even), Err(ERR_417));
+
+        // 3. Return the largest bet passing both checks, capped at max_bet
+        assert_eq!(game.effective_max_bet(&even), 500);
+        assert_eq!(game.effective_max_bet(&quarter), 250);
+        game.max_bet = 300;
+        assert_eq!(game.effective_max_bet(&even), 300);
+        game.house_funds = 0;
+        assert_eq!(game.effective_max_bet(&quarter), 0);
+    }
 }
@@ -0,0 +1,38 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::AccountId;
+
+use crate::{BetChoice, GameParameters, RollDirection};
+
+/// Bet placed through commit_bet that still waits for a future
+/// block's random seed to be settled by reveal_bet
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingBet {
+    pub account_id: AccountId,
+    pub game_code: String,
+    #[serde(with = "crate::string")]
+    pub bet_size: u128,
+    #[serde(with = "crate::string")]
+    pub net_bet: u128,
+    pub odds: u32,
+    pub direction: RollDirection,
+    #[serde(with = "crate::string")]
+    pub target_multiplier: u128,
+    pub game_parameters: GameParameters,
+    // house funds reserved to pay the bet in case it wins
+    #[serde(with = "crate::string")]
+    pub potential_win: u128,
+    #[serde(with = "crate::string")]
+    pub commit_block: u64,
+}
+
+impl PendingBet {
+    pub fn choice(&self) -> BetChoice {
+        BetChoice {
+            odds: self.odds,
+            direction: self.direction,
+            target_multiplier: self.target_multiplier,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, Vector};
+use near_sdk::json_types::U64;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+use crate::{StorageKey, ERR_702, FRACTIONAL_BASE};
+
+pub const MAX_RAFFLE_WINNERS: usize = 10;
+
+/// Tickets bought in a single purchase, numbered from first_ticket up to
+/// the first_ticket of the next purchase
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TicketBatch {
+    pub buyer: AccountId,
+    pub first_ticket: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaffleWinner {
+    pub account_id: AccountId,
+    #[serde(with = "crate::string")]
+    pub ticket: u64,
+    #[serde(with = "crate::string")]
+    pub prize: u128,
+}
+
+/// Periodic draw of a partnered game. Players buy tickets with credits until
+/// closes_at, then the pot minus the game fees is split between winners drawn
+/// with the seed of draw_block, set when the round is closed
+#[derive(BorshDeserialize, BorshSerialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaffleRound {
+    pub game_code: String,
+    pub token_contract: AccountId,
+    #[serde(with = "crate::string")]
+    pub ticket_price: u128,
+    // block timestamp in nanoseconds ticket sales end at
+    #[serde(with = "crate::string")]
+    pub closes_at: u64,
+    // share of the prize each winner place takes, base 10e-5, summing to FRACTIONAL_BASE
+    pub winner_shares: Vec<u32>,
+    #[serde(with = "crate::string")]
+    pub tickets_sold: u64,
+    #[serde(with = "crate::string")]
+    pub pot: u128,
+    // first block the round can be drawn in, REVEAL_DELAY_BLOCKS after it was closed
+    pub draw_block: Option<U64>,
+    pub drawn: bool,
+    pub winners: Vec<RaffleWinner>,
+    #[serde(skip)]
+    pub batches: Vector<TicketBatch>,
+    #[serde(skip)]
+    pub ticket_counts: LookupMap<AccountId, u64>,
+}
+
+impl RaffleRound {
+    pub fn new(
+        round_id: u128,
+        game_code: String,
+        token_contract: AccountId,
+        ticket_price: u128,
+        closes_at: u64,
+        winner_shares: Vec<u32>,
+    ) -> Self {
+        Self {
+            game_code,
+            token_contract,
+            ticket_price,
+            closes_at,
+            winner_shares,
+            tickets_sold: 0,
+            pot: 0,
+            draw_block: None,
+            drawn: false,
+            winners: vec![],
+            batches: Vector::new(StorageKey::RaffleTickets { round_id }),
+            ticket_counts: LookupMap::new(StorageKey::RaffleTicketCounts { round_id }),
+        }
+    }
+
+    pub fn validate_shares(winner_shares: &[u32]) -> Result<(), &'static str> {
+        if !valid_shares(winner_shares, MAX_RAFFLE_WINNERS) {
+            return Err(ERR_702);
+        }
+        Ok(())
+    }
+
+    pub fn add_tickets(&mut self, buyer: &AccountId, tickets: u64) -> u64 {
+        self.batches.push(&TicketBatch {
+            buyer: buyer.clone(),
+            first_ticket: self.tickets_sold,
+        });
+        self.tickets_sold += tickets;
+        self.pot += tickets as u128 * self.ticket_price;
+        let count = self.ticket_counts.get(buyer).unwrap_or(0) + tickets;
+        self.ticket_counts.insert(buyer, &count);
+        count
+    }
+
+    /// Buyer of a ticket, found by binary search over the purchases
+    pub fn ticket_owner(&self, ticket: u64) -> AccountId {
+        assert!(ticket < self.tickets_sold, "ticket out of the sold range");
+        let mut low = 0;
+        let mut high = self.batches.len();
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if self.batches.get(middle).unwrap().first_ticket <= ticket {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        self.batches.get(low).unwrap().buyer
+    }
+
+    /// Splits a prize between winner places. Places left without a winner
+    /// because too few tickets were sold and rounding dust go to first place
+    pub fn split_prize(&self, prize: u128, winners: usize) -> Vec<u128> {
+        split_by_shares(prize, &self.winner_shares[..winners])
+    }
+}
+
+/// Whether shares are 1 to max_places positive shares summing to FRACTIONAL_BASE
+pub fn valid_shares(shares: &[u32], max_places: usize) -> bool {
+    !shares.is_empty()
+        && shares.len() <= max_places
+        && !shares.contains(&0)
+        && shares.iter().map(|share| *share as u128).sum::<u128>() == FRACTIONAL_BASE
+}
+
+/// Splits a prize by shares of FRACTIONAL_BASE, rounding dust goes to the first share
+pub fn split_by_shares(prize: u128, shares: &[u32]) -> Vec<u128> {
+    let mut prizes: Vec<u128> = shares
+        .iter()
+        .map(|share| (prize * *share as u128) / FRACTIONAL_BASE)
+        .collect();
+    let dust = prize - prizes.iter().sum::<u128>();
+    if let Some(first) = prizes.first_mut() {
+        *first += dust;
+    }
+    prizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    fn sample_round(seed: u128, winner_shares: Vec<u32>) -> RaffleRound {
+        RaffleRound::new(seed, "game".to_string(), "token.testnet".to_string(), 10, 100, winner_shares)
+    }
+
+    /// validate_shares
+    /// method must:
+    /// 1. Accept 1 to MAX_RAFFLE_WINNERS positive shares summing to FRACTIONAL_BASE
+    /// 2. Reject anything else
+    #[test]
+    fn test_validate_shares() {
+        let base = FRACTIONAL_BASE as u32;
+        // 1. Accept 1 to MAX_RAFFLE_WINNERS positive shares summing to FRACTIONAL_BASE
+        assert_eq!(RaffleRound::validate_shares(&[base]), Ok(()));
+        assert_eq!(RaffleRound::validate_shares(&[base / 2, base / 4, base / 4]), Ok(()));
+        assert_eq!(RaffleRound::validate_shares(&[base / 10; MAX_RAFFLE_WINNERS]), Ok(()));
+
+        // 2. Reject anything else
+        assert_eq!(RaffleRound::validate_shares(&[]), Err(ERR_702));
+        assert_eq!(RaffleRound::validate_shares(&[base - 1]), Err(ERR_702));
+        assert_eq!(RaffleRound::validate_shares(&[base, 0]), Err(ERR_702));
+        assert_eq!(RaffleRound::validate_shares(&[base / 11; 11]), Err(ERR_702));
+    }
+
+    /// ticket_owner
+    /// method must:
+    /// 1. Number tickets in purchase order
+    /// 2. Count the tickets of every buyer
+    #[test]
+    fn test_ticket_owner() {
+        let context = get_context(vec![], false, 0, 0, SIGNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut round = sample_round(0, vec![FRACTIONAL_BASE as u32]);
+        let alice = "alice.testnet".to_string();
+        let bob = "bob.testnet".to_string();
+
+        assert_eq!(round.add_tickets(&alice, 3), 3);
+        assert_eq!(round.add_tickets(&bob, 1), 1);
+        assert_eq!(round.add_tickets(&alice, 2), 5);
+        assert_eq!(round.tickets_sold, 6);
+        assert_eq!(round.pot, 60);
+
+        // 1. Number tickets in purchase order
+        let expected = [&alice, &alice, &alice, &bob, &alice, &alice];
+        for (ticket, owner) in expected.iter().enumerate() {
+            assert_eq!(&&round.ticket_owner(ticket as u64), owner);
+        }
+
+        // 2. Count the tickets of every buyer
+        assert_eq!(round.ticket_counts.get(&alice), Some(5));
+        assert_eq!(round.ticket_counts.get(&bob), Some(1));
+    }
+
+    /// split_prize
+    /// method must:
+    /// 1. Split the prize by winner shares
+    /// 2. Give shares of undrawn places and rounding dust to first place
+    #[test]
+    fn test_split_prize() {
+        let base = FRACTIONAL_BASE as u32;
+        let round = sample_round(1, vec![base / 2, base * 3 / 10, base / 5]);
+
+        // 1. Split the prize by winner shares
+        assert_eq!(round.split_prize(1_000, 3), vec![500, 300, 200]);
+
+        // 2. Give shares of undrawn places and rounding dust to first place
+        assert_eq!(round.split_prize(1_000, 1), vec![1_000]);
+        assert_eq!(round.split_prize(1_000, 2), vec![700, 300]);
+        assert_eq!(round.split_prize(999, 3), vec![501, 299, 199]);
+    }
+}
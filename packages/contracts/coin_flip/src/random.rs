@@ -0,0 +1,133 @@
+use std::convert::TryInto;
+
+use near_sdk::{env, AccountId};
+
+/// Hashes every input of a bet into the 32 byte value all outcomes are drawn from.
+/// Variable length inputs are length prefixed so that different combinations of
+/// account_id and game_code can never produce the same preimage
+pub fn bet_hash(seed: &[u8], account_id: &AccountId, nonce: u128, game_code: &str) -> Vec<u8> {
+    let mut preimage = Vec::with_capacity(seed.len() + account_id.len() + game_code.len() + 28);
+    preimage.extend_from_slice(&(seed.len() as u32).to_be_bytes());
+    preimage.extend_from_slice(seed);
+    preimage.extend_from_slice(&(account_id.len() as u32).to_be_bytes());
+    preimage.extend_from_slice(account_id.as_bytes());
+    preimage.extend_from_slice(&nonce.to_be_bytes());
+    preimage.extend_from_slice(&(game_code.len() as u32).to_be_bytes());
+    preimage.extend_from_slice(game_code.as_bytes());
+    env::keccak256(&preimage)
+}
+
+/// Draws an unbiased value in [0, range) from hash. Values that fall in the
+/// incomplete last bucket of u128 are rejected and the hash is rehashed,
+/// returns the value and how many rehashes were needed
+pub fn draw(hash: &[u8], range: u128) -> (u128, u32) {
+    assert!(range > 0, "range must be greater than 0");
+    let zone = (u128::MAX / range) * range;
+    let mut current = hash.to_vec();
+    let mut rehashes = 0;
+    loop {
+        let value = u128::from_be_bytes(current[0..16].try_into().unwrap());
+        if value < zone {
+            return (value % range, rehashes);
+        }
+        current = env::keccak256(&current);
+        rehashes += 1;
+    }
+}
+
+/// Hash the jackpot trigger of a bet is drawn from. Domain separated from the
+/// bet hash so the trigger is independent from the roll of the bet
+pub fn jackpot_hash(bet_hash: &[u8]) -> Vec<u8> {
+    let mut preimage = b"jackpot".to_vec();
+    preimage.extend_from_slice(bet_hash);
+    env::keccak256(&preimage)
+}
+
+/// Whether a bet wins the jackpot, which happens once every trigger bets on average
+pub fn jackpot_triggered(seed: &[u8], account_id: &AccountId, nonce: u128, game_code: &str, trigger: u32) -> bool {
+    draw(&jackpot_hash(&bet_hash(seed, account_id, nonce, game_code)), trigger as u128).0 == 0
+}
+
+/// Draws a ticket in [0, tickets) for a raffle round. Every attempt of a draw uses
+/// its own domain separated hash, attempts are repeated to skip tickets already drawn
+pub fn raffle_ticket(seed: &[u8], round_id: u128, game_code: &str, attempt: u32, tickets: u64) -> u64 {
+    let mut preimage = b"raffle".to_vec();
+    preimage.extend_from_slice(&attempt.to_be_bytes());
+    preimage.extend_from_slice(&bet_hash(seed, &env::current_account_id(), round_id, game_code));
+    draw(&env::keccak256(&preimage), tickets as u128).0 as u64
+}
+
+/// Single entry point used by every game mode to get a random value in [0, range)
+pub fn random_value(
+    seed: &[u8],
+    account_id: &AccountId,
+    nonce: u128,
+    game_code: &str,
+    range: u128,
+) -> u128 {
+    draw(&bet_hash(seed, account_id, nonce, game_code), range).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    /// random_value
+    /// method must:
+    /// 1. Be deterministic for the same inputs
+    /// 2. Change with every input
+    /// 3. Always return values inside range
+    #[test]
+    fn test_random_value() {
+        let context = get_context(vec![], false, 0, 0, SIGNER_ACCOUNT.to_string());
+        testing_env!(context);
+
+        let seed = env::keccak256(b"seed");
+        let account = SIGNER_ACCOUNT.to_string();
+        let game = "game".to_string();
+        let range = u128::MAX / 3;
+
+        // 1. Be deterministic for the same inputs
+        let base = random_value(&seed, &account, 0, &game, range);
+        assert_eq!(base, random_value(&seed, &account, 0, &game, range));
+
+        // 2. Change with every input
+        let other_seed = env::keccak256(b"other_seed");
+        assert_ne!(base, random_value(&other_seed, &account, 0, &game, range));
+        assert_ne!(base, random_value(&seed, &OWNER_ACCOUNT.to_string(), 0, &game, range));
+        assert_ne!(base, random_value(&seed, &account, 1, &game, range));
+        assert_ne!(base, random_value(&seed, &account, 0, "other_game", range));
+        assert_ne!(
+            bet_hash(&seed, &"ab".to_string(), 0, "c"),
+            bet_hash(&seed, &"a".to_string(), 0, "bc")
+        );
+
+        // 3. Always return values inside range
+        for nonce in 0..256 {
+            assert!(random_value(&seed, &account, nonce, &game, 10_000) < 10_000);
+            assert!(random_value(&seed, &account, nonce, &game, 3) < 3);
+        }
+    }
+
+    /// draw
+    /// method must:
+    /// 1. Accept values inside the unbiased zone
+    /// 2. Rehash values that would bias the result
+    #[test]
+    fn test_draw() {
+        let context = get_context(vec![], false, 0, 0, SIGNER_ACCOUNT.to_string());
+        testing_env!(context);
+
+        // 1. Accept values inside the unbiased zone
+        let mut hash = vec![0u8; 32];
+        hash[15] = 107;
+        assert_eq!(draw(&hash, 100), (7, 0));
+
+        // 2. Rehash values that would bias the result
+        let max_hash = vec![255u8; 32];
+        let (value, rehashes) = draw(&max_hash, 100);
+        assert_eq!(rehashes, 1);
+        assert_eq!(value, draw(&env::keccak256(&max_hash), 100).0);
+    }
+}
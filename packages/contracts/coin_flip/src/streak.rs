@@ -0,0 +1,18 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::AccountId;
+
+/// Payout of a winning play called with start_streak, held apart from the player's
+/// balances until it is collected or lost pressing it. Receipts of streak bets count
+/// the held payout in their credits
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Streak {
+    pub token_contract: AccountId,
+    #[serde(with = "crate::string")]
+    pub amount: u128,
+    // wins in a row, the opening play included
+    pub length: u32,
+    #[serde(with = "crate::string")]
+    pub last_bet_id: u128,
+}
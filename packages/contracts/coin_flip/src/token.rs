@@ -0,0 +1,57 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+
+/// Whitelisted NEP-141 token, metadata is fetched once through ft_metadata
+/// when the owner registers the token and cached here
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon: Option<String>,
+}
+
+impl TokenInfo {
+    /// Formats a raw token amount using the token decimals, e.g. 1500000 with
+    /// 6 decimals and symbol USDC becomes "1.5 USDC"
+    pub fn format_amount(&self, amount: u128) -> String {
+        let decimals = self.decimals as usize;
+        let digits = format!("{:0>width$}", amount, width = decimals + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            format!("{} {}", integer, self.symbol)
+        } else {
+            format!("{}.{} {}", integer, fraction, self.symbol)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// format_amount
+    /// method must:
+    /// 1. Place decimal point according to decimals
+    /// 2. Trim trailing zeros of the fraction
+    /// 3. Pad amounts smaller than one unit
+    #[test]
+    fn test_format_amount() {
+        let token = |decimals| TokenInfo {
+            symbol: "TKN".to_string(),
+            decimals,
+            icon: None,
+        };
+
+        // 1. Place decimal point according to decimals
+        assert_eq!(token(6).format_amount(1_234_567), "1.234567 TKN");
+        assert_eq!(token(0).format_amount(1_234_567), "1234567 TKN");
+        // 2. Trim trailing zeros of the fraction
+        assert_eq!(token(6).format_amount(1_500_000), "1.5 TKN");
+        assert_eq!(token(24).format_amount(10u128.pow(24)), "1 TKN");
+        // 3. Pad amounts smaller than one unit
+        assert_eq!(token(6).format_amount(25), "0.000025 TKN");
+        assert_eq!(token(6).format_amount(0), "0 TKN");
+    }
+}
@@ -0,0 +1,256 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::AccountId;
+
+use crate::raffle::{split_by_shares, valid_shares};
+use crate::{StorageKey, ERR_802, FRACTIONAL_BASE};
+
+pub const MAX_TOURNAMENT_WINNERS: usize = 10;
+// unsettled tournaments a game can run at once, every play updates all of them
+pub const MAX_GAME_TOURNAMENTS: usize = 3;
+
+/// What a tournament ranks players by, counting only bets placed between
+/// starts_at and ends_at
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum TournamentScoring {
+    // sum of bet sizes
+    Volume,
+    // prizes and jackpots won minus bet sizes
+    NetProfit,
+    // best payout / bet_size of a single bet, base 10e-5. Free bets of games with a zero
+    // min_bet have no multiplier and are not scored
+    BiggestMultiplier,
+}
+
+impl TournamentScoring {
+    /// Score of a player after a bet, given the score before it
+    pub fn score(&self, current: i128, bet_size: u128, payout: u128, jackpot_payout: u128) -> i128 {
+        match self {
+            TournamentScoring::Volume => current + bet_size as i128,
+            TournamentScoring::NetProfit => current + (payout + jackpot_payout) as i128 - bet_size as i128,
+            TournamentScoring::BiggestMultiplier if bet_size == 0 => current,
+            TournamentScoring::BiggestMultiplier => current.max(((payout * FRACTIONAL_BASE) / bet_size) as i128),
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaderboardEntry {
+    pub account_id: AccountId,
+    #[serde(with = "crate::string")]
+    pub score: i128,
+    // paid once the tournament is settled
+    #[serde(with = "crate::string")]
+    pub prize: u128,
+}
+
+/// Competition between the players of a partnered game. Prize pool is funded
+/// through ft_transfer_call and paid to the leaderboard once the tournament ends
+#[derive(BorshDeserialize, BorshSerialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Tournament {
+    pub game_code: String,
+    pub token_contract: AccountId,
+    // block timestamps in nanoseconds
+    #[serde(with = "crate::string")]
+    pub starts_at: u64,
+    #[serde(with = "crate::string")]
+    pub ends_at: u64,
+    pub scoring: TournamentScoring,
+    // share of the prize pool each leaderboard place takes, base 10e-5, summing to FRACTIONAL_BASE
+    pub prize_shares: Vec<u32>,
+    #[serde(with = "crate::string")]
+    pub prize_pool: u128,
+    pub settled: bool,
+    // best players with a positive score, highest first, one place per prize share
+    pub leaderboard: Vec<LeaderboardEntry>,
+    #[serde(skip)]
+    pub scores: LookupMap<AccountId, i128>,
+}
+
+impl Tournament {
+    pub fn new(
+        tournament_id: u128,
+        game_code: String,
+        token_contract: AccountId,
+        starts_at: u64,
+        ends_at: u64,
+        scoring: TournamentScoring,
+        prize_shares: Vec<u32>,
+    ) -> Self {
+        Self {
+            game_code,
+            token_contract,
+            starts_at,
+            ends_at,
+            scoring,
+            prize_shares,
+            prize_pool: 0,
+            settled: false,
+            leaderboard: vec![],
+            scores: LookupMap::new(StorageKey::TournamentScores { tournament_id }),
+        }
+    }
+
+    pub fn validate_shares(prize_shares: &[u32]) -> Result<(), &'static str> {
+        if !valid_shares(prize_shares, MAX_TOURNAMENT_WINNERS) {
+            return Err(ERR_802);
+        }
+        Ok(())
+    }
+
+    pub fn is_running(&self, block_timestamp: u64) -> bool {
+        !self.settled && self.starts_at <= block_timestamp && block_timestamp < self.ends_at
+    }
+
+    /// Scores a bet for its player and moves them on the leaderboard
+    pub fn record_bet(&mut self, account_id: &AccountId, bet_size: u128, payout: u128, jackpot_payout: u128) {
+        let current = self.scores.get(account_id).unwrap_or(0);
+        let score = self.scoring.score(current, bet_size, payout, jackpot_payout);
+        self.scores.insert(account_id, &score);
+        self.update_leaderboard(account_id, score);
+    }
+
+    /// Keeps the leaderboard bounded to the paid places. Players are only ranked
+    /// when they bet, so a player pushed off the board returns with their next bet
+    pub fn update_leaderboard(&mut self, account_id: &AccountId, score: i128) {
+        self.leaderboard.retain(|entry| &entry.account_id != account_id);
+        if score <= 0 {
+            return;
+        }
+        // ties keep the player that reached the score first ahead
+        let place = self
+            .leaderboard
+            .iter()
+            .position(|entry| entry.score < score)
+            .unwrap_or(self.leaderboard.len());
+        self.leaderboard.insert(
+            place,
+            LeaderboardEntry {
+                account_id: account_id.clone(),
+                score,
+                prize: 0,
+            },
+        );
+        self.leaderboard.truncate(self.prize_shares.len());
+    }
+
+    /// Prizes of the ranked players, shares of empty places go to first place
+    pub fn split_prize_pool(&self) -> Vec<u128> {
+        split_by_shares(self.prize_pool, &self.prize_shares[..self.leaderboard.len()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    fn sample_tournament(seed: u128, scoring: TournamentScoring, prize_shares: Vec<u32>) -> Tournament {
+        Tournament::new(seed, "game".to_string(), "token.testnet".to_string(), 10, 100, scoring, prize_shares)
+    }
+
+    /// score
+    /// method must:
+    /// 1. Add up bet sizes for volume
+    /// 2. Add up winnings minus bet sizes for net profit
+    /// 3. Keep the best payout multiplier for biggest multiplier
+    /// 4. Skip bets without a size for biggest multiplier
+    #[test]
+    fn test_score() {
+        // 1. Add up bet sizes for volume
+        let volume = TournamentScoring::Volume;
+        assert_eq!(volume.score(volume.score(0, 100, 250, 0), 40, 0, 0), 140);
+
+        // 2. Add up winnings minus bet sizes for net profit
+        let net_profit = TournamentScoring::NetProfit;
+        assert_eq!(net_profit.score(0, 40, 0, 0), -40);
+        assert_eq!(net_profit.score(-40, 100, 250, 0), 110);
+        assert_eq!(net_profit.score(0, 10, 0, 1_000), 990);
+
+        // 3. Keep the best payout multiplier for biggest multiplier
+        let multiplier = TournamentScoring::BiggestMultiplier;
+        assert_eq!(multiplier.score(0, 100, 250, 0), 250_000);
+        assert_eq!(multiplier.score(250_000, 40, 0, 0), 250_000);
+        assert_eq!(multiplier.score(100_000, 100, 250, 0), 250_000);
+
+        // 4. Skip bets without a size for biggest multiplier
+        assert_eq!(multiplier.score(100_000, 0, 250, 0), 100_000);
+        assert_eq!(multiplier.score(0, 0, 0, 0), 0);
+    }
+
+    /// update_leaderboard
+    /// method must:
+    /// 1. Rank players highest score first, ties in order of arrival
+    /// 2. Keep one entry per player
+    /// 3. Keep at most one entry per prize share
+    /// 4. Drop players whose score is no longer positive
+    #[test]
+    fn test_update_leaderboard() {
+        let context = get_context(vec![], false, 0, 0, SIGNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let base = FRACTIONAL_BASE as u32;
+        let mut tournament = sample_tournament(0, TournamentScoring::NetProfit, vec![base / 2, base / 4, base / 4]);
+        let ranking = |tournament: &Tournament| -> Vec<(AccountId, i128)> {
+            tournament
+                .leaderboard
+                .iter()
+                .map(|entry| (entry.account_id.clone(), entry.score))
+                .collect()
+        };
+        let (alice, bob, carol, dave) = (
+            "alice.testnet".to_string(),
+            "bob.testnet".to_string(),
+            "carol.testnet".to_string(),
+            "dave.testnet".to_string(),
+        );
+
+        // 1. Rank players highest score first, ties in order of arrival
+        tournament.update_leaderboard(&alice, 10);
+        tournament.update_leaderboard(&bob, 30);
+        tournament.update_leaderboard(&carol, 10);
+        assert_eq!(ranking(&tournament), vec![(bob.clone(), 30), (alice.clone(), 10), (carol.clone(), 10)]);
+
+        // 2. Keep one entry per player
+        tournament.update_leaderboard(&carol, 40);
+        assert_eq!(ranking(&tournament), vec![(carol.clone(), 40), (bob.clone(), 30), (alice.clone(), 10)]);
+
+        // 3. Keep at most one entry per prize share
+        tournament.update_leaderboard(&dave, 5);
+        assert_eq!(ranking(&tournament), vec![(carol.clone(), 40), (bob.clone(), 30), (alice.clone(), 10)]);
+        tournament.update_leaderboard(&dave, 20);
+        assert_eq!(ranking(&tournament), vec![(carol.clone(), 40), (bob.clone(), 30), (dave.clone(), 20)]);
+
+        // 4. Drop players whose score is no longer positive
+        tournament.update_leaderboard(&bob, 0);
+        assert_eq!(ranking(&tournament), vec![(carol, 40), (dave, 20)]);
+    }
+
+    /// record_bet
+    /// method must:
+    /// 1. Accumulate scores across bets
+    /// 2. Rank players by their accumulated score
+    #[test]
+    fn test_record_bet() {
+        let context = get_context(vec![], false, 0, 0, SIGNER_ACCOUNT.to_string());
+        testing_env!(context);
+        let mut tournament = sample_tournament(1, TournamentScoring::Volume, vec![FRACTIONAL_BASE as u32]);
+
+        let alice = "alice.testnet".to_string();
+        let bob = "bob.testnet".to_string();
+        tournament.record_bet(&alice, 100, 0, 0);
+        tournament.record_bet(&bob, 150, 0, 0);
+        // 1. Accumulate scores across bets
+        tournament.record_bet(&alice, 60, 0, 0);
+        assert_eq!(tournament.scores.get(&alice), Some(160));
+        assert_eq!(tournament.scores.get(&bob), Some(150));
+
+        // 2. Rank players by their accumulated score
+        assert_eq!(tournament.leaderboard.len(), 1);
+        assert_eq!(tournament.leaderboard[0].account_id, alice);
+        assert_eq!(tournament.leaderboard[0].score, 160);
+    }
+}
@@ -6,6 +6,13 @@ interface UserStorage {
   available: string;
 }
 
+interface BetReceipt {
+  bet_id: string;
+  outcome: boolean;
+  payout: string;
+  credits: string;
+}
+
 interface FeeConfig {
   partner_fee: string;
   bet_payment_adjustment: string;
@@ -31,6 +38,7 @@ describe("Greeting Contract Integration Tests", () => {
   let tokenContractAccount: NearAccount;
 
   let fee_config: FeeConfig;
+  let game_id: string;
 
   beforeAll(async () => {
     worker = await Worker.init();
@@ -102,20 +110,38 @@ describe("Greeting Contract Integration Tests", () => {
     );
   });
 
+  it("should whitelist the game token", async () => {
+    await owner.call(
+      coinflipContractAccount,
+      "register_token",
+      { token_contract: tokenContractAccount.accountId },
+      { gas: "300 TGas", attachedDeposit: "1" }
+    );
+
+    let token: { symbol: string; decimals: number } =
+      await coinflipContractAccount.view("view_token", {
+        token_contract: tokenContractAccount.accountId,
+      });
+
+    expect(token.decimals).toBeGreaterThan(0);
+  });
+
   it("should create game", async () => {
     let initial_storage_deposit: UserStorage =
       await coinflipContractAccount.view("storage_balance_of", {
         account_id: coinflipContractAccount.accountId,
       });
 
-    await owner.call(
+    game_id = await owner.call(
       coinflipContractAccount,
       "create_new_partner",
       {
+        game_id: "test-game",
         partner_owner: project_owner,
         nft_contract: nft_account.accountId,
         token_contract: tokenContractAccount.accountId,
         ...fee_config,
+        withdrawal_delay: "0",
       },
       { attachedDeposit: "1" }
     );
@@ -125,6 +151,12 @@ describe("Greeting Contract Integration Tests", () => {
       { account_id: coinflipContractAccount.accountId }
     );
 
+    expect(game_id).toEqual("test-game");
+    let games: [string, any][] = await coinflipContractAccount.view(
+      "games_by_token",
+      { token: tokenContractAccount.accountId }
+    );
+    expect(games.map(([id]) => id)).toEqual([game_id]);
     expect(initial_storage_deposit.total).toEqual(final_storage_deposit.total);
     expect(BigInt(final_storage_deposit.available)).toBeLessThan(
       BigInt(initial_storage_deposit.available)
@@ -143,7 +175,7 @@ describe("Greeting Contract Integration Tests", () => {
         memo: null,
         msg: JSON.stringify({
           type: "FundGame",
-          game_id: nft_account.accountId,
+          game_id,
         }),
       },
       {
@@ -154,7 +186,7 @@ describe("Greeting Contract Integration Tests", () => {
 
     let game_state: any = await coinflipContractAccount.view(
       "view_partner_data",
-      { nft_contract: nft_account.accountId }
+      { game_id }
     );
 
     expect(game_state.house_funds).toEqual(balance_to_fund);
@@ -264,11 +296,11 @@ describe("Greeting Contract Integration Tests", () => {
     while (
       BigInt(user_current_balance) > BigInt(utils.format.parseNearAmount("3")!)
     ) {
-      let won_status: boolean = await user.call(
+      let receipt: BetReceipt = await user.call(
         coinflipContractAccount,
         "play",
         {
-          game_code: nft_account.accountId,
+          game_code: game_id,
           bet_size,
           odds: 128,
           _bet_type: "whatever",
@@ -279,7 +311,8 @@ describe("Greeting Contract Integration Tests", () => {
         token_type: tokenContractAccount.accountId,
         account_id: user.accountId,
       });
-      if (won_status) {
+      expect(receipt.credits).toEqual(new_user_balance);
+      if (receipt.outcome) {
         expect(BigInt(new_user_balance)).toBeGreaterThan(
           BigInt(user_current_balance)
         );
@@ -339,7 +372,7 @@ describe("Greeting Contract Integration Tests", () => {
   it("should enable project_owner to withdraw house_funds and project_funds", async () => {
     let partner_data: any = await coinflipContractAccount.view(
       "view_partner_data",
-      { nft_contract: nft_account.accountId }
+      { game_id }
     );
     let partner_funds: string = partner_data.partner_balance;
     let house_funds: string = partner_data.house_funds;
@@ -352,27 +385,38 @@ describe("Greeting Contract Integration Tests", () => {
       coinflipContractAccount,
       "retrieve_partner_balance",
       {
-        game_code: nft_account.accountId,
+        game_code: game_id,
       },
       {
         gas: "300 TGas",
       }
     );
-    await project_owner.call(
+    let withdrawal_id: string = await project_owner.call(
       coinflipContractAccount,
       "retrieve_house_funds",
       {
-        game_code: nft_account.accountId,
+        game_code: game_id,
         quantity: house_funds.toString(),
       },
       {
         gas: "300 TGas",
       }
     );
+    await project_owner.call(
+      coinflipContractAccount,
+      "execute_house_withdrawal",
+      {
+        game_code: game_id,
+        withdrawal_id,
+      },
+      {
+        gas: "300 TGas",
+      }
+    );
 
     let final_partner_data: any = await coinflipContractAccount.view(
       "view_partner_data",
-      { nft_contract: nft_account.accountId }
+      { game_id }
     );
     let final_partner_funds: string = final_partner_data.partner_balance;
     let final_house_funds: string = final_partner_data.house_funds;
@@ -408,7 +452,7 @@ describe("Greeting Contract Integration Tests", () => {
 
     const should_fail = async () => {
       await user.call(coinflipContractAccount, "play", {
-        game_code: nft_account.accountId,
+        game_code: game_id,
         bet_size: utils.format.parseNearAmount("1"),
         odds: 128,
         _bet_type: "whatever",